@@ -15,9 +15,10 @@ use pallet_octopus_appchain::sr25519::AuthorityId as OctopusId;
 use myriad_runtime::{
 	currency::UNITS as MYRIA,
 	opaque::{Block, SessionKeys},
+	pallet_tipping::{AssetKind, AssetMetadata},
 	AccountId, BabeConfig, Balance, BalancesConfig, CouncilConfig, DemocracyConfig, GenesisConfig,
 	OctopusAppchainConfig, OctopusBridgeConfig, OctopusLposConfig, OctopusUpwardMessagesConfig,
-	SessionConfig, Signature, SudoConfig, SystemConfig, TechnicalCommitteeConfig,
+	SessionConfig, Signature, SudoConfig, SystemConfig, TechnicalCommitteeConfig, TippingConfig,
 	BABE_GENESIS_EPOCH_CONFIG, WASM_BINARY,
 };
 
@@ -300,6 +301,10 @@ fn genesis(
 			members: vec![],
 			phantom: Default::default(),
 		},
+		treasury: Default::default(),
 		sudo: SudoConfig { key: Some(root_key) },
+		tipping: TippingConfig {
+			assets: vec![(b"native".to_vec(), AssetMetadata::new(AssetKind::Native, 0, 18))],
+		},
 	}
 }
@@ -33,6 +33,8 @@ use pallet_transaction_payment_rpc::{
 	TransactionPayment, TransactionPaymentApiServer, TransactionPaymentRuntimeApi,
 };
 
+use pallet_tipping_rpc::{Tipping, TippingApiServer, TippingRuntimeApi};
+
 use myriad_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Hash, Index};
 
 use jsonrpsee::RpcModule;
@@ -109,6 +111,7 @@ where
 	C::Api: TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
 	C::Api: MmrRuntimeApi<Block, <Block as BlockT>::Hash>,
+	C::Api: TippingRuntimeApi<Block, AccountId, Balance>,
 	P: TransactionPool + 'static,
 	SC: SelectChain<Block> + 'static,
 	B: Backend<Block> + Send + Sync + 'static,
@@ -170,6 +173,7 @@ where
 		.into_rpc(),
 	)?;
 	io.merge(Mmr::new(client.clone()).into_rpc())?;
+	io.merge(Tipping::new(client.clone()).into_rpc())?;
 	io.merge(
 		SyncState::new(chain_spec, client.clone(), shared_authority_set, shared_epoch_changes)?
 			.into_rpc(),
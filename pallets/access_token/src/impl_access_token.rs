@@ -6,7 +6,7 @@ use sp_std::vec::Vec;
 impl<T: Config + pallet_timestamp::Config> AccessTokenInterface<T> for Pallet<T> {
 	type Error = Error<T>;
 	type AccessToken = AccessTokenOf<T>;
-	type Scopes = Scopes<TimelineId>;
+	type Scopes = ScopesOf<T>;
 
 	fn create(
 		owner: &T::AccountId,
@@ -24,12 +24,14 @@ impl<T: Config + pallet_timestamp::Config> AccessTokenInterface<T> for Pallet<T>
 		let updated_count = count.checked_add(1).ok_or(Error::<T>::Overflow)?;
 		let updated_index = index.checked_add(1).ok_or(Error::<T>::Overflow)?;
 
+		let mut access_token_list = AccessTokenByOwner::<T>::get(owner).unwrap_or_default();
+		access_token_list
+			.try_push(access_token.clone())
+			.map_err(|_| Error::<T>::TooManyAccessTokens)?;
+
 		AccessTokenCount::<T>::set(updated_count);
 		AccessTokenIndex::<T>::set(updated_index);
 		AccessTokenByHash::<T>::insert(hash, access_token.clone());
-
-		let mut access_token_list = AccessTokenByOwner::<T>::get(owner).unwrap_or_default();
-		access_token_list.push(access_token.clone());
 		AccessTokenByOwner::<T>::insert(owner, &access_token_list);
 
 		Ok(access_token)
@@ -57,11 +59,10 @@ impl<T: Config + pallet_timestamp::Config> AccessTokenInterface<T> for Pallet<T>
 	}
 
 	fn revoke_all(owner: &T::AccountId) -> Result<Vec<Self::AccessToken>, Self::Error> {
-		let mut access_token_list =
-			AccessTokenByOwner::<T>::get(owner).ok_or(Error::<T>::NotExists)?;
+		let access_token_list = AccessTokenByOwner::<T>::get(owner).ok_or(Error::<T>::NotExists)?;
 
 		// Get access_token from storage
-		for access_token in &access_token_list {
+		for access_token in access_token_list.iter() {
 			AccessTokenByHash::<T>::take(access_token.hash);
 
 			let count = Self::access_token_count();
@@ -69,8 +70,8 @@ impl<T: Config + pallet_timestamp::Config> AccessTokenInterface<T> for Pallet<T>
 			AccessTokenCount::<T>::set(updated_count);
 		}
 
-		access_token_list = AccessTokenByOwner::<T>::take(owner).unwrap();
-		Ok(access_token_list)
+		AccessTokenByOwner::<T>::remove(owner);
+		Ok(access_token_list.into_inner())
 	}
 
 	fn revoke_all_by_scopes(
@@ -78,10 +79,10 @@ impl<T: Config + pallet_timestamp::Config> AccessTokenInterface<T> for Pallet<T>
 		scope: &Self::Scopes,
 	) -> Result<Vec<Self::AccessToken>, Self::Error> {
 		let mut access_token_list =
-			AccessTokenByOwner::<T>::get(owner).ok_or(Error::<T>::NotExists)?;
+			AccessTokenByOwner::<T>::get(owner).ok_or(Error::<T>::NotExists)?.into_inner();
 		access_token_list.retain(|x| x.scope == scope.clone());
 
-		for access_token in &access_token_list {
+		for access_token in access_token_list.iter() {
 			AccessTokenByHash::<T>::take(access_token.hash);
 
 			let count = Self::access_token_count();
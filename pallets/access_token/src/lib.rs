@@ -13,6 +13,7 @@ pub use scale_info::TypeInfo;
 pub mod functions;
 pub mod impl_access_token;
 pub mod interface;
+pub mod migrations;
 pub mod types;
 pub mod weights;
 
@@ -29,7 +30,7 @@ pub use sp_std::{fmt::Debug, prelude::*};
 use frame_support::traits::StorageVersion;
 
 /// The current storage version.
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -43,11 +44,22 @@ pub mod pallet {
 	pub trait Config: frame_system::Config + pallet_timestamp::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type WeightInfo: WeightInfo;
+
+		/// The maximum length of a timeline id in a scope.
+		#[pallet::constant]
+		type MaxTimelineIdLength: Get<u32>;
+
+		/// The maximum number of timelines in a scope.
+		#[pallet::constant]
+		type MaxTimelinesPerScope: Get<u32>;
+
+		/// The maximum number of access tokens an account can hold.
+		#[pallet::constant]
+		type MaxAccessTokensPerOwner: Get<u32>;
 	}
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
-	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
@@ -66,7 +78,7 @@ pub mod pallet {
 	#[pallet::storage]
 	#[pallet::getter(fn all_access_tokens_by_owner)]
 	pub(super) type AccessTokenByOwner<T: Config> =
-		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, Vec<AccessTokenOf<T>>>;
+		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, AccessTokensOf<T>>;
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -87,10 +99,15 @@ pub mod pallet {
 		Overflow,
 		Underflow,
 		BadSignature,
+		TooManyAccessTokens,
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			migrations::migrate::<T>()
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -99,7 +116,7 @@ pub mod pallet {
 		pub fn create(
 			origin: OriginFor<T>,
 			hash: HashOf<T>,
-			scopes: Scopes<TimelineId>,
+			scopes: ScopesOf<T>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
@@ -144,7 +161,7 @@ pub mod pallet {
 		#[pallet::weight(<T as pallet::Config>::WeightInfo::unregister())]
 		pub fn revoke_all_by_scopes(
 			origin: OriginFor<T>,
-			scopes: Scopes<TimelineId>,
+			scopes: ScopesOf<T>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
@@ -0,0 +1,131 @@
+use crate::{
+	AccessToken, AccessTokenByHash, AccessTokenByOwner, AccessTokenCount, AccessTokenOf,
+	AccountIdOf, Config, HashOf, MomentOf, Pallet, Scopes, ScopesOf, TimelineId, TimelineIdOf,
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Get, StorageVersion},
+	weights::Weight,
+};
+use sp_std::vec::Vec;
+
+pub fn migrate<T: Config>() -> Weight {
+	let mut weight: Weight = Weight::zero();
+	let mut version = StorageVersion::get::<Pallet<T>>();
+
+	if version < 1 {
+		weight = weight.saturating_add(versions::v1::migrate::<T>());
+		version = StorageVersion::new(1);
+	}
+
+	version.put::<Pallet<T>>();
+	weight
+}
+
+mod versions {
+	use super::*;
+
+	pub mod v1 {
+		use super::*;
+
+		#[allow(dead_code)]
+		#[derive(Encode, Decode, Clone)]
+		pub struct OldAccessToken<AccountId, Hash, Moment> {
+			owner: AccountId,
+			hash: Hash,
+			scope: Scopes<Vec<TimelineId>>,
+			created_at: Moment,
+			updated_at: Moment,
+		}
+
+		type OldAccessTokenOf<T> = OldAccessToken<AccountIdOf<T>, HashOf<T>, MomentOf<T>>;
+
+		/// Bounds the scope of the access token, `None` when it doesn't fit the limits.
+		fn bounded_access_token<T: Config>(old: OldAccessTokenOf<T>) -> Option<AccessTokenOf<T>> {
+			let scope: ScopesOf<T> = match old.scope {
+				Scopes::Login => Scopes::Login,
+				Scopes::Timeline(timeline_ids) => {
+					let timeline_ids = timeline_ids
+						.into_iter()
+						.map(|timeline_id| timeline_id.try_into().ok())
+						.collect::<Option<Vec<TimelineIdOf<T>>>>()?;
+
+					Scopes::Timeline(timeline_ids.try_into().ok()?)
+				},
+			};
+
+			Some(AccessToken::new(old.owner, old.hash, scope, old.created_at))
+		}
+
+		pub fn migrate<T: Config>() -> Weight {
+			let mut weight = T::DbWeight::get().writes(1);
+			let max_access_tokens = T::MaxAccessTokensPerOwner::get() as usize;
+
+			// Only the latest access tokens of an owner are kept, the rest are revoked.
+			AccessTokenByOwner::<T>::translate(|owner, old: Vec<OldAccessTokenOf<T>>| {
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+
+				let mut access_tokens = Vec::new();
+
+				for old_access_token in old {
+					let hash = old_access_token.hash;
+
+					match bounded_access_token::<T>(old_access_token) {
+						Some(access_token) => access_tokens.push(access_token),
+						None => {
+							log::warn!(
+								target: "runtime::access_token",
+								"Revoked access token {:?} of {:?}, its scope is over the bounds",
+								hash,
+								owner,
+							);
+
+							weight = weight.saturating_add(T::DbWeight::get().writes(1));
+							AccessTokenByHash::<T>::remove(hash);
+						},
+					}
+				}
+
+				let revoked = access_tokens.len().saturating_sub(max_access_tokens);
+
+				for access_token in access_tokens.drain(..revoked) {
+					log::warn!(
+						target: "runtime::access_token",
+						"Revoked access token {:?} of {:?}, over {} access tokens per owner",
+						access_token.hash,
+						owner,
+						max_access_tokens,
+					);
+
+					weight = weight.saturating_add(T::DbWeight::get().writes(1));
+					AccessTokenByHash::<T>::remove(access_token.hash);
+				}
+
+				access_tokens.try_into().ok()
+			});
+
+			let mut total_access_tokens: u64 = 0;
+
+			AccessTokenByHash::<T>::translate(|hash, old: OldAccessTokenOf<T>| {
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+
+				let access_token = bounded_access_token::<T>(old);
+
+				match access_token {
+					Some(_) => total_access_tokens = total_access_tokens.saturating_add(1),
+					None => log::warn!(
+						target: "runtime::access_token",
+						"Revoked access token {:?}, its scope is over the bounds",
+						hash,
+					),
+				}
+
+				access_token
+			});
+
+			AccessTokenCount::<T>::set(total_access_tokens);
+
+			weight.saturating_add(T::DbWeight::get().writes(1))
+		}
+	}
+}
@@ -83,6 +83,9 @@ impl pallet_timestamp::Config for Test {
 }
 
 impl pallet_access_token::Config for Test {
+	type MaxAccessTokensPerOwner = ConstU32<5>;
+	type MaxTimelineIdLength = ConstU32<64>;
+	type MaxTimelinesPerScope = ConstU32<10>;
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
 }
@@ -1,4 +1,4 @@
-use crate::{self as pallet_access_token, mock::*, Error, Scopes, TimelineId};
+use crate::{self as pallet_access_token, mock::*, Error, ScopesOf};
 use frame_support::{
 	assert_noop, assert_ok,
 	sp_runtime::traits::{Hash, Keccak256},
@@ -14,19 +14,19 @@ fn create_works() {
 		let access_token = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin,
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone()])
+			Some(vec![access_token.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())),
@@ -46,28 +46,28 @@ pub fn cant_create_when_hash_exist() {
 		let access_token = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_noop!(
 			AccessToken::create(
 				owner_origin,
 				Keccak256::hash("hash".as_bytes()),
-				Scopes::<TimelineId>::default()
+				ScopesOf::<Test>::default()
 			),
 			Error::<Test>::AlreadyExists,
 		);
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone()])
+			Some(vec![access_token.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())),
@@ -78,6 +78,34 @@ pub fn cant_create_when_hash_exist() {
 	})
 }
 
+#[test]
+pub fn cant_create_when_access_tokens_full() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("alice");
+		let owner_origin = RuntimeOrigin::signed(owner);
+
+		for i in 0..5u8 {
+			assert_ok!(AccessToken::create(
+				owner_origin.clone(),
+				Keccak256::hash(&[i]),
+				ScopesOf::<Test>::default()
+			));
+		}
+
+		assert_noop!(
+			AccessToken::create(
+				owner_origin,
+				Keccak256::hash("hash".as_bytes()),
+				ScopesOf::<Test>::default()
+			),
+			Error::<Test>::TooManyAccessTokens,
+		);
+
+		assert_eq!(AccessToken::access_token_count(), 5);
+		assert_eq!(AccessToken::access_token_index(), 5);
+	})
+}
+
 #[test]
 fn revoke_works() {
 	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
@@ -87,19 +115,19 @@ fn revoke_works() {
 		let access_token = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone()])
+			Some(vec![access_token.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())),
@@ -110,7 +138,10 @@ fn revoke_works() {
 
 		assert_ok!(AccessToken::revoke(owner_origin, Keccak256::hash("hash".as_bytes())));
 
-		assert_eq!(AccessToken::all_access_tokens_by_owner(owner), Some(vec![]));
+		assert_eq!(
+			AccessToken::all_access_tokens_by_owner(owner),
+			Some(vec![].try_into().unwrap())
+		);
 		assert_eq!(AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())), None);
 		assert_eq!(AccessToken::access_token_count(), 0);
 		assert_eq!(AccessToken::access_token_index(), 1);
@@ -126,19 +157,19 @@ fn cant_revoke_when_hash_does_not_exist() {
 		let access_token = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone()])
+			Some(vec![access_token.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())),
@@ -154,7 +185,7 @@ fn cant_revoke_when_hash_does_not_exist() {
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone()])
+			Some(vec![access_token.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())),
@@ -174,19 +205,19 @@ fn cant_revoke_when_not_owner() {
 		let access_token = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin,
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone()])
+			Some(vec![access_token.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())),
@@ -205,7 +236,7 @@ fn cant_revoke_when_not_owner() {
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone()])
+			Some(vec![access_token.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())),
@@ -225,19 +256,19 @@ fn revoke_all_works() {
 		let access_token = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone()])
+			Some(vec![access_token.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())),
@@ -249,19 +280,19 @@ fn revoke_all_works() {
 		let access_token_2 = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash2".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin.clone(),
 			Keccak256::hash("hash2".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone(), access_token_2.clone()])
+			Some(vec![access_token.clone(), access_token_2.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash2".as_bytes())),
@@ -289,19 +320,19 @@ fn cant_revoke_all_not_exists() {
 		let access_token = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone()])
+			Some(vec![access_token.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())),
@@ -313,19 +344,19 @@ fn cant_revoke_all_not_exists() {
 		let access_token_2 = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash2".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin.clone(),
 			Keccak256::hash("hash2".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone(), access_token_2.clone()])
+			Some(vec![access_token.clone(), access_token_2.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash2".as_bytes())),
@@ -341,7 +372,7 @@ fn cant_revoke_all_not_exists() {
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone(), access_token_2.clone()])
+			Some(vec![access_token.clone(), access_token_2.clone()].try_into().unwrap())
 		);
 
 		assert_eq!(
@@ -368,19 +399,19 @@ fn revoke_all_by_scopes_works() {
 		let access_token = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone()])
+			Some(vec![access_token.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())),
@@ -392,19 +423,19 @@ fn revoke_all_by_scopes_works() {
 		let access_token_2 = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash2".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin.clone(),
 			Keccak256::hash("hash2".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone(), access_token_2.clone()])
+			Some(vec![access_token.clone(), access_token_2.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash2".as_bytes())),
@@ -413,12 +444,12 @@ fn revoke_all_by_scopes_works() {
 		assert_eq!(AccessToken::access_token_count(), 2);
 		assert_eq!(AccessToken::access_token_index(), 2);
 
-		assert_ok!(AccessToken::revoke_all_by_scopes(
-			owner_origin,
-			Scopes::<TimelineId>::default()
-		));
+		assert_ok!(AccessToken::revoke_all_by_scopes(owner_origin, ScopesOf::<Test>::default()));
 
-		assert_eq!(AccessToken::all_access_tokens_by_owner(owner), Some(vec![]));
+		assert_eq!(
+			AccessToken::all_access_tokens_by_owner(owner),
+			Some(vec![].try_into().unwrap())
+		);
 		assert_eq!(AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())), None);
 		assert_eq!(AccessToken::access_token_by_hash(Keccak256::hash("hash2".as_bytes())), None);
 		assert_eq!(AccessToken::access_token_count(), 0);
@@ -435,19 +466,19 @@ fn cant_revoke_all_by_scopes_not_exists() {
 		let access_token = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin.clone(),
 			Keccak256::hash("hash".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone()])
+			Some(vec![access_token.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash".as_bytes())),
@@ -459,19 +490,19 @@ fn cant_revoke_all_by_scopes_not_exists() {
 		let access_token_2 = pallet_access_token::AccessToken::new(
 			owner.clone(),
 			Keccak256::hash("hash2".as_bytes()),
-			Scopes::<TimelineId>::default(),
+			ScopesOf::<Test>::default(),
 			0,
 		);
 
 		assert_ok!(AccessToken::create(
 			owner_origin.clone(),
 			Keccak256::hash("hash2".as_bytes()),
-			Scopes::<TimelineId>::default()
+			ScopesOf::<Test>::default()
 		));
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone(), access_token_2.clone()])
+			Some(vec![access_token.clone(), access_token_2.clone()].try_into().unwrap())
 		);
 		assert_eq!(
 			AccessToken::access_token_by_hash(Keccak256::hash("hash2".as_bytes())),
@@ -484,13 +515,13 @@ fn cant_revoke_all_by_scopes_not_exists() {
 		let owner_origin_2 = RuntimeOrigin::signed(owner_2);
 
 		assert_noop!(
-			AccessToken::revoke_all_by_scopes(owner_origin_2, Scopes::<TimelineId>::default()),
+			AccessToken::revoke_all_by_scopes(owner_origin_2, ScopesOf::<Test>::default()),
 			Error::<Test>::NotExists,
 		);
 
 		assert_eq!(
 			AccessToken::all_access_tokens_by_owner(owner),
-			Some(vec![access_token.clone(), access_token_2.clone()])
+			Some(vec![access_token.clone(), access_token_2.clone()].try_into().unwrap())
 		);
 
 		assert_eq!(
@@ -6,39 +6,30 @@ use sp_std::vec::Vec;
 
 pub type TimelineId = Vec<u8>;
 
-#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
-pub enum Scopes<TimelineId> {
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum Scopes<Timelines> {
 	Login,
-	Timeline(Vec<TimelineId>),
+	Timeline(Timelines),
 }
-impl Default for Scopes<TimelineId> {
+impl<Timelines> Default for Scopes<Timelines> {
 	fn default() -> Self {
 		Self::Login
 	}
 }
 
-#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo)]
-pub struct AccessToken<AccountId, Hash, TimelineId, Moment>
-where
-	Scopes<TimelineId>: Default,
-{
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct AccessToken<AccountId, Hash, Timelines, Moment> {
 	owner: AccountId,
 	pub hash: Hash,
-	pub scope: Scopes<TimelineId>,
+	pub scope: Scopes<Timelines>,
 	created_at: Moment,
 	updated_at: Moment,
 }
-impl<AccountId, Hash, TimelineId, Moment: Copy> AccessToken<AccountId, Hash, TimelineId, Moment>
+impl<AccountId, Hash, Timelines, Moment: Copy> AccessToken<AccountId, Hash, Timelines, Moment>
 where
-	Scopes<TimelineId>: Default,
 	AccountId: PartialEq,
 {
-	pub fn new(
-		owner: AccountId,
-		hash: Hash,
-		scope: Scopes<TimelineId>,
-		created_at: Moment,
-	) -> Self {
+	pub fn new(owner: AccountId, hash: Hash, scope: Scopes<Timelines>, created_at: Moment) -> Self {
 		Self { owner, hash, scope, created_at, updated_at: created_at }
 	}
 
@@ -54,4 +45,8 @@ where
 pub type HashOf<T> = <T as frame_system::Config>::Hash;
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 pub type MomentOf<T> = <T as pallet_timestamp::Config>::Moment;
-pub type AccessTokenOf<T> = AccessToken<AccountIdOf<T>, HashOf<T>, TimelineId, MomentOf<T>>;
+pub type TimelineIdOf<T> = BoundedVec<u8, <T as Config>::MaxTimelineIdLength>;
+pub type TimelinesOf<T> = BoundedVec<TimelineIdOf<T>, <T as Config>::MaxTimelinesPerScope>;
+pub type ScopesOf<T> = Scopes<TimelinesOf<T>>;
+pub type AccessTokenOf<T> = AccessToken<AccountIdOf<T>, HashOf<T>, TimelinesOf<T>, MomentOf<T>>;
+pub type AccessTokensOf<T> = BoundedVec<AccessTokenOf<T>, <T as Config>::MaxAccessTokensPerOwner>;
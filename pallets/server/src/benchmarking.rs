@@ -5,10 +5,11 @@ use super::*;
 #[allow(unused)]
 use crate::{Action, ActionType, Pallet as Server, ServerInterface};
 
+use codec::Encode;
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
 use frame_support::{
-	sp_runtime::SaturatedConversion,
-	traits::{Currency, Get, OnInitialize},
+	sp_runtime::{FixedPointNumber, FixedU128, RuntimeAppPublic, SaturatedConversion},
+	traits::{Currency, EnsureOrigin, Get, OnInitialize},
 };
 use frame_system::{Pallet as System, RawOrigin};
 use sp_std::vec;
@@ -17,7 +18,7 @@ const SEED: u32 = 0;
 
 benchmarks! {
 	register {
-		let s in 1 .. 100;
+		let s in 1 .. T::MaxApiUrlLength::get();
 		let caller: T::AccountId = whitelisted_caller();
 
 		// Default balance
@@ -26,11 +27,12 @@ benchmarks! {
 
 		// Caller initial balance
 		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
-	}: _(RawOrigin::Signed(caller), vec![s as u8], Some(stake_amount))
+		let api_url: ApiUrlOf<T> = vec![b'a'; s as usize].try_into().unwrap();
+	}: _(RawOrigin::Signed(caller), api_url, Some(stake_amount))
 
 	update_server {
 		let new_owner = account("new_owner", 0, SEED);
-		let new_api_url = "https://api.testnet.myriad.social".as_bytes().to_vec();
+		let new_api_url: ApiUrlOf<T> = "https://api.testnet.myriad.social".as_bytes().to_vec().try_into().unwrap();
 		let new_stake_amount = 10_000_000_000_000_000_000u128.saturated_into(); // 10 MYRIA
 		let action_types = vec![ActionType::StakeAmount(new_stake_amount), ActionType::UpdateApiUrl(new_api_url), ActionType::TransferOwner(new_owner)];
 
@@ -45,11 +47,64 @@ benchmarks! {
 		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
 
 		let server_id = 0u64;
-		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
 
 		let _ = Server::<T>::register(caller_origin.clone(), server_api_url, None);
 	}: _(RawOrigin::Signed(caller), server_id, action_types[s as usize].clone())
 
+	update_metadata {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+
+		// Default balance
+		let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		let server_id = 0u64;
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
+
+		let _ = Server::<T>::register(caller_origin, server_api_url, None);
+
+		// Longest metadata
+		let field: MetadataFieldOf<T> = vec![b'a'; T::MaxMetadataFieldLength::get() as usize].try_into().unwrap();
+		let description = vec![b'a'; T::MaxDescriptionLength::get() as usize].try_into().unwrap();
+		let web_url = vec![b'a'; T::MaxApiUrlLength::get() as usize].try_into().unwrap();
+		let metadata = ServerMetadata::new(
+			field.clone(),
+			description,
+			web_url,
+			field.clone(),
+			field,
+			Some(Default::default())
+		);
+	}: update_server(RawOrigin::Signed(caller), server_id, ActionType::UpdateMetadata(Some(metadata)))
+
+	update_heartbeat_key {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+
+		// Default balance
+		let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		let server_id = 0u64;
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
+
+		let _ = Server::<T>::register(caller_origin.clone(), server_api_url, None);
+
+		// Replacing a key also moves the scheduled liveness check
+		let old_key = T::HeartbeatKey::generate_pair(None);
+		let new_key = T::HeartbeatKey::generate_pair(None);
+
+		let _ = Server::<T>::update_server(caller_origin, server_id, ActionType::UpdateHeartbeatKey(Some(old_key)));
+
+		System::<T>::set_block_number(System::<T>::block_number() + T::HeartbeatInterval::get());
+	}: update_server(RawOrigin::Signed(caller), server_id, ActionType::UpdateHeartbeatKey(Some(new_key)))
+
 	unregister {
 		let caller: T::AccountId = whitelisted_caller();
 		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
@@ -61,7 +116,7 @@ benchmarks! {
 		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
 
 		let server_id = 0u64;
-		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
 
 		let _ = Server::<T>::register(caller_origin.clone(), server_api_url, None);
 	}: _(RawOrigin::Signed(caller), server_id)
@@ -77,13 +132,257 @@ benchmarks! {
 		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
 
 		let server_id = 0u64;
-		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
 
 		let _ = Server::<T>::register(caller_origin.clone(), server_api_url, None);
 		let _ = Server::<T>::unregister(caller_origin, server_id);
 	}: _(RawOrigin::Signed(caller), server_id)
 
+	delegate {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let delegator: T::AccountId = account("delegator", 0, SEED);
+
+		// Default balance
+		let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+
+		// Caller and delegator initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+		let _ = <T as Config>::Currency::deposit_creating(&delegator, balance);
+
+		let server_id = 0u64;
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
+
+		let _ = Server::<T>::register(caller_origin, server_api_url, None);
+	}: _(RawOrigin::Signed(delegator), server_id, T::MinimumDelegationAmount::get())
+
+	undelegate {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let delegator: T::AccountId = account("delegator", 0, SEED);
+		let delegator_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(delegator.clone()));
+
+		// Default balance
+		let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+
+		// Caller and delegator initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+		let _ = <T as Config>::Currency::deposit_creating(&delegator, balance);
+
+		let server_id = 0u64;
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
+		let amount = T::MinimumDelegationAmount::get();
+
+		let _ = Server::<T>::register(caller_origin, server_api_url, None);
+		let _ = Server::<T>::delegate(delegator_origin.clone(), server_id, amount + amount);
+
+		// Unbonding moves to a later block
+		let _ = Server::<T>::undelegate(delegator_origin, server_id, amount);
+		System::<T>::set_block_number(System::<T>::block_number() + 1u32.into());
+	}: _(RawOrigin::Signed(delegator), server_id, amount)
+
+	on_initialize_unbonding {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let delegator: T::AccountId = account("delegator", 0, SEED);
+		let delegator_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(delegator.clone()));
+
+		// Default balance
+		let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+
+		// Caller and delegator initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+		let _ = <T as Config>::Currency::deposit_creating(&delegator, balance);
+
+		let server_id = 0u64;
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
+		let amount = T::MinimumDelegationAmount::get();
+
+		let _ = Server::<T>::register(caller_origin, server_api_url, None);
+		let _ = Server::<T>::delegate(delegator_origin.clone(), server_id, amount);
+
+		// Current block
+		let current_block = System::<T>::block_number();
+
+		let _ = Server::<T>::undelegate(delegator_origin, server_id, amount);
+
+		let scheduled_block_number = current_block + T::ScheduledBlockTime::get();
+
+		// Set blocknumber
+		System::<T>::set_block_number(scheduled_block_number);
+	}: {
+		Server::<T>::on_initialize(scheduled_block_number)
+	}
+
+	claim_reward {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+
+		// Default balance
+		let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+
+		// Caller and pot initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+		let _ = <T as Config>::Currency::deposit_creating(&Server::<T>::reward_pot_account_id(), balance);
+
+		let server_id = 0u64;
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
+
+		let _ = Server::<T>::register(caller_origin, server_api_url, None);
+
+		// One unit of reward per unit of stake
+		RewardPerStake::<T>::put(FixedU128::saturating_from_integer(1u32));
+	}: _(RawOrigin::Signed(caller), server_id)
+
+	on_initialize_era {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+
+		// Default balance
+		let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
+
+		let _ = Server::<T>::register(caller_origin, server_api_url, None);
+
+		let era_block_number = T::EraLength::get();
+
+		// Set blocknumber
+		System::<T>::set_block_number(era_block_number);
+	}: {
+		Server::<T>::on_initialize(era_block_number)
+	}
+
+	report {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let reporter: T::AccountId = account("reporter", 0, SEED);
+
+		// Default balance
+		let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+
+		// Caller and reporter initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+		let _ = <T as Config>::Currency::deposit_creating(&reporter, balance);
+
+		let server_id = 0u64;
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
+
+		let _ = Server::<T>::register(caller_origin, server_api_url, None);
+	}: _(RawOrigin::Signed(reporter), server_id, Default::default())
+
+	slash {
+		let d in 0 .. T::MaxDelegatorsPerServer::get();
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let reporter: T::AccountId = account("reporter", 0, SEED);
+		let reporter_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(reporter.clone()));
+
+		// Default balance
+		let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+
+		// Caller and reporter initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+		let _ = <T as Config>::Currency::deposit_creating(&reporter, balance);
+
+		let server_id = 0u64;
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
+
+		let _ = Server::<T>::register(caller_origin, server_api_url, None);
+
+		// Delegators to slash
+		for i in 0 .. d {
+			let delegator: T::AccountId = account("delegator", i, SEED);
+			let delegator_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(delegator.clone()));
+
+			let _ = <T as Config>::Currency::deposit_creating(&delegator, balance);
+			let _ = Server::<T>::delegate(delegator_origin, server_id, T::MinimumDelegationAmount::get());
+		}
+
+		let _ = Server::<T>::report(reporter_origin, server_id, Default::default());
+
+		let origin = T::SlashOrigin::successful_origin();
+	}: _<T::RuntimeOrigin>(origin, 0, T::MaxSlashFraction::get())
+
+	reject_report {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let reporter: T::AccountId = account("reporter", 0, SEED);
+		let reporter_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(reporter.clone()));
+
+		// Default balance
+		let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+
+		// Caller and reporter initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+		let _ = <T as Config>::Currency::deposit_creating(&reporter, balance);
+
+		let server_id = 0u64;
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
+
+		let _ = Server::<T>::register(caller_origin, server_api_url, None);
+		let _ = Server::<T>::report(reporter_origin, server_id, Default::default());
+
+		let origin = T::SlashOrigin::successful_origin();
+	}: _<T::RuntimeOrigin>(origin, 0)
+
+	heartbeat {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+
+		// Default balance
+		let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		let server_id = 0u64;
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
+		let key = T::HeartbeatKey::generate_pair(None);
+
+		let _ = Server::<T>::register(caller_origin.clone(), server_api_url, None);
+		let _ = Server::<T>::update_server(caller_origin, server_id, ActionType::UpdateHeartbeatKey(Some(key.clone())));
+
+		// Flag the server as inactive
+		let scheduled_block_number = System::<T>::block_number() + T::InactivityPeriod::get();
+
+		System::<T>::set_block_number(scheduled_block_number);
+		Server::<T>::on_initialize(scheduled_block_number);
+
+		let heartbeat = Heartbeat::new(server_id, scheduled_block_number);
+		let signature = key.sign(&heartbeat.encode()).ok_or("couldn't make signature")?;
+	}: _(RawOrigin::None, heartbeat, signature)
+
+	on_initialize_liveness {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+
+		// Default balance
+		let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		let server_id = 0u64;
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
+		let key = T::HeartbeatKey::generate_pair(None);
+
+		let _ = Server::<T>::register(caller_origin.clone(), server_api_url, None);
+		let _ = Server::<T>::update_server(caller_origin, server_id, ActionType::UpdateHeartbeatKey(Some(key)));
+
+		let scheduled_block_number = System::<T>::block_number() + T::InactivityPeriod::get();
+
+		// Set blocknumber
+		System::<T>::set_block_number(scheduled_block_number);
+	}: {
+		Server::<T>::on_initialize(scheduled_block_number)
+	}
+
 	on_initialize_server {
+		let d in 0 .. T::MaxDelegatorsPerServer::get();
 		let caller: T::AccountId = whitelisted_caller();
 		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
 
@@ -94,10 +393,19 @@ benchmarks! {
 		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
 
 		let server_id = 0u64;
-		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let server_api_url = "https://api.dev.myriad.social".as_bytes().to_vec().try_into().unwrap();
 
 		let _ = Server::<T>::register(caller_origin.clone(), server_api_url, None);
 
+		// Delegators to repay
+		for i in 0 .. d {
+			let delegator: T::AccountId = account("delegator", i, SEED);
+			let delegator_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(delegator.clone()));
+
+			let _ = <T as Config>::Currency::deposit_creating(&delegator, balance);
+			let _ = Server::<T>::delegate(delegator_origin, server_id, T::MinimumDelegationAmount::get());
+		}
+
 		// Current block
 		let current_block = System::<T>::block_number();
 
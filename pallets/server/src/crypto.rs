@@ -0,0 +1,24 @@
+use sp_runtime::KeyTypeId;
+
+/// The key type server operators sign their heartbeats with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"srvr");
+
+pub mod sr25519 {
+	mod app_sr25519 {
+		use crate::crypto::KEY_TYPE;
+		use sp_application_crypto::{app_crypto, sr25519};
+
+		app_crypto!(sr25519, KEY_TYPE);
+	}
+
+	sp_application_crypto::with_pair! {
+		/// A heartbeat key pair using sr25519.
+		pub type HeartbeatPair = app_sr25519::Pair;
+	}
+
+	/// A heartbeat signature using sr25519.
+	pub type HeartbeatSignature = app_sr25519::Signature;
+
+	/// A heartbeat key using sr25519.
+	pub type HeartbeatKey = app_sr25519::Public;
+}
@@ -1,10 +1,12 @@
 use crate::*;
+use codec::Encode;
 use frame_support::{
 	sp_runtime::{
-		traits::{AccountIdConversion, Zero},
-		DispatchError,
+		traits::{AccountIdConversion, SaturatedConversion, Saturating, Zero},
+		DispatchError, FixedPointNumber, FixedU128, PerThing, Permill,
 	},
-	traits::{Currency, ExistenceRequirement},
+	storage::with_storage_layer,
+	traits::{Currency, ExistenceRequirement, Get, ReservableCurrency},
 	weights::Weight,
 	PalletId,
 };
@@ -17,7 +19,113 @@ impl<T: Config> Pallet<T> {
 		PALLET_ID.into_sub_account_truncating(server_id)
 	}
 
-	pub fn do_api_url_exist(api_url: &[u8]) -> Result<(), Error<T>> {
+	/// The account the era rewards are paid into and claimed from.
+	pub fn reward_pot_account_id() -> T::AccountId {
+		PALLET_ID.into_account_truncating()
+	}
+
+	/// The owner stake and the bonded delegation of the account on the server.
+	pub fn stake_of(server_id: ServerId, who: &T::AccountId) -> BalanceOf<T> {
+		let stake = ServerById::<T>::get(server_id)
+			.filter(|server| server.get_owner() == who)
+			.map(|server| *server.get_stake_amount())
+			.unwrap_or_else(Zero::zero);
+		let bonded = Self::delegation_by_server(server_id, who)
+			.map(|delegation| delegation.get_bonded())
+			.unwrap_or_else(Zero::zero);
+
+		stake.saturating_add(bonded)
+	}
+
+	/// The rewards paid out to the stake over every era so far.
+	fn accumulated_reward(stake: BalanceOf<T>) -> BalanceOf<T> {
+		Self::reward_per_stake()
+			.saturating_mul_int(stake.saturated_into::<u128>())
+			.saturated_into()
+	}
+
+	/// Settles the rewards of the account on the server, then updates its stake to the one in
+	/// storage. Called after every change of an owner stake or a bonded delegation.
+	pub fn do_update_reward_stake(server_id: ServerId, who: &T::AccountId) {
+		let mut ledger = Self::reward_ledger(server_id, who).unwrap_or_default();
+		let old_stake = ledger.get_stake();
+		let stake = Self::stake_of(server_id, who);
+
+		ledger.settle(Self::accumulated_reward(old_stake));
+		ledger.set_stake(stake, Self::accumulated_reward(stake));
+
+		TotalStake::<T>::mutate(|total| {
+			*total = total.saturating_sub(old_stake).saturating_add(stake);
+		});
+
+		if ledger.is_empty() {
+			RewardLedgers::<T>::remove(server_id, who);
+		} else {
+			RewardLedgers::<T>::insert(server_id, who, ledger);
+		}
+	}
+
+	pub fn do_claim_reward(
+		server_id: ServerId,
+		who: &T::AccountId,
+	) -> Result<BalanceOf<T>, Error<T>> {
+		let mut ledger = Self::reward_ledger(server_id, who).ok_or(Error::<T>::NotExists)?;
+
+		ledger.settle(Self::accumulated_reward(ledger.get_stake()));
+
+		let amount = ledger.take_unclaimed();
+
+		if amount.is_zero() {
+			return Err(Error::<T>::NotExists)
+		}
+
+		let sender = Self::reward_pot_account_id();
+
+		Self::do_transfer(&sender, who, amount, ExistenceRequirement::AllowDeath)?;
+
+		if ledger.is_empty() {
+			RewardLedgers::<T>::remove(server_id, who);
+		} else {
+			RewardLedgers::<T>::insert(server_id, who, ledger);
+		}
+
+		Ok(amount)
+	}
+
+	/// Funds the pot and splits its reward over the total stake, keeping `MaxPayoutHistory`
+	/// eras of rewards. Nothing is funded while nobody stakes.
+	pub fn do_end_era() -> Weight {
+		let era = Self::current_era();
+		let total_stake = Self::total_stake();
+		let reward = if total_stake.is_zero() {
+			Zero::zero()
+		} else {
+			T::RewardFunding::fund(&Self::reward_pot_account_id())
+		};
+
+		if !reward.is_zero() {
+			let reward_per_stake = FixedU128::saturating_from_rational(
+				reward.saturated_into::<u128>(),
+				total_stake.saturated_into::<u128>(),
+			);
+
+			RewardPerStake::<T>::mutate(|value| *value = value.saturating_add(reward_per_stake));
+		}
+
+		ErasReward::<T>::insert(era, EraReward::new(reward, total_stake));
+
+		if let Some(expired_era) = era.checked_sub(T::MaxPayoutHistory::get()) {
+			ErasReward::<T>::remove(expired_era);
+		}
+
+		CurrentEra::<T>::put(era.saturating_add(1));
+
+		Self::deposit_event(Event::EraPaid { era, reward, total_stake });
+
+		T::WeightInfo::on_initialize_era()
+	}
+
+	pub fn do_api_url_exist(api_url: &ApiUrlOf<T>) -> Result<(), Error<T>> {
 		if Self::server_by_api_url(api_url).is_some() {
 			return Err(Error::<T>::AlreadyExists)
 		}
@@ -44,6 +152,316 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// The base deposit plus the per byte deposit for the encoded metadata.
+	pub fn metadata_deposit_of(metadata: &ServerMetadataOf<T>) -> BalanceOf<T> {
+		let bytes = BalanceOf::<T>::from(metadata.encoded_size() as u32);
+
+		T::MetadataDepositPerByte::get()
+			.saturating_mul(bytes)
+			.saturating_add(T::MetadataDepositBase::get())
+	}
+
+	/// Reserves the metadata deposit from the owner, returning the previous deposit to whoever
+	/// reserved it, e.g. the owner before an ownership transfer.
+	pub fn do_set_metadata(
+		server_id: ServerId,
+		owner: &T::AccountId,
+		metadata: &ServerMetadataOf<T>,
+	) -> Result<(), Error<T>> {
+		let deposit = Self::metadata_deposit_of(metadata);
+
+		match Self::metadata_deposit(server_id) {
+			Some((depositor, old_deposit)) if &depositor == owner =>
+				if deposit > old_deposit {
+					CurrencyOf::<T>::reserve(owner, deposit - old_deposit)
+						.map_err(|_| Error::<T>::InsufficientBalance)?;
+				} else {
+					CurrencyOf::<T>::unreserve(owner, old_deposit - deposit);
+				},
+			old => {
+				CurrencyOf::<T>::reserve(owner, deposit)
+					.map_err(|_| Error::<T>::InsufficientBalance)?;
+
+				if let Some((depositor, old_deposit)) = old {
+					CurrencyOf::<T>::unreserve(&depositor, old_deposit);
+				}
+			},
+		}
+
+		MetadataByServer::<T>::insert(server_id, metadata);
+		MetadataDeposit::<T>::insert(server_id, (owner, deposit));
+
+		Self::deposit_event(Event::MetadataSet { server_id, deposit });
+
+		Ok(())
+	}
+
+	pub fn do_clear_metadata(server_id: ServerId) -> Result<(), Error<T>> {
+		let (depositor, deposit) =
+			MetadataDeposit::<T>::take(server_id).ok_or(Error::<T>::NotExists)?;
+
+		CurrencyOf::<T>::unreserve(&depositor, deposit);
+		MetadataByServer::<T>::remove(server_id);
+
+		Self::deposit_event(Event::MetadataCleared { server_id, deposit });
+
+		Ok(())
+	}
+
+	/// Returns every delegation of the server, then the rest of the server account to the owner.
+	/// Nothing is transferred unless everyone can be repaid.
+	pub fn do_repay_stakes(
+		server_id: ServerId,
+		owner: &T::AccountId,
+	) -> Result<(BalanceOf<T>, u32), DispatchError> {
+		with_storage_layer(|| {
+			let sender = Self::server_account_id(server_id);
+			let existence = ExistenceRequirement::AllowDeath;
+			let mut delegator_count = 0u32;
+
+			for (delegator, delegation) in DelegationByServer::<T>::drain_prefix(server_id) {
+				let amount = delegation.total();
+
+				Self::do_transfer(&sender, &delegator, amount, existence)?;
+				Self::do_update_reward_stake(server_id, &delegator);
+				Self::deposit_event(Event::Unbonded(delegator, server_id, amount));
+
+				delegator_count = delegator_count.saturating_add(1);
+			}
+
+			BackingByServer::<T>::remove(server_id);
+
+			let amount = CurrencyOf::<T>::free_balance(&sender);
+
+			Self::do_transfer(&sender, owner, amount, existence)?;
+
+			Ok((amount, delegator_count))
+		})
+	}
+
+	/// Slashes the fraction of the owner stake and of every delegation held by the server account,
+	/// paying `ReporterRewardShare` of it to the reporter and the rest to `SlashDestination`.
+	pub fn do_slash(
+		server_id: ServerId,
+		slash_fraction: Permill,
+		reporter: &T::AccountId,
+	) -> Result<(BalanceOf<T>, BalanceOf<T>, u32), DispatchError> {
+		let server = ServerById::<T>::get(server_id).ok_or(Error::<T>::NotExists)?;
+
+		with_storage_layer(|| {
+			let owner = server.get_owner().clone();
+			let owner_slash = slash_fraction.mul_floor(*server.get_stake_amount());
+			let server = server.decrease_stake_amount(owner_slash);
+
+			let mut amount = owner_slash;
+			let mut backing = Self::backing(server_id);
+			// The call is weighed for `MaxDelegatorsPerServer`, delegations past a lowered bound
+			// are left alone
+			let delegations = DelegationByServer::<T>::iter_prefix(server_id)
+				.take(T::MaxDelegatorsPerServer::get() as usize)
+				.collect::<Vec<_>>();
+			let delegator_count = delegations.len() as u32;
+
+			for (delegator, mut delegation) in delegations.into_iter() {
+				let bonded_slash = slash_fraction.mul_floor(delegation.get_bonded());
+				let unbonding_slash = slash_fraction.mul_floor(delegation.get_unbonding());
+
+				delegation.slash(bonded_slash, unbonding_slash);
+				backing.remove(bonded_slash, delegation.is_empty());
+
+				amount = amount.saturating_add(bonded_slash).saturating_add(unbonding_slash);
+
+				if delegation.is_empty() {
+					DelegationByServer::<T>::remove(server_id, &delegator);
+				} else {
+					DelegationByServer::<T>::insert(server_id, &delegator, delegation);
+				}
+
+				Self::do_update_reward_stake(server_id, &delegator);
+			}
+
+			ServerById::<T>::insert(server_id, &server);
+			ServerByOwner::<T>::insert(&owner, server_id, &server);
+			BackingByServer::<T>::insert(server_id, backing);
+			Self::do_update_reward_stake(server_id, &owner);
+
+			let sender = Self::server_account_id(server_id);
+			let reporter_reward = T::ReporterRewardShare::get().mul_floor(amount);
+			let existence = ExistenceRequirement::AllowDeath;
+
+			Self::do_transfer(&sender, reporter, reporter_reward, existence)?;
+			Self::do_transfer(
+				&sender,
+				&T::SlashDestination::get(),
+				amount - reporter_reward,
+				existence,
+			)?;
+
+			Ok((amount, reporter_reward, delegator_count))
+		})
+	}
+
+	/// A server sends at most one heartbeat every `HeartbeatInterval`.
+	pub fn is_heartbeat_due(server_id: ServerId, now: T::BlockNumber) -> bool {
+		match Self::last_heartbeat(server_id) {
+			Some(last_heartbeat) =>
+				last_heartbeat.saturating_add(T::HeartbeatInterval::get()) <= now,
+			None => true,
+		}
+	}
+
+	/// Moves the liveness check of the server to `InactivityPeriod` after now.
+	pub fn do_schedule_liveness_check(
+		server_id: ServerId,
+		now: T::BlockNumber,
+	) -> Result<T::BlockNumber, Error<T>> {
+		let when = now.saturating_add(T::InactivityPeriod::get());
+		let scheduled = Self::next_liveness_check(server_id);
+
+		if scheduled == Some(when) {
+			return Ok(when)
+		}
+
+		LivenessTasks::<T>::try_append(when, server_id)
+			.map_err(|_| Error::<T>::FailedToSchedule)?;
+
+		if let Some(scheduled) = scheduled {
+			Self::do_cancel_liveness_check(server_id, scheduled);
+		}
+
+		NextLivenessCheck::<T>::insert(server_id, when);
+
+		Ok(when)
+	}
+
+	fn do_cancel_liveness_check(server_id: ServerId, scheduled: T::BlockNumber) {
+		let mut tasks = LivenessTasks::<T>::take(scheduled);
+
+		tasks.retain(|e| *e != server_id);
+
+		if !tasks.is_empty() {
+			LivenessTasks::<T>::insert(scheduled, tasks);
+		}
+	}
+
+	/// Registers the heartbeat key of the server and gives it `InactivityPeriod` to send the first
+	/// heartbeat, `None` removes the key and stops tracking the server liveness.
+	pub fn do_set_heartbeat_key(
+		server_id: ServerId,
+		key: &Option<T::HeartbeatKey>,
+	) -> Result<(), Error<T>> {
+		match key {
+			Some(key) => {
+				if Self::server_by_heartbeat_key(key).filter(|id| *id != server_id).is_some() {
+					return Err(Error::<T>::AlreadyExists)
+				}
+
+				let now = <frame_system::Pallet<T>>::block_number();
+
+				Self::do_schedule_liveness_check(server_id, now)?;
+
+				if let Some(old_key) = HeartbeatKeyByServer::<T>::take(server_id) {
+					ServerByHeartbeatKey::<T>::remove(old_key);
+				}
+
+				HeartbeatKeyByServer::<T>::insert(server_id, key);
+				ServerByHeartbeatKey::<T>::insert(key, server_id);
+			},
+			None => Self::do_clear_heartbeat(server_id),
+		}
+
+		Ok(())
+	}
+
+	pub fn do_clear_heartbeat(server_id: ServerId) {
+		if let Some(key) = HeartbeatKeyByServer::<T>::take(server_id) {
+			ServerByHeartbeatKey::<T>::remove(key);
+		}
+
+		if let Some(scheduled) = NextLivenessCheck::<T>::take(server_id) {
+			Self::do_cancel_liveness_check(server_id, scheduled);
+		}
+
+		LastHeartbeat::<T>::remove(server_id);
+		InactiveServers::<T>::remove(server_id);
+	}
+
+	/// Flags the servers without a heartbeat since their check was scheduled as inactive. They
+	/// aren't checked again until their next heartbeat.
+	pub fn do_check_liveness(when: T::BlockNumber, tasks: Vec<ServerId>) -> Weight {
+		let mut total_weight = Weight::zero();
+
+		for server_id in tasks.into_iter() {
+			// A heartbeat moved the check to a later block.
+			if Self::next_liveness_check(server_id) != Some(when) {
+				continue
+			}
+
+			total_weight += T::WeightInfo::on_initialize_liveness();
+
+			NextLivenessCheck::<T>::remove(server_id);
+			InactiveServers::<T>::insert(server_id, when);
+
+			Self::deposit_event(Event::ServerInactive {
+				server_id,
+				last_heartbeat: Self::last_heartbeat(server_id),
+			});
+		}
+
+		total_weight
+	}
+
+	pub fn do_release_unbondings(
+		when: T::BlockNumber,
+		tasks: Vec<(ServerId, T::AccountId)>,
+	) -> Weight {
+		let mut total_weight = Weight::zero();
+
+		for (server_id, delegator) in tasks.into_iter() {
+			// Delegations of a removed server were repaid in full along with its stake.
+			let mut delegation = match Self::delegation_by_server(server_id, &delegator) {
+				Some(delegation) if delegation.get_unbonded_at() == Some(when) => delegation,
+				_ => continue,
+			};
+
+			let amount = delegation.release();
+			let sender = Self::server_account_id(server_id);
+			let existence = ExistenceRequirement::KeepAlive;
+
+			if Self::do_transfer(&sender, &delegator, amount, existence).is_err() {
+				Self::deposit_event(Event::Scheduled {
+					server_id,
+					when,
+					task: b"Unbonded".to_vec(),
+					status: Status::Failed,
+				});
+
+				continue
+			}
+
+			total_weight += T::WeightInfo::on_initialize_unbonding();
+
+			if delegation.is_empty() {
+				DelegationByServer::<T>::remove(server_id, &delegator);
+				BackingByServer::<T>::mutate(server_id, |backing| {
+					backing.remove(Zero::zero(), true)
+				});
+			} else {
+				DelegationByServer::<T>::insert(server_id, &delegator, delegation);
+			}
+
+			Self::deposit_event(Event::Unbonded(delegator, server_id, amount));
+			Self::deposit_event(Event::Scheduled {
+				server_id,
+				when,
+				task: b"Unbonded".to_vec(),
+				status: Status::Success,
+			});
+		}
+
+		total_weight
+	}
+
 	pub fn do_remove_servers(when: T::BlockNumber, tasks: Vec<ServerId>) -> Weight {
 		let mut total_weight = Weight::zero();
 
@@ -54,27 +472,20 @@ impl<T: Config> Pallet<T> {
 			if let Some(server) = server {
 				let server = server.set_stake_amount(Zero::zero());
 				let count = Self::server_count().saturating_sub(1);
-				let sender = Self::server_account_id(server_id);
 				let receiver = server.get_owner();
-				let amount = CurrencyOf::<T>::free_balance(&sender);
+				let repaid = Self::do_repay_stakes(server_id, receiver);
 
-				let existence = ExistenceRequirement::AllowDeath;
-				let transfer = Self::do_transfer(&sender, receiver, amount, existence);
-
-				if transfer.is_err() {
-					Self::deposit_event(Event::Scheduled {
-						server_id,
-						when,
-						task: b"Unstaked".to_vec(),
-						status: Status::Failed,
-					});
-				} else {
-					total_weight += T::WeightInfo::on_initialize_server();
+				if let Ok((amount, delegator_count)) = repaid {
+					total_weight += T::WeightInfo::on_initialize_server(delegator_count);
 
 					ServerCount::<T>::set(count);
 					ServerById::<T>::remove(server_id);
 					ServerByApiUrl::<T>::remove(server.get_api_url());
+					SigningKeyByServer::<T>::remove(server_id);
+					Self::do_clear_heartbeat(server_id);
+					let _ = Self::do_clear_metadata(server_id);
 					ServerByOwner::<T>::insert(receiver, server_id, &server);
+					Self::do_update_reward_stake(server_id, receiver);
 
 					Self::deposit_event(Event::Unregistered(server_id));
 					Self::deposit_event(Event::Unstaked(receiver.clone(), server_id, amount));
@@ -84,6 +495,13 @@ impl<T: Config> Pallet<T> {
 						task: b"Unstaked".to_vec(),
 						status: Status::Success,
 					});
+				} else {
+					Self::deposit_event(Event::Scheduled {
+						server_id,
+						when,
+						task: b"Unstaked".to_vec(),
+						status: Status::Failed,
+					});
 				}
 			} else {
 				Self::deposit_event(Event::Scheduled {
@@ -0,0 +1,110 @@
+use super::*;
+use frame_support::{
+	sp_runtime::traits::{Saturating, Zero},
+	traits::{ExistenceRequirement, Get},
+};
+
+impl<T: Config> DelegationInterface<T> for Pallet<T> {
+	type Error = Error<T>;
+	type Balance = BalanceOf<T>;
+
+	fn delegate(
+		delegator: &T::AccountId,
+		server_id: u64,
+		amount: Self::Balance,
+	) -> Result<(), Self::Error> {
+		let server = ServerById::<T>::get(server_id).ok_or(Error::<T>::NotExists)?;
+
+		if server.get_unstaked_at().is_some() {
+			return Err(Error::<T>::WaitingToUnstaked)
+		}
+
+		let delegation = Self::delegation_by_server(server_id, delegator);
+		let new_delegator = delegation.is_none();
+		let mut delegation = delegation.unwrap_or_default();
+		let mut backing = Self::backing(server_id);
+
+		if delegation.get_bonded().saturating_add(amount) < T::MinimumDelegationAmount::get() {
+			return Err(Error::<T>::BelowMinimumDelegation)
+		}
+
+		if new_delegator && backing.get_delegator_count() >= T::MaxDelegatorsPerServer::get() {
+			return Err(Error::<T>::TooManyDelegators)
+		}
+
+		let receiver = Self::server_account_id(server_id);
+
+		Self::do_transfer(delegator, &receiver, amount, ExistenceRequirement::KeepAlive)?;
+
+		delegation.bond(amount);
+		backing.add(amount, new_delegator);
+
+		DelegationByServer::<T>::insert(server_id, delegator, delegation);
+		BackingByServer::<T>::insert(server_id, backing);
+
+		Self::do_update_reward_stake(server_id, delegator);
+
+		Ok(())
+	}
+
+	fn undelegate(
+		delegator: &T::AccountId,
+		server_id: u64,
+		amount: Self::Balance,
+	) -> Result<T::BlockNumber, Self::Error> {
+		let mut delegation =
+			Self::delegation_by_server(server_id, delegator).ok_or(Error::<T>::NotExists)?;
+		let bonded = delegation.get_bonded();
+
+		if amount.is_zero() || amount > bonded {
+			return Err(Error::<T>::InsufficientBalance)
+		}
+
+		let remaining = bonded - amount;
+
+		if !remaining.is_zero() && remaining < T::MinimumDelegationAmount::get() {
+			return Err(Error::<T>::BelowMinimumDelegation)
+		}
+
+		let current_block_number = <frame_system::Pallet<T>>::block_number();
+		let scheduled_block_number = current_block_number + T::ScheduledBlockTime::get();
+		let task = (server_id, delegator.clone());
+
+		// The whole unbonding amount is released together, so the task moves to the latest block.
+		if let Some(unbonded_at) = delegation.get_unbonded_at() {
+			if unbonded_at != scheduled_block_number {
+				UnbondingTasks::<T>::try_append(scheduled_block_number, &task)
+					.map_err(|_| Error::<T>::FailedToSchedule)?;
+				UnbondingTasks::<T>::mutate_exists(unbonded_at, |tasks| {
+					if let Some(items) = tasks {
+						items.retain(|e| *e != task);
+
+						if items.is_empty() {
+							*tasks = None;
+						}
+					}
+				});
+			}
+		} else {
+			UnbondingTasks::<T>::try_append(scheduled_block_number, &task)
+				.map_err(|_| Error::<T>::FailedToSchedule)?;
+		}
+
+		delegation.unbond(amount, scheduled_block_number);
+
+		DelegationByServer::<T>::insert(server_id, delegator, delegation);
+		BackingByServer::<T>::mutate(server_id, |backing| backing.remove(amount, false));
+
+		Self::do_update_reward_stake(server_id, delegator);
+
+		Ok(scheduled_block_number)
+	}
+
+	fn total_backing(server_id: u64) -> Self::Balance {
+		let stake_amount = ServerById::<T>::get(server_id)
+			.map(|server| *server.get_stake_amount())
+			.unwrap_or_else(Zero::zero);
+
+		stake_amount.saturating_add(Self::backing(server_id).get_delegated())
+	}
+}
@@ -1,16 +1,16 @@
 use super::*;
 use frame_support::traits::{ExistenceRequirement, Get};
-use sp_std::vec::Vec;
 
 impl<T: Config> ServerInterface<T> for Pallet<T> {
 	type Error = Error<T>;
 	type Server = ServerOf<T>;
 	type Balance = BalanceOf<T>;
 	type ActionType = ActionTypeOf<T>;
+	type ApiUrl = ApiUrlOf<T>;
 
 	fn register(
 		owner: &T::AccountId,
-		api_url: &[u8],
+		api_url: &Self::ApiUrl,
 		stake_amount: Option<Self::Balance>,
 	) -> Result<Self::Server, Self::Error> {
 		Self::do_api_url_exist(api_url)?;
@@ -40,6 +40,8 @@ impl<T: Config> ServerInterface<T> for Pallet<T> {
 		ServerByApiUrl::<T>::insert(api_url, index);
 		ServerByOwner::<T>::insert(owner, index, &server);
 
+		Self::do_update_reward_stake(index, owner);
+
 		Ok(server)
 	}
 
@@ -107,6 +109,28 @@ impl<T: Config> ServerInterface<T> for Pallet<T> {
 						let server = server.clone().decrease_stake_amount(*amount);
 						Ok(server)
 					},
+					ActionType::UpdateSigningKey(signing_key) => {
+						match signing_key {
+							Some(signing_key) =>
+								SigningKeyByServer::<T>::insert(server_id, signing_key),
+							None => SigningKeyByServer::<T>::remove(server_id),
+						}
+
+						Ok(server.clone())
+					},
+					ActionType::UpdateMetadata(metadata) => {
+						match metadata {
+							Some(metadata) => Self::do_set_metadata(server_id, owner, metadata),
+							None => Self::do_clear_metadata(server_id),
+						}?;
+
+						Ok(server.clone())
+					},
+					ActionType::UpdateHeartbeatKey(key) => {
+						Self::do_set_heartbeat_key(server_id, key)?;
+
+						Ok(server.clone())
+					},
 				}?;
 
 				ServerByOwner::<T>::insert(updated_server.get_owner(), server_id, &updated_server);
@@ -118,6 +142,16 @@ impl<T: Config> ServerInterface<T> for Pallet<T> {
 			None => Err(Error::<T>::NotExists),
 		})?;
 
+		match action {
+			ActionType::TransferOwner(new_owner) => {
+				Self::do_update_reward_stake(server_id, owner);
+				Self::do_update_reward_stake(server_id, new_owner);
+			},
+			ActionType::StakeAmount(_) | ActionType::UnstakeAmount(_) =>
+				Self::do_update_reward_stake(server_id, owner),
+			_ => {},
+		}
+
 		Ok(())
 	}
 
@@ -130,15 +164,8 @@ impl<T: Config> ServerInterface<T> for Pallet<T> {
 		let current_block_number = <frame_system::Pallet<T>>::block_number();
 		let scheduled_block_number = current_block_number + T::ScheduledBlockTime::get();
 
-		Tasks::<T>::mutate(scheduled_block_number, |tasks: &mut Vec<ServerId>| {
-			if tasks.len() as u32 >= T::MaxScheduledPerBlock::get() {
-				return Err(Error::<T>::FailedToSchedule)
-			}
-
-			tasks.push(server_id);
-
-			Ok(())
-		})?;
+		Tasks::<T>::try_append(scheduled_block_number, server_id)
+			.map_err(|_| Error::<T>::FailedToSchedule)?;
 
 		let server = server.set_unstaked_at(Some(scheduled_block_number));
 
@@ -165,13 +192,9 @@ impl<T: Config> ServerInterface<T> for Pallet<T> {
 
 		let unstaked_at = unstaked_at.unwrap();
 
-		let mut tasks = Vec::new();
+		let mut tasks = Tasks::<T>::take(unstaked_at);
 
-		for e in Tasks::<T>::take(unstaked_at).iter() {
-			if *e != server_id {
-				tasks.push(*e);
-			}
-		}
+		tasks.retain(|e| *e != server_id);
 
 		let server = server.set_unstaked_at(None);
 
@@ -196,4 +219,8 @@ where
 	fn get_by_id(id: u64) -> Option<ServerOf<T>> {
 		ServerById::<T>::get(id)
 	}
+
+	fn get_signing_key(server_id: u64) -> Option<T::AccountId> {
+		SigningKeyByServer::<T>::get(server_id)
+	}
 }
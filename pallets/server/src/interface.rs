@@ -4,6 +4,7 @@ pub trait ServerInfo<T: frame_system::Config> {
 	fn get_id(&self) -> u64;
 	fn get_owner(&self) -> &T::AccountId;
 	fn get_api_url(&self) -> &Vec<u8>;
+	fn get_unstaked_at(&self) -> Option<T::BlockNumber>;
 }
 
 pub trait ServerProvider<T: frame_system::Config> {
@@ -11,6 +12,8 @@ pub trait ServerProvider<T: frame_system::Config> {
 	type Server: ServerInfo<T> + sp_std::fmt::Debug;
 
 	fn get_by_id(server_id: u64) -> Option<Self::Server>;
+	/// The key the server signs off-chain attestations with.
+	fn get_signing_key(server_id: u64) -> Option<T::AccountId>;
 }
 
 pub trait ServerInterface<T: frame_system::Config> {
@@ -18,10 +21,11 @@ pub trait ServerInterface<T: frame_system::Config> {
 	type Server;
 	type Balance: Copy;
 	type ActionType;
+	type ApiUrl;
 
 	fn register(
 		owner: &T::AccountId,
-		api_url: &[u8],
+		api_url: &Self::ApiUrl,
 		stake_amount: Option<Self::Balance>,
 	) -> Result<Self::Server, Self::Error>;
 
@@ -38,3 +42,35 @@ pub trait ServerInterface<T: frame_system::Config> {
 		owner: &T::AccountId,
 	) -> Result<T::BlockNumber, Self::Error>;
 }
+
+pub trait DelegationInterface<T: frame_system::Config> {
+	type Error;
+	type Balance;
+
+	fn delegate(
+		delegator: &T::AccountId,
+		server_id: u64,
+		amount: Self::Balance,
+	) -> Result<(), Self::Error>;
+
+	fn undelegate(
+		delegator: &T::AccountId,
+		server_id: u64,
+		amount: Self::Balance,
+	) -> Result<T::BlockNumber, Self::Error>;
+
+	/// The owner stake plus the stake bonded by delegators.
+	fn total_backing(server_id: u64) -> Self::Balance;
+}
+
+/// Funds the era reward pot, e.g. from a share of tipping fees.
+pub trait RewardFunding<AccountId, Balance> {
+	/// Moves the rewards collected since the last era into the pot, returning the amount.
+	fn fund(pot: &AccountId) -> Balance;
+}
+
+impl<AccountId, Balance: Default> RewardFunding<AccountId, Balance> for () {
+	fn fund(_: &AccountId) -> Balance {
+		Default::default()
+	}
+}
@@ -10,21 +10,30 @@ mod benchmarking;
 pub use pallet::*;
 pub use scale_info::TypeInfo;
 
+pub mod crypto;
 pub mod functions;
+pub mod impl_delegation;
 pub mod impl_server;
 pub mod interface;
 pub mod migrations;
+pub mod offchain;
 pub mod types;
 pub mod weights;
 
-pub use crate::interface::{ServerInfo, ServerInterface, ServerProvider};
+pub use crate::interface::{
+	DelegationInterface, RewardFunding, ServerInfo, ServerInterface, ServerProvider,
+};
+pub use crypto::KEY_TYPE;
 pub use types::*;
 pub use weights::WeightInfo;
 
 use frame_support::traits::StorageVersion;
 
 /// The current storage version.
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(8);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(10);
+
+/// Report deposits are reserved under this id.
+const REPORT_RESERVE_ID: [u8; 8] = *b"srv/rprt";
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -33,15 +42,22 @@ pub mod pallet {
 	use frame_support::{
 		dispatch::DispatchResultWithPostInfo,
 		pallet_prelude::*,
-		traits::{Currency, Get},
+		sp_runtime::{
+			traits::{Saturating, Zero},
+			FixedU128, Permill, RuntimeAppPublic,
+		},
+		traits::{Currency, Get, NamedReservableCurrency},
 		Blake2_128Concat,
 	};
-	use frame_system::pallet_prelude::*;
+	use frame_system::{offchain::SendTransactionTypes, pallet_prelude::*};
 	use sp_std::vec::Vec;
 
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
-		type Currency: Currency<<Self as frame_system::Config>::AccountId>;
+	pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
+		type Currency: NamedReservableCurrency<
+			<Self as frame_system::Config>::AccountId,
+			ReserveIdentifier = [u8; 8],
+		>;
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type WeightInfo: WeightInfo;
 
@@ -53,12 +69,90 @@ pub mod pallet {
 
 		#[pallet::constant]
 		type MaxScheduledPerBlock: Get<u32>;
+
+		/// The maximum length of a server api url, also bounds the metadata web url.
+		#[pallet::constant]
+		type MaxApiUrlLength: Get<u32>;
+
+		/// The maximum length of the metadata name, region and api version.
+		#[pallet::constant]
+		type MaxMetadataFieldLength: Get<u32>;
+
+		/// The maximum length of the metadata description.
+		#[pallet::constant]
+		type MaxDescriptionLength: Get<u32>;
+
+		/// The deposit reserved from the owner for storing server metadata.
+		#[pallet::constant]
+		type MetadataDepositBase: Get<BalanceOf<Self>>;
+
+		/// The deposit reserved per byte of server metadata.
+		#[pallet::constant]
+		type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+
+		/// The minimum amount an account keeps delegated to a server.
+		#[pallet::constant]
+		type MinimumDelegationAmount: Get<BalanceOf<Self>>;
+
+		/// The maximum number of accounts delegating to a server, all repaid when it's removed.
+		#[pallet::constant]
+		type MaxDelegatorsPerServer: Get<u32>;
+
+		/// Funds the reward pot paid out to stakers at the end of every era.
+		type RewardFunding: RewardFunding<Self::AccountId, BalanceOf<Self>>;
+
+		/// The number of blocks in an era.
+		#[pallet::constant]
+		type EraLength: Get<Self::BlockNumber>;
+
+		/// The number of past eras whose rewards are kept in `ErasReward`.
+		#[pallet::constant]
+		type MaxPayoutHistory: Get<u32>;
+
+		/// The origin that upholds or rejects misbehavior reports.
+		type SlashOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The deposit reserved from a reporter, slashed when the report is rejected.
+		#[pallet::constant]
+		type ReportDeposit: Get<BalanceOf<Self>>;
+
+		/// The share of slashed stake paid to the reporter.
+		#[pallet::constant]
+		type ReporterRewardShare: Get<Permill>;
+
+		/// The largest fraction of the stake a single upheld report slashes.
+		#[pallet::constant]
+		type MaxSlashFraction: Get<Permill>;
+
+		/// The account the rest of the slashed stake and rejected deposits go to, e.g. a treasury
+		/// account.
+		type SlashDestination: Get<Self::AccountId>;
+
+		/// The key server operators keep in their node keystore to sign heartbeats with.
+		type HeartbeatKey: Member
+			+ Parameter
+			+ RuntimeAppPublic
+			+ Ord
+			+ MaybeSerializeDeserialize
+			+ MaxEncodedLen;
+
+		/// The number of blocks between two heartbeats of a server.
+		#[pallet::constant]
+		type HeartbeatInterval: Get<Self::BlockNumber>;
+
+		/// The number of blocks without a heartbeat after which a server is flagged as inactive,
+		/// a few `HeartbeatInterval`s so a missed heartbeat can be retried.
+		#[pallet::constant]
+		type InactivityPeriod: Get<Self::BlockNumber>;
+
+		/// The priority of heartbeat transactions.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
 	}
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::generate_store(pub(super) trait Store)]
-	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
@@ -75,7 +169,8 @@ pub mod pallet {
 
 	#[pallet::storage]
 	#[pallet::getter(fn server_by_api_url)]
-	pub(super) type ServerByApiUrl<T: Config> = StorageMap<_, Blake2_128Concat, ApiUrl, ServerId>;
+	pub(super) type ServerByApiUrl<T: Config> =
+		StorageMap<_, Blake2_128Concat, ApiUrlOf<T>, ServerId>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn server_by_owner)]
@@ -88,10 +183,150 @@ pub mod pallet {
 		ServerOf<T>,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn signing_key_by_server)]
+	pub(super) type SigningKeyByServer<T: Config> =
+		StorageMap<_, Blake2_128Concat, ServerId, T::AccountId>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn metadata_by_server)]
+	pub(super) type MetadataByServer<T: Config> =
+		StorageMap<_, Blake2_128Concat, ServerId, ServerMetadataOf<T>>;
+
+	/// The account that reserved the metadata deposit of a server, and how much.
+	#[pallet::storage]
+	#[pallet::getter(fn metadata_deposit)]
+	pub(super) type MetadataDeposit<T: Config> =
+		StorageMap<_, Blake2_128Concat, ServerId, (T::AccountId, BalanceOf<T>)>;
+
+	/// Stake delegated to a server by each account, held in the server account.
+	#[pallet::storage]
+	#[pallet::getter(fn delegation_by_server)]
+	pub(super) type DelegationByServer<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		ServerId,
+		Blake2_128Concat,
+		T::AccountId,
+		DelegationOf<T>,
+	>;
+
+	/// Bonded stake delegated to a server and the number of its delegators.
+	#[pallet::storage]
+	#[pallet::getter(fn backing)]
+	pub(super) type BackingByServer<T: Config> =
+		StorageMap<_, Blake2_128Concat, ServerId, BackingOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn current_era)]
+	pub type CurrentEra<T> = StorageValue<_, EraIndex, ValueQuery>;
+
+	/// Stake of every server owner and delegator, which the era rewards are split by.
+	#[pallet::storage]
+	#[pallet::getter(fn total_stake)]
+	pub type TotalStake<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// Rewards paid out per unit of stake over every era so far.
+	#[pallet::storage]
+	#[pallet::getter(fn reward_per_stake)]
+	pub type RewardPerStake<T> = StorageValue<_, FixedU128, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn reward_ledger)]
+	pub(super) type RewardLedgers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		ServerId,
+		Blake2_128Concat,
+		T::AccountId,
+		RewardLedgerOf<T>,
+	>;
+
+	/// Rewards paid out in the last `MaxPayoutHistory` eras.
+	#[pallet::storage]
+	#[pallet::getter(fn eras_reward)]
+	pub(super) type ErasReward<T: Config> =
+		StorageMap<_, Blake2_128Concat, EraIndex, EraRewardOf<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn report_count)]
+	pub type ReportCount<T> = StorageValue<_, ReportId, ValueQuery>;
+
+	/// Misbehavior reports waiting for `SlashOrigin`.
+	#[pallet::storage]
+	#[pallet::getter(fn report_by_id)]
+	pub(super) type ReportById<T: Config> = StorageMap<_, Blake2_128Concat, ReportId, ReportOf<T>>;
+
+	/// The block each upheld offence, a server and a hash of its evidence, was slashed at.
+	#[pallet::storage]
+	#[pallet::getter(fn slashed_offence)]
+	pub(super) type SlashedOffences<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		ServerId,
+		Blake2_128Concat,
+		HashOf<T>,
+		BlockNumberFor<T>,
+	>;
+
+	/// The heartbeat key registered by each server.
+	#[pallet::storage]
+	#[pallet::getter(fn heartbeat_key_by_server)]
+	pub(super) type HeartbeatKeyByServer<T: Config> =
+		StorageMap<_, Blake2_128Concat, ServerId, T::HeartbeatKey>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn server_by_heartbeat_key)]
+	pub(super) type ServerByHeartbeatKey<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::HeartbeatKey, ServerId>;
+
+	/// The block of the last heartbeat of each server.
+	#[pallet::storage]
+	#[pallet::getter(fn last_heartbeat)]
+	pub(super) type LastHeartbeat<T: Config> =
+		StorageMap<_, Blake2_128Concat, ServerId, BlockNumberFor<T>>;
+
+	/// The block each server is flagged as inactive at, unless it sends a heartbeat before.
+	#[pallet::storage]
+	#[pallet::getter(fn next_liveness_check)]
+	pub(super) type NextLivenessCheck<T: Config> =
+		StorageMap<_, Blake2_128Concat, ServerId, BlockNumberFor<T>>;
+
+	/// Servers that missed their heartbeats, and the block they were flagged at.
+	#[pallet::storage]
+	#[pallet::getter(fn inactive_since)]
+	pub type InactiveServers<T: Config> =
+		StorageMap<_, Blake2_128Concat, ServerId, BlockNumberFor<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn liveness_tasks)]
+	pub(super) type LivenessTasks<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<ServerId, T::MaxScheduledPerBlock>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn unbonding_tasks)]
+	pub(super) type UnbondingTasks<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<(ServerId, T::AccountId), T::MaxScheduledPerBlock>,
+		ValueQuery,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn tasks)]
-	pub(super) type Tasks<T: Config> =
-		StorageMap<_, Blake2_128Concat, BlockNumberFor<T>, Vec<ServerId>, ValueQuery>;
+	pub(super) type Tasks<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<ServerId, T::MaxScheduledPerBlock>,
+		ValueQuery,
+	>;
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -106,7 +341,40 @@ pub mod pallet {
 		Staked(T::AccountId, ServerId, BalanceOf<T>),
 		/// Unstaked success. [account_id, server_id, amount]
 		Unstaked(T::AccountId, ServerId, BalanceOf<T>),
-		/// Unstaked scheduled success. { server_id, when, task }
+		/// Delegated success. [delegator, server_id, amount]
+		Delegated(T::AccountId, ServerId, BalanceOf<T>),
+		/// Undelegated success, the amount unbonds after `ScheduledBlockTime`. [delegator,
+		/// server_id, amount]
+		Undelegated(T::AccountId, ServerId, BalanceOf<T>),
+		/// Unbonded stake returned to its delegator. [delegator, server_id, amount]
+		Unbonded(T::AccountId, ServerId, BalanceOf<T>),
+		/// Era rewards paid into the pot. { era, reward, total_stake }
+		EraPaid { era: EraIndex, reward: BalanceOf<T>, total_stake: BalanceOf<T> },
+		/// Rewards claimed from the pot. { server_id, account_id, amount }
+		RewardClaimed { server_id: ServerId, account_id: T::AccountId, amount: BalanceOf<T> },
+		/// Misbehavior reported. { report_id, report }
+		Reported { report_id: ReportId, report: ReportOf<T> },
+		/// Report upheld and the server stake slashed. { report_id, server_id, amount,
+		/// reporter_reward }
+		Slashed {
+			report_id: ReportId,
+			server_id: ServerId,
+			amount: BalanceOf<T>,
+			reporter_reward: BalanceOf<T>,
+		},
+		/// Report rejected and its deposit slashed. { report_id, report }
+		ReportRejected { report_id: ReportId, report: ReportOf<T> },
+		/// Heartbeat received. { server_id, block_number }
+		HeartbeatReceived { server_id: ServerId, block_number: BlockNumberFor<T> },
+		/// Server missed its heartbeats for `InactivityPeriod`. { server_id, last_heartbeat }
+		ServerInactive { server_id: ServerId, last_heartbeat: Option<BlockNumberFor<T>> },
+		/// Inactive server sent a heartbeat again. { server_id }
+		ServerActive { server_id: ServerId },
+		/// Metadata set success. { server_id, deposit }
+		MetadataSet { server_id: ServerId, deposit: BalanceOf<T> },
+		/// Metadata cleared and its deposit returned. { server_id, deposit }
+		MetadataCleared { server_id: ServerId, deposit: BalanceOf<T> },
+		/// Unstaked or unbonded scheduled success. { server_id, when, task }
 		Scheduled { server_id: ServerId, when: BlockNumberFor<T>, task: Vec<u8>, status: Status },
 	}
 
@@ -122,18 +390,48 @@ pub mod pallet {
 		MinimumStakeLimitBalance,
 		UnstakingLimitBalance,
 		WaitingToUnstaked,
+		BelowMinimumDelegation,
+		TooManyDelegators,
+		HeartbeatNotDue,
+		SlashFractionTooHigh,
+		AlreadySlashed,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(n: T::BlockNumber) -> Weight {
 			let tasks = Tasks::<T>::take(n);
-			Self::do_remove_servers(n, tasks)
+			let unbondings = UnbondingTasks::<T>::take(n);
+			let liveness_checks = LivenessTasks::<T>::take(n);
+
+			let mut weight = Self::do_remove_servers(n, tasks.into_inner())
+				.saturating_add(Self::do_release_unbondings(n, unbondings.into_inner()))
+				.saturating_add(Self::do_check_liveness(n, liveness_checks.into_inner()));
+
+			if !n.is_zero() && (n % T::EraLength::get()).is_zero() {
+				weight = weight.saturating_add(Self::do_end_era());
+			}
+
+			weight
 		}
 
 		fn on_runtime_upgrade() -> Weight {
 			migrations::migrate::<T>()
 		}
+
+		/// Sends a heartbeat for every server whose heartbeat key is in the node keystore.
+		fn offchain_worker(now: T::BlockNumber) {
+			for key in T::HeartbeatKey::all().into_iter() {
+				if let Err(error) = Self::send_heartbeat(key, now) {
+					log::debug!(
+						target: "runtime::server",
+						"Skipping heartbeat at {:?}: {:?}",
+						now,
+						error,
+					);
+				}
+			}
+		}
 	}
 
 	#[pallet::call]
@@ -141,7 +439,7 @@ pub mod pallet {
 		#[pallet::weight(T::WeightInfo::register(api_url.len() as u32))]
 		pub fn register(
 			origin: OriginFor<T>,
-			api_url: Vec<u8>,
+			api_url: ApiUrlOf<T>,
 			stake_amount: Option<BalanceOf<T>>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
@@ -160,7 +458,11 @@ pub mod pallet {
 			}
 		}
 
-		#[pallet::weight(T::WeightInfo::update_server(*server_id as u32))]
+		#[pallet::weight(match action_type {
+			ActionType::UpdateMetadata(_) => T::WeightInfo::update_metadata(),
+			ActionType::UpdateHeartbeatKey(_) => T::WeightInfo::update_heartbeat_key(),
+			_ => T::WeightInfo::update_server(*server_id as u32),
+		})]
 		pub fn update_server(
 			origin: OriginFor<T>,
 			server_id: ServerId,
@@ -215,5 +517,220 @@ pub mod pallet {
 				Err(error) => Err(error.into()),
 			}
 		}
+
+		#[pallet::weight(T::WeightInfo::delegate())]
+		pub fn delegate(
+			origin: OriginFor<T>,
+			server_id: ServerId,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			match <Self as DelegationInterface<T>>::delegate(&who, server_id, amount) {
+				Ok(_) => {
+					Self::deposit_event(Event::Delegated(who, server_id, amount));
+					Ok(().into())
+				},
+				Err(error) => Err(error.into()),
+			}
+		}
+
+		#[pallet::weight(T::WeightInfo::undelegate())]
+		pub fn undelegate(
+			origin: OriginFor<T>,
+			server_id: ServerId,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			match <Self as DelegationInterface<T>>::undelegate(&who, server_id, amount) {
+				Ok(when) => {
+					Self::deposit_event(Event::Undelegated(who, server_id, amount));
+					Self::deposit_event(Event::Scheduled {
+						server_id,
+						when,
+						task: b"Unbonded".to_vec(),
+						status: Status::InProgress,
+					});
+					Ok(().into())
+				},
+				Err(error) => Err(error.into()),
+			}
+		}
+
+		/// Claims the era rewards earned by the stake of the caller on the server.
+		#[pallet::weight(T::WeightInfo::claim_reward())]
+		pub fn claim_reward(
+			origin: OriginFor<T>,
+			server_id: ServerId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let amount = Self::do_claim_reward(server_id, &who)?;
+
+			Self::deposit_event(Event::RewardClaimed { server_id, account_id: who, amount });
+			Ok(().into())
+		}
+
+		/// Reports a server with a hash of the evidence, reserving `ReportDeposit`.
+		#[pallet::weight(T::WeightInfo::report())]
+		pub fn report(
+			origin: OriginFor<T>,
+			server_id: ServerId,
+			evidence: HashOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let reporter = ensure_signed(origin)?;
+
+			ensure!(ServerById::<T>::contains_key(server_id), Error::<T>::NotExists);
+			ensure!(
+				!SlashedOffences::<T>::contains_key(server_id, evidence),
+				Error::<T>::AlreadySlashed
+			);
+
+			let report_id = Self::report_count();
+			let next_report_id = report_id.checked_add(1).ok_or(Error::<T>::Overflow)?;
+			let deposit = T::ReportDeposit::get();
+
+			CurrencyOf::<T>::reserve_named(&REPORT_RESERVE_ID, &reporter, deposit)
+				.map_err(|_| Error::<T>::InsufficientBalance)?;
+
+			let report = Report::new(server_id, reporter, evidence, deposit);
+
+			ReportById::<T>::insert(report_id, &report);
+			ReportCount::<T>::put(next_report_id);
+
+			Self::deposit_event(Event::Reported { report_id, report });
+			Ok(().into())
+		}
+
+		/// Upholds the report, slashing the fraction of the owner stake and of every delegation,
+		/// at most `MaxSlashFraction`. An offence is slashed once, other reports with the same
+		/// evidence are left to be rejected.
+		#[pallet::weight(T::WeightInfo::slash(T::MaxDelegatorsPerServer::get()))]
+		pub fn slash(
+			origin: OriginFor<T>,
+			report_id: ReportId,
+			slash_fraction: Permill,
+		) -> DispatchResultWithPostInfo {
+			T::SlashOrigin::ensure_origin(origin)?;
+
+			ensure!(slash_fraction <= T::MaxSlashFraction::get(), Error::<T>::SlashFractionTooHigh);
+
+			let report = Self::report_by_id(report_id).ok_or(Error::<T>::NotExists)?;
+			let server_id = report.get_server_id();
+			let reporter = report.get_reporter();
+			let evidence = report.get_evidence();
+
+			ensure!(
+				!SlashedOffences::<T>::contains_key(server_id, evidence),
+				Error::<T>::AlreadySlashed
+			);
+
+			let (amount, reporter_reward, delegator_count) =
+				Self::do_slash(server_id, slash_fraction, reporter)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			SlashedOffences::<T>::insert(server_id, evidence, now);
+
+			CurrencyOf::<T>::unreserve_named(&REPORT_RESERVE_ID, reporter, *report.get_deposit());
+			ReportById::<T>::remove(report_id);
+
+			Self::deposit_event(Event::Slashed { report_id, server_id, amount, reporter_reward });
+			Ok(Some(T::WeightInfo::slash(delegator_count)).into())
+		}
+
+		#[pallet::weight(T::WeightInfo::reject_report())]
+		pub fn reject_report(
+			origin: OriginFor<T>,
+			report_id: ReportId,
+		) -> DispatchResultWithPostInfo {
+			T::SlashOrigin::ensure_origin(origin)?;
+
+			let report = ReportById::<T>::take(report_id).ok_or(Error::<T>::NotExists)?;
+
+			// The deposit goes to the slash destination
+			let (slashed, _) = CurrencyOf::<T>::slash_reserved_named(
+				&REPORT_RESERVE_ID,
+				report.get_reporter(),
+				*report.get_deposit(),
+			);
+
+			CurrencyOf::<T>::resolve_creating(&T::SlashDestination::get(), slashed);
+
+			Self::deposit_event(Event::ReportRejected { report_id, report });
+			Ok(().into())
+		}
+
+		/// Records a heartbeat submitted by the offchain worker of the server operator, the
+		/// signature is checked when the transaction is validated.
+		#[pallet::weight(T::WeightInfo::heartbeat())]
+		pub fn heartbeat(
+			origin: OriginFor<T>,
+			heartbeat: HeartbeatOf<T>,
+			_signature: HeartbeatSignatureOf<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+
+			let server_id = heartbeat.get_server_id();
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			ensure!(HeartbeatKeyByServer::<T>::contains_key(server_id), Error::<T>::NotExists);
+			ensure!(Self::is_heartbeat_due(server_id, now), Error::<T>::HeartbeatNotDue);
+
+			Self::do_schedule_liveness_check(server_id, now)?;
+
+			LastHeartbeat::<T>::insert(server_id, now);
+
+			if InactiveServers::<T>::take(server_id).is_some() {
+				Self::deposit_event(Event::ServerActive { server_id });
+			}
+
+			Self::deposit_event(Event::HeartbeatReceived { server_id, block_number: now });
+			Ok(().into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			if let Call::heartbeat { heartbeat, signature } = call {
+				let server_id = heartbeat.get_server_id();
+				let now = <frame_system::Pallet<T>>::block_number();
+				let interval = T::HeartbeatInterval::get();
+
+				if heartbeat.get_block_number() > now {
+					return InvalidTransaction::Future.into()
+				}
+
+				if heartbeat.get_block_number().saturating_add(interval) < now ||
+					!Self::is_heartbeat_due(server_id, now)
+				{
+					return InvalidTransaction::Stale.into()
+				}
+
+				let key = match Self::heartbeat_key_by_server(server_id) {
+					Some(key) => key,
+					None => return InvalidTransaction::BadSigner.into(),
+				};
+
+				let signature_valid =
+					heartbeat.using_encoded(|encoded| key.verify(&encoded, signature));
+
+				if !signature_valid {
+					return InvalidTransaction::BadProof.into()
+				}
+
+				ValidTransaction::with_tag_prefix("ServerHeartbeat")
+					.priority(T::UnsignedPriority::get())
+					.and_provides((server_id, Self::last_heartbeat(server_id)))
+					.longevity(TryInto::<u64>::try_into(interval).unwrap_or(64_u64))
+					.propagate(true)
+					.build()
+			} else {
+				InvalidTransaction::Call.into()
+			}
+		}
 	}
 }
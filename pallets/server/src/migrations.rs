@@ -1,7 +1,7 @@
 use crate::{
-	AccountIdOf, Config, Pallet, Server as NewServer, ServerByApiUrl, ServerById as NewServerById,
-	ServerByOwner as NewServerByOwner, ServerCount as NewServerCount, ServerId,
-	ServerIndex as NewServerIndex, ServerOf,
+	AccountIdOf, ApiUrlOf, Config, Pallet, Server as NewServer, ServerByApiUrl,
+	ServerById as NewServerById, ServerByOwner as NewServerByOwner, ServerCount as NewServerCount,
+	ServerId, ServerIndex as NewServerIndex, ServerOf,
 };
 use frame_support::{
 	pallet_prelude::*,
@@ -57,6 +57,16 @@ pub fn migrate<T: Config>() -> Weight {
 		version = StorageVersion::new(8);
 	}
 
+	if version == 8 {
+		weight = weight.saturating_add(versions::v9::migrate::<T>());
+		version = StorageVersion::new(9);
+	}
+
+	if version == 9 {
+		weight = weight.saturating_add(versions::v10::migrate::<T>());
+		version = StorageVersion::new(10);
+	}
+
 	version.put::<Pallet<T>>();
 	weight
 }
@@ -293,15 +303,15 @@ mod versions {
 
 			#[allow(dead_code)]
 			#[derive(Encode, Decode, Clone)]
-			pub struct OldServer<AccountId, Balance> {
+			pub struct OldServer<AccountId, Balance, ApiUrl> {
 				id: u64,
 				owner: AccountId,
-				api_url: Vec<u8>,
+				api_url: ApiUrl,
 				staked_amount: Balance,
 			}
 
 			NewServerById::<T>::translate(
-				|server_id: ServerId, old: OldServer<AccountIdOf<T>, BalanceOf<T>>| {
+				|server_id: ServerId, old: OldServer<AccountIdOf<T>, BalanceOf<T>, ApiUrlOf<T>>| {
 					weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
 
 					let new_server =
@@ -320,15 +330,12 @@ mod versions {
 	pub mod v7 {
 		use crate::BalanceOf;
 		use frame_support::sp_runtime::traits::Zero;
-		use frame_system::pallet_prelude::BlockNumberFor;
 
 		use super::*;
 
 		pub fn migrate<T: Config>() -> Weight {
 			let mut weight = T::DbWeight::get().writes(1);
 
-			pub type ServerOf<T> = NewServer<AccountIdOf<T>, BalanceOf<T>, BlockNumberFor<T>>;
-
 			#[storage_alias]
 			type RewardBalance<T: Config> = StorageNMap<
 				Tipping,
@@ -431,4 +438,89 @@ mod versions {
 			weight
 		}
 	}
+
+	pub mod v9 {
+		use crate::BalanceOf;
+		use frame_system::pallet_prelude::BlockNumberFor;
+
+		use super::*;
+
+		/// Bounds the api url, truncating the ones that don't fit `MaxApiUrlLength`.
+		fn bounded_api_url<T: Config>(mut api_url: Vec<u8>) -> ApiUrlOf<T> {
+			api_url.truncate(T::MaxApiUrlLength::get() as usize);
+			api_url.try_into().unwrap_or_default()
+		}
+
+		pub fn migrate<T: Config>() -> Weight {
+			let mut weight = T::DbWeight::get().writes(1);
+
+			#[allow(dead_code)]
+			#[derive(Encode, Decode, Clone)]
+			pub struct OldServer<AccountId, Balance, BlockNumber> {
+				id: u64,
+				owner: AccountId,
+				api_url: Vec<u8>,
+				staked_amount: Balance,
+				unstaked_at: Option<BlockNumber>,
+			}
+
+			type OldServerOf<T> = OldServer<AccountIdOf<T>, BalanceOf<T>, BlockNumberFor<T>>;
+
+			#[storage_alias]
+			type ServerByApiUrl<T: Config> =
+				StorageMap<Server, Blake2_128Concat, Vec<u8>, ServerId>;
+
+			let into_server = |server_id: ServerId, old: OldServerOf<T>| {
+				let api_url = bounded_api_url::<T>(old.api_url);
+
+				NewServer::new(server_id, &old.owner, &api_url, old.staked_amount)
+					.set_unstaked_at(old.unstaked_at)
+			};
+
+			NewServerById::<T>::translate(|server_id: ServerId, old: OldServerOf<T>| {
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+
+				if old.api_url.len() > T::MaxApiUrlLength::get() as usize {
+					weight = weight.saturating_add(T::DbWeight::get().writes(2));
+
+					ServerByApiUrl::<T>::remove(&old.api_url);
+
+					let server = into_server(server_id, old);
+
+					crate::ServerByApiUrl::<T>::insert(server.get_api_url(), server_id);
+
+					return Some(server)
+				}
+
+				Some(into_server(server_id, old))
+			});
+
+			NewServerByOwner::<T>::translate(
+				|_: AccountIdOf<T>, server_id: ServerId, old: OldServerOf<T>| {
+					weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+
+					Some(into_server(server_id, old))
+				},
+			);
+
+			weight
+		}
+	}
+
+	pub mod v10 {
+		use super::*;
+
+		/// Starts every server owner earning era rewards on its current stake.
+		pub fn migrate<T: Config>() -> Weight {
+			let mut weight = T::DbWeight::get().writes(1);
+
+			for (server_id, server) in NewServerById::<T>::iter() {
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(5, 2));
+
+				Pallet::<T>::do_update_reward_stake(server_id, server.get_owner());
+			}
+
+			weight
+		}
+	}
 }
@@ -3,20 +3,22 @@ use crate as pallet_server;
 use sp_core::{sr25519, Pair, H256};
 use sp_io::TestExternalities;
 use sp_runtime::{
-	testing::Header,
+	testing::{Header, UintAuthorityId},
 	traits::{BlakeTwo256, IdentityLookup},
+	transaction_validity::TransactionPriority,
+	Permill,
 };
 
 use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{ConstU16, ConstU32, ConstU64, Everything},
+	traits::{ConstU16, ConstU32, ConstU64, Currency, Everything},
 	weights::Weight,
 };
 use frame_system as system;
 
 use pallet_balances::AccountData;
 
-type UncheckedExtrinsic = system::mocking::MockUncheckedExtrinsic<Test>;
+pub type UncheckedExtrinsic = system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = system::mocking::MockBlock<Test>;
 type Balance = u64;
 
@@ -73,7 +75,7 @@ impl pallet_balances::Config for Test {
 	type DustRemoval = ();
 	type ExistentialDeposit = ExistentialDeposit;
 	type MaxLocks = ();
-	type MaxReserves = ();
+	type MaxReserves = ConstU32<50>;
 	type ReserveIdentifier = [u8; 8];
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
@@ -83,23 +85,92 @@ parameter_types! {
 	pub const MinimumStakeAmount: u64 = 3;
 	pub const ScheduledBlockTime: u32 = 10;
 	pub const MaxScheduledPerBlock: u32 = 5;
+	pub const MaxApiUrlLength: u32 = 64;
+	pub const MetadataDepositBase: Balance = 2;
+	pub const MetadataDepositPerByte: Balance = 1;
+	pub const MinimumDelegationAmount: Balance = 2;
+	pub const EraLength: u64 = 50;
+	pub const EraReward: Balance = 12;
+	pub const ReportDeposit: Balance = 2;
+	pub const ReporterRewardShare: Permill = Permill::from_percent(50);
+	pub const MaxSlashFraction: Permill = Permill::from_percent(50);
+	pub SlashDestination: sr25519::Public = account_key("treasury");
+	pub const HeartbeatInterval: u64 = 5;
+	pub const InactivityPeriod: u64 = 20;
+	pub const UnsignedPriority: TransactionPriority = TransactionPriority::max_value();
+}
+
+/// Mints `EraReward` into the pot every era.
+pub struct MockRewardFunding;
+impl pallet_server::RewardFunding<sr25519::Public, Balance> for MockRewardFunding {
+	fn fund(pot: &sr25519::Public) -> Balance {
+		let _ = Balances::deposit_creating(pot, EraReward::get());
+
+		EraReward::get()
+	}
 }
 
 impl pallet_server::Config for Test {
 	type Currency = Balances;
+	type EraLength = EraLength;
+	type HeartbeatInterval = HeartbeatInterval;
+	type HeartbeatKey = UintAuthorityId;
+	type InactivityPeriod = InactivityPeriod;
+	type MaxApiUrlLength = MaxApiUrlLength;
+	type MaxDelegatorsPerServer = ConstU32<2>;
+	type MaxDescriptionLength = ConstU32<64>;
+	type MaxMetadataFieldLength = ConstU32<16>;
+	type MaxPayoutHistory = ConstU32<2>;
 	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type MaxSlashFraction = MaxSlashFraction;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type MinimumDelegationAmount = MinimumDelegationAmount;
 	type MinimumStakeAmount = MinimumStakeAmount;
+	type ReportDeposit = ReportDeposit;
+	type ReporterRewardShare = ReporterRewardShare;
+	type RewardFunding = MockRewardFunding;
 	type RuntimeEvent = RuntimeEvent;
 	type ScheduledBlockTime = ScheduledBlockTime;
+	type SlashDestination = SlashDestination;
+	type SlashOrigin = system::EnsureRoot<sr25519::Public>;
+	type UnsignedPriority = UnsignedPriority;
 	type WeightInfo = ();
 }
 
+impl<C> system::offchain::SendTransactionTypes<C> for Test
+where
+	RuntimeCall: From<C>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = RuntimeCall;
+}
+
 pub fn account_key(s: &str) -> sr25519::Public {
 	sr25519::Pair::from_string(&format!("//{}", s), None)
 		.expect("static values are valud; qed")
 		.public()
 }
 
+pub fn api_url_key(s: &str) -> pallet_server::ApiUrlOf<Test> {
+	s.as_bytes().to_vec().try_into().expect("static values are valid; qed")
+}
+
+pub fn metadata_key(name: &str, description: &str) -> pallet_server::ServerMetadataOf<Test> {
+	pallet_server::ServerMetadata::new(
+		name.as_bytes().to_vec().try_into().expect("static values are valid; qed"),
+		description
+			.as_bytes()
+			.to_vec()
+			.try_into()
+			.expect("static values are valid; qed"),
+		api_url_key(""),
+		b"id".to_vec().try_into().expect("static values are valid; qed"),
+		b"2".to_vec().try_into().expect("static values are valid; qed"),
+		None,
+	)
+}
+
 pub struct ExternalityBuilder {
 	existential_deposit: u64,
 }
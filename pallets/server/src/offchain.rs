@@ -0,0 +1,114 @@
+use crate::*;
+use codec::Encode;
+use frame_support::sp_runtime::{
+	offchain::{
+		http,
+		storage::{MutateStorageError, StorageRetrievalError, StorageValueRef},
+		Duration,
+	},
+	traits::Saturating,
+	RuntimeAppPublic,
+};
+use frame_system::offchain::SubmitTransaction;
+
+/// The prefix of the local storage keeping the block each heartbeat was sent at.
+const DB_PREFIX: &[u8] = b"server::heartbeat::";
+
+/// How long the offchain worker waits for the server api to respond.
+const HTTP_TIMEOUT_MS: u64 = 3_000;
+
+/// The only url schemes the offchain worker requests.
+const HTTP_SCHEMES: [&str; 2] = ["http://", "https://"];
+
+#[derive(PartialEq, Eq, RuntimeDebug)]
+pub enum OffchainErr<BlockNumber> {
+	NotRegistered,
+	TooEarly(BlockNumber),
+	InvalidApiUrl,
+	HttpFetchingError,
+	Unhealthy(u16),
+	FailedSigning,
+	SubmitTransaction,
+}
+
+impl<T: Config> Pallet<T> {
+	/// Checks the api of the server registered with the key, then submits a heartbeat signed
+	/// with it. Every server sends at most one heartbeat every `HeartbeatInterval`.
+	pub fn send_heartbeat(
+		key: T::HeartbeatKey,
+		now: T::BlockNumber,
+	) -> Result<(), OffchainErr<T::BlockNumber>> {
+		let server_id = Self::server_by_heartbeat_key(&key).ok_or(OffchainErr::NotRegistered)?;
+		let server = ServerById::<T>::get(server_id).ok_or(OffchainErr::NotRegistered)?;
+
+		if !Self::is_heartbeat_due(server_id, now) {
+			return Err(OffchainErr::TooEarly(now))
+		}
+
+		Self::with_heartbeat_lock(server_id, now, || {
+			Self::fetch_health(server.get_api_url())?;
+
+			let heartbeat = Heartbeat::new(server_id, now);
+			let signature = key.sign(&heartbeat.encode()).ok_or(OffchainErr::FailedSigning)?;
+			let call = Call::heartbeat { heartbeat, signature };
+
+			SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into())
+				.map_err(|_| OffchainErr::SubmitTransaction)
+		})
+	}
+
+	/// Runs `send` unless a heartbeat of the server was sent in the last `HeartbeatInterval`,
+	/// so concurrent offchain workers don't send it twice.
+	fn with_heartbeat_lock<F>(
+		server_id: ServerId,
+		now: T::BlockNumber,
+		send: F,
+	) -> Result<(), OffchainErr<T::BlockNumber>>
+	where
+		F: FnOnce() -> Result<(), OffchainErr<T::BlockNumber>>,
+	{
+		let key = (DB_PREFIX, server_id).encode();
+		let storage = StorageValueRef::persistent(&key);
+		let interval = T::HeartbeatInterval::get();
+		let locked =
+			storage.mutate(|sent_at: Result<Option<T::BlockNumber>, StorageRetrievalError>| {
+				match sent_at {
+					Ok(Some(sent_at)) if sent_at.saturating_add(interval) > now =>
+						Err(OffchainErr::TooEarly(sent_at)),
+					_ => Ok(now),
+				}
+			});
+
+		match locked {
+			Ok(_) => send(),
+			Err(MutateStorageError::ValueFunctionFailed(error)) => Err(error),
+			Err(MutateStorageError::ConcurrentModification(_)) => Err(OffchainErr::TooEarly(now)),
+		}
+	}
+
+	/// Requests the api url of the server, anything but a success response fails. The url is set
+	/// by the owner, so anything but an http(s) url is refused before it is requested.
+	fn fetch_health(api_url: &[u8]) -> Result<(), OffchainErr<T::BlockNumber>> {
+		let url = sp_std::str::from_utf8(api_url).map_err(|_| OffchainErr::InvalidApiUrl)?;
+
+		if !HTTP_SCHEMES.iter().any(|scheme| url.starts_with(scheme)) {
+			return Err(OffchainErr::InvalidApiUrl)
+		}
+
+		let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(HTTP_TIMEOUT_MS));
+		let pending = http::Request::get(url)
+			.deadline(deadline)
+			.send()
+			.map_err(|_| OffchainErr::HttpFetchingError)?;
+		let response = pending
+			.try_wait(deadline)
+			.map_err(|_| OffchainErr::HttpFetchingError)?
+			.map_err(|_| OffchainErr::HttpFetchingError)?;
+
+		if !(200..300).contains(&response.code) {
+			return Err(OffchainErr::Unhealthy(response.code))
+		}
+
+		Ok(())
+	}
+}
@@ -1,5 +1,26 @@
-use crate::{self as pallet_server, mock::*, ActionType, Error, Status};
-use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
+use crate::{
+	self as pallet_server, mock::*, offchain::OffchainErr, ActionType, DelegationInterface, Error,
+	Status,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::DispatchError,
+	traits::{OffchainWorker, OnInitialize},
+};
+use sp_core::{
+	offchain::{
+		testing::{PendingRequest, TestOffchainExt, TestTransactionPoolExt},
+		OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+	},
+	H256,
+};
+use sp_runtime::{
+	testing::UintAuthorityId,
+	traits::ValidateUnsigned,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+	PerThing, Permill, RuntimeAppPublic,
+};
 
 #[test]
 fn register_works() {
@@ -8,7 +29,7 @@ fn register_works() {
 		let owner_origin = RuntimeOrigin::signed(owner);
 
 		let server_id = 0u64;
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
 		let server = pallet_server::Server::new(server_id, &owner, &api_url, 5);
 		let server_account_id = Server::server_account_id(server_id);
@@ -32,7 +53,7 @@ pub fn transfer_owner_works() {
 		let owner_origin = RuntimeOrigin::signed(owner);
 
 		let server_id = 0u64;
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
 		let new_owner = account_key("bob");
 		let server = pallet_server::Server::new(server_id, &new_owner, &api_url, 3);
@@ -53,9 +74,9 @@ pub fn change_api_url_works() {
 		let owner_origin = RuntimeOrigin::signed(owner);
 
 		let server_id = 0u64;
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
-		let new_api_url = "https://api.testnet.myriad.social".as_bytes().to_vec();
+		let new_api_url = api_url_key("https://api.testnet.myriad.social");
 		let server = pallet_server::Server::new(server_id, &owner, &new_api_url, 3);
 
 		assert_ok!(Server::register(RuntimeOrigin::signed(owner), api_url.clone(), None));
@@ -71,6 +92,34 @@ pub fn change_api_url_works() {
 	})
 }
 
+#[test]
+pub fn update_signing_key_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("alice");
+		let signing_key = account_key("signer");
+
+		let server_id = 0u64;
+		let api_url = api_url_key("https://api.dev.myriad.social");
+
+		assert_ok!(Server::register(RuntimeOrigin::signed(owner), api_url, None));
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(owner),
+			server_id,
+			ActionType::UpdateSigningKey(Some(signing_key))
+		));
+
+		assert_eq!(Server::signing_key_by_server(server_id), Some(signing_key));
+
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(owner),
+			server_id,
+			ActionType::UpdateSigningKey(None)
+		));
+
+		assert_eq!(Server::signing_key_by_server(server_id), None);
+	})
+}
+
 #[test]
 pub fn deregister_works() {
 	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
@@ -78,7 +127,7 @@ pub fn deregister_works() {
 		let owner_origin = RuntimeOrigin::signed(owner);
 
 		let server_id = 0u64;
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 		let server =
 			pallet_server::Server::new(server_id, &owner, &api_url, 3).set_unstaked_at(Some(20));
 
@@ -102,8 +151,8 @@ pub fn cancel_deregister_works() {
 		let server_id = 0u64;
 		let other_server_id = 1u64;
 
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
-		let other_api_url = "https://api.testnet.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
+		let other_api_url = api_url_key("https://api.testnet.myriad.social");
 
 		let server = pallet_server::Server::new(server_id, &owner, &api_url, 3);
 
@@ -133,7 +182,7 @@ pub fn cancel_deregister_works() {
 pub fn increase_stake_amount_works() {
 	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
 		let owner = account_key("alice");
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 		let server_id = 0u64;
 		let amount = 3;
 
@@ -158,7 +207,7 @@ pub fn increase_stake_amount_works() {
 pub fn decrease_stake_amount_works() {
 	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
 		let owner = account_key("alice");
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 		let server_id = 0u64;
 		let amount = 3;
 
@@ -190,7 +239,7 @@ pub fn unstake_server_works() {
 		let owner = account_key("alice");
 
 		let server_id = 0u64;
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 		let server =
 			pallet_server::Server::new(server_id, &owner, &api_url, 0).set_unstaked_at(Some(11));
 
@@ -199,7 +248,7 @@ pub fn unstake_server_works() {
 
 		let other_owner = account_key("bob");
 		let other_server_id = 1u64;
-		let other_api_url = "https://api.testnet.myriad.social".as_bytes().to_vec();
+		let other_api_url = api_url_key("https://api.testnet.myriad.social");
 
 		System::set_block_number(2);
 
@@ -228,13 +277,13 @@ pub fn cant_register_when_api_url_exist() {
 	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
 		let owner = account_key("alice");
 		let owner_origin = RuntimeOrigin::signed(owner);
-		let api_url = b"https://api.dev.myriad.social".to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
 		assert_ok!(Server::register(owner_origin, api_url, None));
 
 		let other_owner = account_key("bob");
 		let other_owner_origin = RuntimeOrigin::signed(other_owner);
-		let other_api_url = b"https://api.dev.myriad.social".to_vec();
+		let other_api_url = api_url_key("https://api.dev.myriad.social");
 
 		assert_noop!(
 			Server::register(other_owner_origin, other_api_url, None),
@@ -248,7 +297,7 @@ pub fn cant_register_when_balance_insufficient() {
 	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
 		let owner = account_key("satoshi");
 		let owner_origin = RuntimeOrigin::signed(owner);
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
 		assert_noop!(
 			Server::register(owner_origin.clone(), api_url.clone(), None),
@@ -268,7 +317,7 @@ pub fn cant_update_server_where_already_deregister() {
 		let owner_origin = RuntimeOrigin::signed(owner);
 
 		let server_id = 0u64;
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
 		System::set_block_number(10);
 
@@ -276,7 +325,7 @@ pub fn cant_update_server_where_already_deregister() {
 		assert_ok!(Server::unregister(owner_origin.clone(), server_id));
 
 		let new_owner = account_key("bob");
-		let new_api_url = "https://api.testnet.myriad.social".as_bytes().to_vec();
+		let new_api_url = api_url_key("https://api.testnet.myriad.social");
 		let new_action = ActionType::StakeAmount(10);
 
 		assert_noop!(
@@ -327,7 +376,7 @@ pub fn cant_transfer_owner_when_not_owner() {
 		let owner_origin = RuntimeOrigin::signed(owner);
 
 		let server_id = 0u64;
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
 		assert_ok!(Server::register(owner_origin, api_url, None));
 
@@ -353,7 +402,7 @@ pub fn cant_change_api_url_when_server_id_not_exist() {
 		let owner_origin = RuntimeOrigin::signed(owner);
 
 		let fake_id = 0u64;
-		let new_api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let new_api_url = api_url_key("https://api.dev.myriad.social");
 
 		assert_noop!(
 			Server::update_server(owner_origin, fake_id, ActionType::UpdateApiUrl(new_api_url)),
@@ -369,13 +418,13 @@ pub fn cant_change_api_url_when_not_owner() {
 		let owner_origin = RuntimeOrigin::signed(owner);
 
 		let server_id = 0u64;
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
 		assert_ok!(Server::register(owner_origin, api_url, None));
 
 		let fake_owner = account_key("bob");
 		let fake_owner_origin = RuntimeOrigin::signed(fake_owner);
-		let new_api_url = "https://api.testnet.myriad.social".as_bytes().to_vec();
+		let new_api_url = api_url_key("https://api.testnet.myriad.social");
 
 		assert_noop!(
 			Server::update_server(
@@ -395,17 +444,17 @@ pub fn cant_change_api_url_when_api_url_exist() {
 		let owner_origin = RuntimeOrigin::signed(owner);
 
 		let server_id = 0u64;
-		let api_url = b"https://api.dev.myriad.social".to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
 		assert_ok!(Server::register(owner_origin.clone(), api_url, None));
 
 		let other_owner = account_key("bob");
 		let other_owner_origin = RuntimeOrigin::signed(other_owner);
-		let other_api_url = b"https://api.testnet.myriad.social".to_vec();
+		let other_api_url = api_url_key("https://api.testnet.myriad.social");
 
 		assert_ok!(Server::register(other_owner_origin, other_api_url, None));
 
-		let new_api_url = b"https://api.testnet.myriad.social".to_vec();
+		let new_api_url = api_url_key("https://api.testnet.myriad.social");
 
 		assert_noop!(
 			Server::update_server(owner_origin, server_id, ActionType::UpdateApiUrl(new_api_url)),
@@ -433,7 +482,7 @@ pub fn cant_deregister_when_not_owner() {
 		let owner_origin = RuntimeOrigin::signed(owner);
 
 		let server_id = 0u64;
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
 		assert_ok!(Server::register(owner_origin, api_url, None));
 
@@ -484,7 +533,7 @@ pub fn cant_cancel_deregister_when_unauthorized() {
 		let other_owner = account_key("bob");
 
 		let server_id = 0u64;
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
 		assert_ok!(Server::register(RuntimeOrigin::signed(owner), api_url, None));
 
@@ -500,7 +549,7 @@ pub fn cant_cancel_deregister_when_not_unstaked() {
 	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
 		let owner = account_key("alice");
 		let server_id = 0u64;
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
 		assert_ok!(Server::register(RuntimeOrigin::signed(owner), api_url, None));
 
@@ -533,7 +582,7 @@ pub fn cant_increase_stake_amount_when_server_id_not_exist() {
 pub fn cant_increase_stake_amount_when_not_owner() {
 	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
 		let owner = account_key("alice");
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 		let server_id = 0u64;
 		let amount = 10;
 
@@ -555,7 +604,7 @@ pub fn cant_increase_stake_amount_when_not_owner() {
 pub fn cant_increase_stake_amount_when_balance_insufficient() {
 	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
 		let owner = account_key("alice");
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 		let server_id = 0u64;
 		let amount = 13;
 
@@ -579,7 +628,7 @@ fn call_event_should_work() {
 		let owner = account_key("alice");
 
 		let server_id = 0u64;
-		let api_url = "https://api.dev.myriad.social".as_bytes().to_vec();
+		let api_url = api_url_key("https://api.dev.myriad.social");
 
 		let server = pallet_server::Server::new(server_id, &owner, &api_url, 3);
 
@@ -614,7 +663,7 @@ fn call_event_should_work() {
 			ActionType::TransferOwner(new_owner),
 		)));
 
-		let new_api_url = "https://api.testnet.myriad.social".as_bytes().to_vec();
+		let new_api_url = api_url_key("https://api.testnet.myriad.social");
 
 		assert_ok!(Server::update_server(
 			RuntimeOrigin::signed(new_owner),
@@ -664,3 +713,894 @@ fn call_event_should_work() {
 		}));
 	})
 }
+
+#[test]
+fn update_metadata_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("john");
+		let new_owner = account_key("bob");
+		let server_id = 0u64;
+		let metadata = metadata_key("A", "");
+		let new_metadata = metadata_key("Myriad", "");
+		let deposit = 2 + metadata.encoded_size() as u64;
+		let new_deposit = 2 + new_metadata.encoded_size() as u64;
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(owner),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(owner),
+			server_id,
+			ActionType::UpdateMetadata(Some(metadata.clone()))
+		));
+
+		System::assert_has_event(RuntimeEvent::Server(crate::Event::MetadataSet {
+			server_id,
+			deposit,
+		}));
+
+		assert_eq!(Server::metadata_by_server(server_id), Some(metadata));
+		assert_eq!(Server::metadata_deposit(server_id), Some((owner, deposit)));
+		assert_eq!(Balances::reserved_balance(owner), deposit);
+
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(owner),
+			server_id,
+			ActionType::UpdateMetadata(Some(new_metadata.clone()))
+		));
+
+		assert_eq!(Server::metadata_by_server(server_id), Some(new_metadata.clone()));
+		assert_eq!(Balances::reserved_balance(owner), new_deposit);
+
+		// The new owner takes over the deposit
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(owner),
+			server_id,
+			ActionType::TransferOwner(new_owner)
+		));
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(new_owner),
+			server_id,
+			ActionType::UpdateMetadata(Some(new_metadata))
+		));
+
+		assert_eq!(Server::metadata_deposit(server_id), Some((new_owner, new_deposit)));
+		assert_eq!(Balances::reserved_balance(owner), 0);
+		assert_eq!(Balances::reserved_balance(new_owner), new_deposit);
+
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(new_owner),
+			server_id,
+			ActionType::UpdateMetadata(None)
+		));
+
+		System::assert_has_event(RuntimeEvent::Server(crate::Event::MetadataCleared {
+			server_id,
+			deposit: new_deposit,
+		}));
+
+		assert_eq!(Server::metadata_by_server(server_id), None);
+		assert_eq!(Server::metadata_deposit(server_id), None);
+		assert_eq!(Balances::reserved_balance(new_owner), 0);
+
+		assert_noop!(
+			Server::update_server(
+				RuntimeOrigin::signed(new_owner),
+				server_id,
+				ActionType::UpdateMetadata(None)
+			),
+			Error::<Test>::NotExists,
+		);
+	})
+}
+
+#[test]
+fn cant_update_metadata() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("alice");
+		let server_id = 0u64;
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(owner),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+
+		assert_noop!(
+			Server::update_server(
+				RuntimeOrigin::signed(account_key("bob")),
+				server_id,
+				ActionType::UpdateMetadata(Some(metadata_key("A", "")))
+			),
+			Error::<Test>::Unauthorized,
+		);
+		assert_noop!(
+			Server::update_server(
+				RuntimeOrigin::signed(owner),
+				server_id,
+				ActionType::UpdateMetadata(Some(metadata_key("Myriad", "")))
+			),
+			Error::<Test>::InsufficientBalance,
+		);
+	})
+}
+
+#[test]
+fn unregister_returns_metadata_deposit() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("john");
+		let server_id = 0u64;
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(owner),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(owner),
+			server_id,
+			ActionType::UpdateMetadata(Some(metadata_key("A", "")))
+		));
+		assert_ok!(Server::unregister(RuntimeOrigin::signed(owner), server_id));
+
+		System::set_block_number(11);
+
+		<Server as OnInitialize<u64>>::on_initialize(11);
+
+		assert_eq!(Server::metadata_by_server(server_id), None);
+		assert_eq!(Server::metadata_deposit(server_id), None);
+		assert_eq!(Balances::reserved_balance(owner), 0);
+		assert_eq!(Balances::free_balance(owner), 30);
+	})
+}
+
+#[test]
+fn delegate_and_undelegate_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("alice");
+		let delegator = account_key("bob");
+		let server_id = 0u64;
+		let server_account_id = Server::server_account_id(server_id);
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(owner),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+		assert_ok!(Server::delegate(RuntimeOrigin::signed(delegator), server_id, 5));
+
+		System::assert_last_event(RuntimeEvent::Server(crate::Event::Delegated(
+			delegator, server_id, 5,
+		)));
+
+		let delegation = Server::delegation_by_server(server_id, delegator).unwrap();
+
+		assert_eq!(delegation.get_bonded(), 5);
+		assert_eq!(Server::backing(server_id).get_delegated(), 5);
+		assert_eq!(Server::backing(server_id).get_delegator_count(), 1);
+		assert_eq!(<Server as DelegationInterface<Test>>::total_backing(server_id), 8);
+		assert_eq!(Balances::free_balance(delegator), 15);
+		assert_eq!(Balances::free_balance(server_account_id), 8);
+
+		assert_ok!(Server::undelegate(RuntimeOrigin::signed(delegator), server_id, 3));
+
+		System::assert_last_event(RuntimeEvent::Server(crate::Event::Scheduled {
+			server_id,
+			when: 11,
+			task: b"Unbonded".to_vec(),
+			status: Status::InProgress,
+		}));
+
+		let delegation = Server::delegation_by_server(server_id, delegator).unwrap();
+
+		assert_eq!(delegation.get_bonded(), 2);
+		assert_eq!(delegation.get_unbonding(), 3);
+		assert_eq!(delegation.get_unbonded_at(), Some(11));
+		assert_eq!(Server::backing(server_id).get_delegated(), 2);
+		assert_eq!(Server::unbonding_tasks(11).into_inner(), vec![(server_id, delegator)]);
+
+		System::set_block_number(11);
+
+		<Server as OnInitialize<u64>>::on_initialize(11);
+
+		System::assert_has_event(RuntimeEvent::Server(crate::Event::Unbonded(
+			delegator, server_id, 3,
+		)));
+
+		let delegation = Server::delegation_by_server(server_id, delegator).unwrap();
+
+		assert_eq!(delegation.get_unbonding(), 0);
+		assert_eq!(delegation.get_unbonded_at(), None);
+		assert_eq!(Balances::free_balance(delegator), 18);
+
+		assert_ok!(Server::undelegate(RuntimeOrigin::signed(delegator), server_id, 2));
+
+		System::set_block_number(21);
+
+		<Server as OnInitialize<u64>>::on_initialize(21);
+
+		assert_eq!(Server::delegation_by_server(server_id, delegator), None);
+		assert_eq!(Server::backing(server_id).get_delegated(), 0);
+		assert_eq!(Server::backing(server_id).get_delegator_count(), 0);
+		assert_eq!(Balances::free_balance(delegator), 20);
+		assert_eq!(Balances::free_balance(server_account_id), 3);
+	})
+}
+
+#[test]
+fn undelegate_moves_unbonding_to_latest_block() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let delegator = account_key("bob");
+		let server_id = 0u64;
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(account_key("alice")),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+		assert_ok!(Server::delegate(RuntimeOrigin::signed(delegator), server_id, 6));
+		assert_ok!(Server::undelegate(RuntimeOrigin::signed(delegator), server_id, 2));
+
+		System::set_block_number(5);
+
+		assert_ok!(Server::undelegate(RuntimeOrigin::signed(delegator), server_id, 2));
+
+		let delegation = Server::delegation_by_server(server_id, delegator).unwrap();
+
+		assert_eq!(delegation.get_unbonding(), 4);
+		assert_eq!(delegation.get_unbonded_at(), Some(15));
+		assert!(Server::unbonding_tasks(11).is_empty());
+		assert_eq!(Server::unbonding_tasks(15).into_inner(), vec![(server_id, delegator)]);
+
+		System::set_block_number(11);
+
+		<Server as OnInitialize<u64>>::on_initialize(11);
+
+		assert_eq!(Balances::free_balance(delegator), 14);
+
+		System::set_block_number(15);
+
+		<Server as OnInitialize<u64>>::on_initialize(15);
+
+		assert_eq!(Balances::free_balance(delegator), 18);
+	})
+}
+
+#[test]
+fn cant_delegate_or_undelegate() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("alice");
+		let server_id = 0u64;
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(owner),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+
+		assert_noop!(
+			Server::delegate(RuntimeOrigin::signed(account_key("bob")), 1, 2),
+			Error::<Test>::NotExists,
+		);
+		assert_noop!(
+			Server::delegate(RuntimeOrigin::signed(account_key("bob")), server_id, 1),
+			Error::<Test>::BelowMinimumDelegation,
+		);
+
+		assert_ok!(Server::delegate(RuntimeOrigin::signed(account_key("bob")), server_id, 2));
+		assert_ok!(Server::delegate(RuntimeOrigin::signed(account_key("john")), server_id, 2));
+
+		assert_noop!(
+			Server::delegate(RuntimeOrigin::signed(account_key("satoshi")), server_id, 2),
+			Error::<Test>::TooManyDelegators,
+		);
+		assert_noop!(
+			Server::undelegate(RuntimeOrigin::signed(owner), server_id, 2),
+			Error::<Test>::NotExists,
+		);
+		assert_noop!(
+			Server::undelegate(RuntimeOrigin::signed(account_key("bob")), server_id, 3),
+			Error::<Test>::InsufficientBalance,
+		);
+		assert_noop!(
+			Server::undelegate(RuntimeOrigin::signed(account_key("bob")), server_id, 1),
+			Error::<Test>::BelowMinimumDelegation,
+		);
+
+		assert_ok!(Server::unregister(RuntimeOrigin::signed(owner), server_id));
+
+		assert_noop!(
+			Server::delegate(RuntimeOrigin::signed(account_key("bob")), server_id, 2),
+			Error::<Test>::WaitingToUnstaked,
+		);
+	})
+}
+
+#[test]
+fn unregister_repays_delegators() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("alice");
+		let delegator = account_key("bob");
+		let server_id = 0u64;
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(owner),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+		assert_ok!(Server::delegate(RuntimeOrigin::signed(delegator), server_id, 4));
+		assert_ok!(Server::undelegate(RuntimeOrigin::signed(delegator), server_id, 2));
+		assert_ok!(Server::unregister(RuntimeOrigin::signed(owner), server_id));
+
+		System::set_block_number(11);
+
+		<Server as OnInitialize<u64>>::on_initialize(11);
+
+		System::assert_has_event(RuntimeEvent::Server(crate::Event::Unbonded(
+			delegator, server_id, 4,
+		)));
+		System::assert_has_event(RuntimeEvent::Server(crate::Event::Unstaked(owner, server_id, 3)));
+
+		assert_eq!(Server::delegation_by_server(server_id, delegator), None);
+		assert_eq!(Server::backing(server_id).get_delegator_count(), 0);
+		assert_eq!(Balances::free_balance(delegator), 20);
+		assert_eq!(Balances::free_balance(owner), 10);
+		assert_eq!(Balances::free_balance(Server::server_account_id(server_id)), 0);
+	})
+}
+
+#[test]
+fn era_rewards_split_by_stake_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("alice");
+		let delegator = account_key("bob");
+		let server_id = 0u64;
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(owner),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+		assert_ok!(Server::delegate(RuntimeOrigin::signed(delegator), server_id, 9));
+
+		assert_eq!(Server::total_stake(), 12);
+
+		System::set_block_number(50);
+
+		<Server as OnInitialize<u64>>::on_initialize(50);
+
+		System::assert_last_event(RuntimeEvent::Server(crate::Event::EraPaid {
+			era: 0,
+			reward: 12,
+			total_stake: 12,
+		}));
+
+		assert_eq!(Server::current_era(), 1);
+		assert_eq!(Server::eras_reward(0), Some(pallet_server::EraReward::new(12, 12)));
+		assert_eq!(Balances::free_balance(Server::reward_pot_account_id()), 12);
+
+		assert_ok!(Server::claim_reward(RuntimeOrigin::signed(owner), server_id));
+
+		System::assert_last_event(RuntimeEvent::Server(crate::Event::RewardClaimed {
+			server_id,
+			account_id: owner,
+			amount: 3,
+		}));
+
+		assert_ok!(Server::claim_reward(RuntimeOrigin::signed(delegator), server_id));
+
+		assert_eq!(Balances::free_balance(owner), 10);
+		assert_eq!(Balances::free_balance(delegator), 20);
+		assert_eq!(Balances::free_balance(Server::reward_pot_account_id()), 0);
+
+		// Only the last `MaxPayoutHistory` eras are kept
+		System::set_block_number(100);
+
+		<Server as OnInitialize<u64>>::on_initialize(100);
+
+		System::set_block_number(150);
+
+		<Server as OnInitialize<u64>>::on_initialize(150);
+
+		assert_eq!(Server::current_era(), 3);
+		assert_eq!(Server::eras_reward(0), None);
+		assert!(Server::eras_reward(1).is_some());
+		assert!(Server::eras_reward(2).is_some());
+	})
+}
+
+#[test]
+fn rewards_are_settled_when_stake_changes() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("john");
+		let new_owner = account_key("bob");
+		let server_id = 0u64;
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(owner),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+
+		System::set_block_number(50);
+
+		<Server as OnInitialize<u64>>::on_initialize(50);
+
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(owner),
+			server_id,
+			ActionType::TransferOwner(new_owner)
+		));
+
+		let ledger = Server::reward_ledger(server_id, owner).unwrap();
+
+		assert_eq!(ledger.get_stake(), 0);
+		assert_eq!(ledger.get_unclaimed(), 12);
+		assert_eq!(Server::reward_ledger(server_id, new_owner).unwrap().get_stake(), 3);
+
+		System::set_block_number(100);
+
+		<Server as OnInitialize<u64>>::on_initialize(100);
+
+		assert_ok!(Server::claim_reward(RuntimeOrigin::signed(owner), server_id));
+		assert_ok!(Server::claim_reward(RuntimeOrigin::signed(new_owner), server_id));
+
+		assert_eq!(Server::reward_ledger(server_id, owner), None);
+		assert_eq!(Balances::free_balance(owner), 39);
+		assert_eq!(Balances::free_balance(new_owner), 32);
+	})
+}
+
+#[test]
+fn cant_claim_reward() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("alice");
+		let server_id = 0u64;
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(owner),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+
+		assert_noop!(
+			Server::claim_reward(RuntimeOrigin::signed(account_key("bob")), server_id),
+			Error::<Test>::NotExists,
+		);
+		assert_noop!(
+			Server::claim_reward(RuntimeOrigin::signed(owner), server_id),
+			Error::<Test>::NotExists,
+		);
+	})
+}
+
+#[test]
+fn report_and_slash_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("john");
+		let delegator = account_key("bob");
+		let reporter = account_key("alice");
+		let server_id = 0u64;
+		let evidence = H256::repeat_byte(1);
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(owner),
+			api_url_key("https://api.dev.myriad.social"),
+			Some(10)
+		));
+		assert_ok!(Server::delegate(RuntimeOrigin::signed(delegator), server_id, 10));
+		assert_ok!(Server::undelegate(RuntimeOrigin::signed(delegator), server_id, 4));
+		assert_ok!(Server::report(RuntimeOrigin::signed(reporter), server_id, evidence));
+
+		let report = pallet_server::Report::new(server_id, reporter, evidence, 2);
+
+		System::assert_last_event(RuntimeEvent::Server(crate::Event::Reported {
+			report_id: 0,
+			report: report.clone(),
+		}));
+
+		assert_eq!(Server::report_by_id(0), Some(report));
+		assert_eq!(Server::report_count(), 1);
+		assert_eq!(Balances::reserved_balance(reporter), 2);
+
+		assert_ok!(Server::slash(RuntimeOrigin::root(), 0, Permill::from_percent(50)));
+
+		System::assert_last_event(RuntimeEvent::Server(crate::Event::Slashed {
+			report_id: 0,
+			server_id,
+			amount: 10,
+			reporter_reward: 5,
+		}));
+
+		let delegation = Server::delegation_by_server(server_id, delegator).unwrap();
+
+		assert_eq!(Server::report_by_id(0), None);
+		assert_eq!(Server::server_by_id(server_id).unwrap().get_stake_amount(), &5);
+		assert_eq!(Server::server_by_owner(owner, server_id).unwrap().get_stake_amount(), &5);
+		assert_eq!(delegation.get_bonded(), 3);
+		assert_eq!(delegation.get_unbonding(), 2);
+		assert_eq!(Server::backing(server_id).get_delegated(), 3);
+		assert_eq!(Server::total_stake(), 8);
+		assert_eq!(Balances::free_balance(Server::server_account_id(server_id)), 10);
+		assert_eq!(Balances::reserved_balance(reporter), 0);
+		assert_eq!(Balances::free_balance(reporter), 15);
+		assert_eq!(Balances::free_balance(account_key("treasury")), 5);
+	})
+}
+
+#[test]
+fn reject_report_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let reporter = account_key("alice");
+		let evidence = H256::repeat_byte(1);
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(account_key("john")),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+		assert_ok!(Server::report(RuntimeOrigin::signed(reporter), 0, evidence));
+		assert_ok!(Server::reject_report(RuntimeOrigin::root(), 0));
+
+		System::assert_last_event(RuntimeEvent::Server(crate::Event::ReportRejected {
+			report_id: 0,
+			report: pallet_server::Report::new(0, reporter, evidence, 2),
+		}));
+
+		assert_eq!(Server::report_by_id(0), None);
+		assert_eq!(Balances::reserved_balance(reporter), 0);
+		assert_eq!(Balances::free_balance(reporter), 8);
+		assert_eq!(Balances::free_balance(account_key("treasury")), 2);
+		assert_eq!(Server::server_by_id(0).unwrap().get_stake_amount(), &3);
+	})
+}
+
+#[test]
+fn cant_report_or_slash() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let evidence = H256::repeat_byte(1);
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(account_key("john")),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+
+		assert_noop!(
+			Server::report(RuntimeOrigin::signed(account_key("alice")), 1, evidence),
+			Error::<Test>::NotExists,
+		);
+		assert_noop!(
+			Server::report(RuntimeOrigin::signed(account_key("nobody")), 0, evidence),
+			Error::<Test>::InsufficientBalance,
+		);
+
+		assert_ok!(Server::report(RuntimeOrigin::signed(account_key("alice")), 0, evidence));
+
+		assert_noop!(
+			Server::slash(RuntimeOrigin::signed(account_key("alice")), 0, Permill::one()),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Server::slash(RuntimeOrigin::root(), 0, Permill::from_percent(51)),
+			Error::<Test>::SlashFractionTooHigh,
+		);
+		assert_noop!(
+			Server::slash(RuntimeOrigin::root(), 1, Permill::from_percent(50)),
+			Error::<Test>::NotExists,
+		);
+		assert_noop!(Server::reject_report(RuntimeOrigin::root(), 1), Error::<Test>::NotExists);
+	})
+}
+
+#[test]
+fn cant_slash_same_offence_twice() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let evidence = H256::repeat_byte(1);
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(account_key("john")),
+			api_url_key("https://api.dev.myriad.social"),
+			Some(10)
+		));
+		assert_ok!(Server::report(RuntimeOrigin::signed(account_key("alice")), 0, evidence));
+		assert_ok!(Server::report(RuntimeOrigin::signed(account_key("bob")), 0, evidence));
+		assert_ok!(Server::slash(RuntimeOrigin::root(), 0, Permill::from_percent(50)));
+
+		assert_eq!(Server::slashed_offence(0, evidence), Some(1));
+
+		assert_noop!(
+			Server::slash(RuntimeOrigin::root(), 1, Permill::from_percent(50)),
+			Error::<Test>::AlreadySlashed,
+		);
+		assert_noop!(
+			Server::report(RuntimeOrigin::signed(account_key("alice")), 0, evidence),
+			Error::<Test>::AlreadySlashed,
+		);
+
+		assert_ok!(Server::reject_report(RuntimeOrigin::root(), 1));
+		assert_ok!(Server::report(
+			RuntimeOrigin::signed(account_key("alice")),
+			0,
+			H256::repeat_byte(2)
+		));
+		assert_ok!(Server::slash(RuntimeOrigin::root(), 2, Permill::from_percent(50)));
+
+		assert_eq!(Server::server_by_id(0).unwrap().get_stake_amount(), &3);
+	})
+}
+
+#[test]
+fn heartbeat_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("john");
+		let owner_origin = RuntimeOrigin::signed(owner);
+		let key = UintAuthorityId(1);
+
+		assert_ok!(Server::register(
+			owner_origin.clone(),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+		assert_ok!(Server::update_server(
+			owner_origin,
+			0,
+			ActionType::UpdateHeartbeatKey(Some(key.clone()))
+		));
+
+		assert_eq!(Server::heartbeat_key_by_server(0), Some(key.clone()));
+		assert_eq!(Server::server_by_heartbeat_key(key.clone()), Some(0));
+		assert_eq!(Server::next_liveness_check(0), Some(21));
+		assert_eq!(Server::liveness_tasks(21).into_inner(), vec![0]);
+
+		System::set_block_number(6);
+
+		let heartbeat = pallet_server::Heartbeat::new(0, 6);
+		let signature = key.sign(&heartbeat.encode()).unwrap();
+		let forged_signature = UintAuthorityId(2).sign(&heartbeat.encode()).unwrap();
+
+		assert_eq!(
+			Server::validate_unsigned(
+				TransactionSource::External,
+				&crate::Call::heartbeat {
+					heartbeat: heartbeat.clone(),
+					signature: forged_signature
+				},
+			),
+			InvalidTransaction::BadProof.into(),
+		);
+		assert_ok!(Server::validate_unsigned(
+			TransactionSource::External,
+			&crate::Call::heartbeat { heartbeat: heartbeat.clone(), signature: signature.clone() },
+		));
+
+		assert_ok!(Server::heartbeat(RuntimeOrigin::none(), heartbeat.clone(), signature.clone()));
+
+		System::assert_last_event(RuntimeEvent::Server(crate::Event::HeartbeatReceived {
+			server_id: 0,
+			block_number: 6,
+		}));
+
+		assert_eq!(Server::last_heartbeat(0), Some(6));
+		assert_eq!(Server::next_liveness_check(0), Some(26));
+		assert!(Server::liveness_tasks(21).is_empty());
+		assert_eq!(Server::liveness_tasks(26).into_inner(), vec![0]);
+
+		// One heartbeat every `HeartbeatInterval`
+		System::set_block_number(10);
+
+		assert_eq!(
+			Server::validate_unsigned(
+				TransactionSource::External,
+				&crate::Call::heartbeat {
+					heartbeat: heartbeat.clone(),
+					signature: signature.clone()
+				},
+			),
+			InvalidTransaction::Stale.into(),
+		);
+		assert_noop!(
+			Server::heartbeat(RuntimeOrigin::none(), heartbeat, signature),
+			Error::<Test>::HeartbeatNotDue,
+		);
+	})
+}
+
+#[test]
+fn server_without_heartbeat_is_flagged_inactive() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("john");
+		let owner_origin = RuntimeOrigin::signed(owner);
+		let key = UintAuthorityId(1);
+
+		assert_ok!(Server::register(
+			owner_origin.clone(),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+		assert_ok!(Server::update_server(
+			owner_origin.clone(),
+			0,
+			ActionType::UpdateHeartbeatKey(Some(key.clone()))
+		));
+
+		System::set_block_number(21);
+		Server::on_initialize(21);
+
+		System::assert_last_event(RuntimeEvent::Server(crate::Event::ServerInactive {
+			server_id: 0,
+			last_heartbeat: None,
+		}));
+
+		assert_eq!(Server::inactive_since(0), Some(21));
+		assert_eq!(Server::next_liveness_check(0), None);
+
+		System::set_block_number(22);
+
+		let heartbeat = pallet_server::Heartbeat::new(0, 22);
+		let signature = key.sign(&heartbeat.encode()).unwrap();
+
+		assert_ok!(Server::heartbeat(RuntimeOrigin::none(), heartbeat, signature));
+
+		System::assert_has_event(RuntimeEvent::Server(crate::Event::ServerActive { server_id: 0 }));
+
+		assert_eq!(Server::inactive_since(0), None);
+		assert_eq!(Server::next_liveness_check(0), Some(42));
+
+		// Removing the key stops tracking the server liveness
+		assert_ok!(Server::update_server(owner_origin, 0, ActionType::UpdateHeartbeatKey(None)));
+
+		assert_eq!(Server::heartbeat_key_by_server(0), None);
+		assert_eq!(Server::server_by_heartbeat_key(key), None);
+		assert_eq!(Server::last_heartbeat(0), None);
+		assert_eq!(Server::next_liveness_check(0), None);
+		assert!(Server::liveness_tasks(42).is_empty());
+	})
+}
+
+#[test]
+fn cant_set_heartbeat_key_or_send_heartbeat() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let key = UintAuthorityId(1);
+
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(account_key("john")),
+			api_url_key("https://api.dev.myriad.social"),
+			None
+		));
+		assert_ok!(Server::register(
+			RuntimeOrigin::signed(account_key("bob")),
+			api_url_key("https://api.testnet.myriad.social"),
+			None
+		));
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(account_key("john")),
+			0,
+			ActionType::UpdateHeartbeatKey(Some(key.clone()))
+		));
+
+		assert_noop!(
+			Server::update_server(
+				RuntimeOrigin::signed(account_key("bob")),
+				1,
+				ActionType::UpdateHeartbeatKey(Some(key.clone()))
+			),
+			Error::<Test>::AlreadyExists,
+		);
+
+		let heartbeat = pallet_server::Heartbeat::new(1, 1);
+		let signature = key.sign(&heartbeat.encode()).unwrap();
+
+		assert_eq!(
+			Server::validate_unsigned(
+				TransactionSource::External,
+				&crate::Call::heartbeat {
+					heartbeat: heartbeat.clone(),
+					signature: signature.clone()
+				},
+			),
+			InvalidTransaction::BadSigner.into(),
+		);
+		assert_noop!(
+			Server::heartbeat(
+				RuntimeOrigin::signed(account_key("bob")),
+				heartbeat.clone(),
+				signature.clone()
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Server::heartbeat(RuntimeOrigin::none(), heartbeat, signature),
+			Error::<Test>::NotExists,
+		);
+	})
+}
+
+#[test]
+fn offchain_worker_sends_heartbeat() {
+	let mut ext = <ExternalityBuilder>::default().existential_deposit(1).build();
+	let (offchain, offchain_state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		let owner_origin = RuntimeOrigin::signed(account_key("john"));
+		let key = UintAuthorityId(1);
+
+		assert_ok!(Server::register(
+			owner_origin.clone(),
+			api_url_key("http://localhost:3001"),
+			None
+		));
+		assert_ok!(Server::update_server(
+			owner_origin,
+			0,
+			ActionType::UpdateHeartbeatKey(Some(key.clone()))
+		));
+
+		// A local stand-in for the server api
+		offchain_state.write().expect_request(PendingRequest {
+			method: "GET".into(),
+			uri: "http://localhost:3001".into(),
+			response: Some(b"OK".to_vec()),
+			sent: true,
+			..Default::default()
+		});
+
+		UintAuthorityId::set_all_keys(vec![1]);
+		System::set_block_number(2);
+		Server::offchain_worker(2);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = UncheckedExtrinsic::decode(&mut &*tx).unwrap();
+		let heartbeat = pallet_server::Heartbeat::new(0, 2);
+		let signature = key.sign(&heartbeat.encode()).unwrap();
+
+		assert_eq!(tx.signature, None);
+		assert_eq!(
+			tx.function,
+			RuntimeCall::Server(crate::Call::heartbeat { heartbeat, signature })
+		);
+
+		// Nothing is sent again until the next interval
+		System::set_block_number(3);
+		Server::offchain_worker(3);
+
+		assert!(pool_state.read().transactions.is_empty());
+	})
+}
+
+#[test]
+fn offchain_worker_only_requests_http_urls() {
+	let mut ext = <ExternalityBuilder>::default().existential_deposit(1).build();
+	let (offchain, _) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		let owner_origin = RuntimeOrigin::signed(account_key("john"));
+		let key = UintAuthorityId(1);
+
+		assert_ok!(Server::register(owner_origin.clone(), api_url_key("file:///etc/hosts"), None));
+		assert_ok!(Server::update_server(
+			owner_origin,
+			0,
+			ActionType::UpdateHeartbeatKey(Some(key.clone()))
+		));
+
+		UintAuthorityId::set_all_keys(vec![1]);
+		System::set_block_number(2);
+
+		// No request is expected, the test offchain extension panics on an unexpected one
+		assert_eq!(Server::send_heartbeat(key, 2), Err(OffchainErr::InvalidApiUrl));
+		assert!(pool_state.read().transactions.is_empty());
+	})
+}
@@ -1,28 +1,36 @@
 use super::*;
-use frame_support::{pallet_prelude::*, sp_runtime::traits::Saturating, traits::Currency};
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::{
+		traits::{Saturating, Zero},
+		RuntimeAppPublic,
+	},
+	traits::Currency,
+};
 use frame_system::pallet_prelude::BlockNumberFor;
 use scale_info::TypeInfo;
 use sp_std::vec::Vec;
 
-#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo)]
-pub struct Server<AccountId, Balance, BlockNumber> {
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Server<AccountId, Balance, BlockNumber, ApiUrl = Vec<u8>> {
 	id: u64,
 	owner: AccountId,
-	api_url: Vec<u8>,
+	api_url: ApiUrl,
 	staked_amount: Balance,
 	unstaked_at: Option<BlockNumber>,
 }
-impl<AccountId, Balance, BlockNumber> Server<AccountId, Balance, BlockNumber>
+impl<AccountId, Balance, BlockNumber, ApiUrl> Server<AccountId, Balance, BlockNumber, ApiUrl>
 where
 	AccountId: Clone + PartialEq + Eq,
 	Balance: Copy + Saturating,
 	BlockNumber: Copy,
+	ApiUrl: Clone,
 {
-	pub fn new(id: u64, owner: &AccountId, api_url: &[u8], staked_amount: Balance) -> Self {
+	pub fn new(id: u64, owner: &AccountId, api_url: &ApiUrl, staked_amount: Balance) -> Self {
 		Self {
 			id,
 			owner: owner.clone(),
-			api_url: api_url.to_vec(),
+			api_url: api_url.clone(),
 			staked_amount,
 			unstaked_at: None,
 		}
@@ -49,7 +57,7 @@ where
 		&self.owner
 	}
 
-	pub fn get_api_url(&self) -> &Vec<u8> {
+	pub fn get_api_url(&self) -> &ApiUrl {
 		&self.api_url
 	}
 
@@ -67,8 +75,8 @@ where
 		self
 	}
 
-	pub fn set_api_url(mut self, api_url: &[u8]) -> Self {
-		self.api_url = api_url.to_vec();
+	pub fn set_api_url(mut self, api_url: &ApiUrl) -> Self {
+		self.api_url = api_url.clone();
 		self
 	}
 
@@ -93,12 +101,14 @@ where
 	}
 }
 
-impl<T, AccountId, Balance, BlockNumber> ServerInfo<T> for Server<AccountId, Balance, BlockNumber>
+impl<T, AccountId, Balance, BlockNumber, S> ServerInfo<T>
+	for Server<AccountId, Balance, BlockNumber, BoundedVec<u8, S>>
 where
-	T: frame_system::Config<AccountId = AccountId>,
+	T: frame_system::Config<AccountId = AccountId, BlockNumber = BlockNumber>,
 	AccountId: Clone + PartialEq + Eq,
 	Balance: Copy + Saturating,
 	BlockNumber: Copy,
+	S: Get<u32>,
 {
 	fn get_id(&self) -> u64 {
 		self.get_id()
@@ -111,14 +121,270 @@ where
 	fn get_api_url(&self) -> &Vec<u8> {
 		self.get_api_url()
 	}
+
+	fn get_unstaked_at(&self) -> Option<BlockNumber> {
+		self.get_unstaked_at()
+	}
+}
+
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct ServerMetadata<Hash, Field, Description, Url> {
+	name: Field,
+	description: Description,
+	web_url: Url,
+	region: Field,
+	api_version: Field,
+	logo: Option<Hash>,
+}
+impl<Hash, Field, Description, Url> ServerMetadata<Hash, Field, Description, Url> {
+	pub fn new(
+		name: Field,
+		description: Description,
+		web_url: Url,
+		region: Field,
+		api_version: Field,
+		logo: Option<Hash>,
+	) -> Self {
+		Self { name, description, web_url, region, api_version, logo }
+	}
+
+	pub fn get_name(&self) -> &Field {
+		&self.name
+	}
+
+	pub fn get_description(&self) -> &Description {
+		&self.description
+	}
+
+	pub fn get_web_url(&self) -> &Url {
+		&self.web_url
+	}
+
+	pub fn get_region(&self) -> &Field {
+		&self.region
+	}
+
+	pub fn get_api_version(&self) -> &Field {
+		&self.api_version
+	}
+
+	pub fn get_logo(&self) -> &Option<Hash> {
+		&self.logo
+	}
+}
+
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Delegation<Balance, BlockNumber> {
+	bonded: Balance,
+	unbonding: Balance,
+	unbonded_at: Option<BlockNumber>,
+}
+impl<Balance, BlockNumber> Delegation<Balance, BlockNumber>
+where
+	Balance: Copy + Saturating + Zero,
+	BlockNumber: Copy,
+{
+	pub fn get_bonded(&self) -> Balance {
+		self.bonded
+	}
+
+	pub fn get_unbonding(&self) -> Balance {
+		self.unbonding
+	}
+
+	pub fn get_unbonded_at(&self) -> Option<BlockNumber> {
+		self.unbonded_at
+	}
+
+	/// Bonded and unbonding, everything the server account holds for the delegator.
+	pub fn total(&self) -> Balance {
+		self.bonded.saturating_add(self.unbonding)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.total().is_zero()
+	}
+
+	pub fn bond(&mut self, amount: Balance) {
+		self.bonded = self.bonded.saturating_add(amount);
+	}
+
+	/// Moves the amount to unbonding, every unbonding amount is released at the latest block.
+	pub fn unbond(&mut self, amount: Balance, unbonded_at: BlockNumber) {
+		self.bonded = self.bonded.saturating_sub(amount);
+		self.unbonding = self.unbonding.saturating_add(amount);
+		self.unbonded_at = Some(unbonded_at);
+	}
+
+	pub fn slash(&mut self, bonded: Balance, unbonding: Balance) {
+		self.bonded = self.bonded.saturating_sub(bonded);
+		self.unbonding = self.unbonding.saturating_sub(unbonding);
+	}
+
+	pub fn release(&mut self) -> Balance {
+		let unbonding = self.unbonding;
+
+		self.unbonding = Zero::zero();
+		self.unbonded_at = None;
+
+		unbonding
+	}
+}
+
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Backing<Balance> {
+	delegated: Balance,
+	delegator_count: u32,
+}
+impl<Balance: Copy + Saturating> Backing<Balance> {
+	pub fn get_delegated(&self) -> Balance {
+		self.delegated
+	}
+
+	pub fn get_delegator_count(&self) -> u32 {
+		self.delegator_count
+	}
+
+	pub fn add(&mut self, amount: Balance, new_delegator: bool) {
+		self.delegated = self.delegated.saturating_add(amount);
+
+		if new_delegator {
+			self.delegator_count = self.delegator_count.saturating_add(1);
+		}
+	}
+
+	pub fn remove(&mut self, amount: Balance, left: bool) {
+		self.delegated = self.delegated.saturating_sub(amount);
+
+		if left {
+			self.delegator_count = self.delegator_count.saturating_sub(1);
+		}
+	}
+}
+
+/// Stake earning era rewards on a server, with the rewards settled so far.
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct RewardLedger<Balance> {
+	stake: Balance,
+	reward_debt: Balance,
+	unclaimed: Balance,
+}
+impl<Balance: Copy + Saturating + Zero> RewardLedger<Balance> {
+	pub fn get_stake(&self) -> Balance {
+		self.stake
+	}
+
+	pub fn get_unclaimed(&self) -> Balance {
+		self.unclaimed
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.stake.is_zero() && self.unclaimed.is_zero()
+	}
+
+	/// Adds the rewards accumulated by the stake since it was last set.
+	pub fn settle(&mut self, accumulated: Balance) {
+		let reward = accumulated.saturating_sub(self.reward_debt);
+
+		self.unclaimed = self.unclaimed.saturating_add(reward);
+		self.reward_debt = accumulated;
+	}
+
+	/// Sets the stake along with the rewards it has already accumulated, which it doesn't earn.
+	pub fn set_stake(&mut self, stake: Balance, reward_debt: Balance) {
+		self.stake = stake;
+		self.reward_debt = reward_debt;
+	}
+
+	pub fn take_unclaimed(&mut self) -> Balance {
+		let unclaimed = self.unclaimed;
+
+		self.unclaimed = Zero::zero();
+
+		unclaimed
+	}
+}
+
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct EraReward<Balance> {
+	reward: Balance,
+	total_stake: Balance,
+}
+impl<Balance: Copy> EraReward<Balance> {
+	pub fn new(reward: Balance, total_stake: Balance) -> Self {
+		Self { reward, total_stake }
+	}
+
+	pub fn get_reward(&self) -> Balance {
+		self.reward
+	}
+
+	pub fn get_total_stake(&self) -> Balance {
+		self.total_stake
+	}
+}
+
+/// A misbehavior report against a server, with a hash of the evidence kept off-chain.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Report<AccountId, Balance, Hash> {
+	server_id: ServerId,
+	reporter: AccountId,
+	evidence: Hash,
+	deposit: Balance,
+}
+impl<AccountId, Balance, Hash> Report<AccountId, Balance, Hash> {
+	pub fn new(server_id: ServerId, reporter: AccountId, evidence: Hash, deposit: Balance) -> Self {
+		Self { server_id, reporter, evidence, deposit }
+	}
+
+	pub fn get_server_id(&self) -> ServerId {
+		self.server_id
+	}
+
+	pub fn get_reporter(&self) -> &AccountId {
+		&self.reporter
+	}
+
+	pub fn get_evidence(&self) -> &Hash {
+		&self.evidence
+	}
+
+	pub fn get_deposit(&self) -> &Balance {
+		&self.deposit
+	}
+}
+
+/// A proof of liveness, signed by the heartbeat key of the server.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Heartbeat<BlockNumber> {
+	server_id: ServerId,
+	block_number: BlockNumber,
+}
+impl<BlockNumber: Copy> Heartbeat<BlockNumber> {
+	pub fn new(server_id: ServerId, block_number: BlockNumber) -> Self {
+		Self { server_id, block_number }
+	}
+
+	pub fn get_server_id(&self) -> ServerId {
+		self.server_id
+	}
+
+	pub fn get_block_number(&self) -> BlockNumber {
+		self.block_number
+	}
 }
 
 #[derive(Encode, Decode, Clone, RuntimeDebug, TypeInfo, PartialEq, Eq)]
-pub enum ActionType<AccountId, Balance> {
+pub enum ActionType<AccountId, Balance, ApiUrl, Metadata, HeartbeatKey> {
 	TransferOwner(AccountId),
-	UpdateApiUrl(Vec<u8>),
+	UpdateApiUrl(ApiUrl),
 	StakeAmount(Balance),
 	UnstakeAmount(Balance),
+	UpdateSigningKey(Option<AccountId>),
+	/// Sets the metadata against a deposit, `None` clears it and returns the deposit.
+	UpdateMetadata(Option<Metadata>),
+	/// Sets the key the server signs heartbeats with, `None` stops tracking its liveness.
+	UpdateHeartbeatKey(Option<HeartbeatKey>),
 }
 
 #[derive(Encode, Decode, Clone, RuntimeDebug, TypeInfo, PartialEq, Eq)]
@@ -136,10 +402,32 @@ pub enum Action<Balance> {
 }
 
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
-pub type ServerOf<T> = Server<AccountIdOf<T>, BalanceOf<T>, BlockNumberFor<T>>;
+pub type HashOf<T> = <T as frame_system::Config>::Hash;
+pub type ServerOf<T> = Server<AccountIdOf<T>, BalanceOf<T>, BlockNumberFor<T>, ApiUrlOf<T>>;
 pub type ServerId = u64;
 pub type CurrencyOf<T> = <T as self::Config>::Currency;
 pub type BalanceOf<T> = <CurrencyOf<T> as Currency<AccountIdOf<T>>>::Balance;
 pub type ActionOf<T> = Action<BalanceOf<T>>;
-pub type ActionTypeOf<T> = ActionType<AccountIdOf<T>, BalanceOf<T>>;
+pub type ActionTypeOf<T> = ActionType<
+	AccountIdOf<T>,
+	BalanceOf<T>,
+	ApiUrlOf<T>,
+	ServerMetadataOf<T>,
+	<T as self::Config>::HeartbeatKey,
+>;
 pub type ApiUrl = Vec<u8>;
+pub type ApiUrlOf<T> = BoundedVec<u8, <T as self::Config>::MaxApiUrlLength>;
+pub type MetadataFieldOf<T> = BoundedVec<u8, <T as self::Config>::MaxMetadataFieldLength>;
+pub type DescriptionOf<T> = BoundedVec<u8, <T as self::Config>::MaxDescriptionLength>;
+pub type ServerMetadataOf<T> =
+	ServerMetadata<HashOf<T>, MetadataFieldOf<T>, DescriptionOf<T>, ApiUrlOf<T>>;
+pub type DelegationOf<T> = Delegation<BalanceOf<T>, BlockNumberFor<T>>;
+pub type BackingOf<T> = Backing<BalanceOf<T>>;
+pub type RewardLedgerOf<T> = RewardLedger<BalanceOf<T>>;
+pub type EraRewardOf<T> = EraReward<BalanceOf<T>>;
+pub type EraIndex = u32;
+pub type ReportOf<T> = Report<AccountIdOf<T>, BalanceOf<T>, HashOf<T>>;
+pub type ReportId = u64;
+pub type HeartbeatOf<T> = Heartbeat<BlockNumberFor<T>>;
+pub type HeartbeatSignatureOf<T> =
+	<<T as self::Config>::HeartbeatKey as RuntimeAppPublic>::Signature;
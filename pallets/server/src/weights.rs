@@ -31,9 +31,21 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn register(s: u32, ) -> Weight;
 	fn update_server(s: u32, ) -> Weight;
+	fn update_metadata() -> Weight;
+	fn update_heartbeat_key() -> Weight;
 	fn unregister() -> Weight;
 	fn cancel_unregister() -> Weight;
-	fn on_initialize_server() -> Weight;
+	fn delegate() -> Weight;
+	fn undelegate() -> Weight;
+	fn on_initialize_unbonding() -> Weight;
+	fn claim_reward() -> Weight;
+	fn on_initialize_era() -> Weight;
+	fn report() -> Weight;
+	fn slash(d: u32, ) -> Weight;
+	fn reject_report() -> Weight;
+	fn heartbeat() -> Weight;
+	fn on_initialize_liveness() -> Weight;
+	fn on_initialize_server(d: u32, ) -> Weight;
 }
 
 /// Weights for pallet_server using the Substrate node and recommended hardware.
@@ -45,29 +57,58 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: System Account (r:1 w:1)
 	// Storage: Server ServerById (r:0 w:1)
 	// Storage: Server ServerByOwner (r:0 w:1)
+	// Storage: Server DelegationByServer (r:1 w:0)
+	// Storage: Server RewardLedgers (r:1 w:1)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: Server TotalStake (r:1 w:1)
 	/// The range of component `s` is `[1, 100]`.
 	fn register(s: u32, ) -> Weight {
-		Weight::from_ref_time(157_000_000_u64)
+		Weight::from_ref_time(171_000_000_u64)
 			// Standard Error: 2_258
 			.saturating_add(Weight::from_ref_time(31_163_u64).saturating_mul(s as u64))
-			.saturating_add(T::DbWeight::get().reads(4_u64))
-			.saturating_add(T::DbWeight::get().writes(6_u64))
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
 	}
 	// Storage: Server ServerById (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
 	// Storage: Server ServerByOwner (r:0 w:1)
 	// Storage: Server ServerByApiUrl (r:2 w:2)
+	// Storage: Server DelegationByServer (r:2 w:0)
+	// Storage: Server RewardLedgers (r:2 w:2)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: Server TotalStake (r:1 w:1)
 	/// The range of component `s` is `[0, 2]`.
 	fn update_server(s: u32, ) -> Weight {
-		Weight::from_ref_time(87_000_000_u64)
+		Weight::from_ref_time(101_000_000_u64)
 			// Standard Error: 1_129_362
 			.saturating_add(Weight::from_ref_time(614_285_u64).saturating_mul(s as u64))
-			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads(8_u64))
 			.saturating_add(T::DbWeight::get().reads(1_u64.saturating_mul(s as u64)))
-			.saturating_add(T::DbWeight::get().writes(3_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64.saturating_mul(s as u64)))
 	}
 	// Storage: Server ServerById (r:1 w:1)
+	// Storage: Server MetadataDeposit (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Server MetadataByServer (r:0 w:1)
+	// Storage: Server ServerByOwner (r:0 w:1)
+	fn update_metadata() -> Weight {
+		Weight::from_ref_time(74_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	// Storage: Server ServerById (r:1 w:1)
+	// Storage: Server ServerByHeartbeatKey (r:1 w:2)
+	// Storage: Server NextLivenessCheck (r:1 w:1)
+	// Storage: Server LivenessTasks (r:2 w:2)
+	// Storage: Server HeartbeatKeyByServer (r:1 w:1)
+	// Storage: Server ServerByOwner (r:0 w:1)
+	fn update_heartbeat_key() -> Weight {
+		Weight::from_ref_time(61_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	// Storage: Server ServerById (r:1 w:1)
 	// Storage: Server Tasks (r:1 w:1)
 	// Storage: Server ServerByOwner (r:0 w:1)
 	fn unregister() -> Weight {
@@ -83,16 +124,143 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(3_u64))
 	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Server DelegationByServer (r:1 w:1)
+	// Storage: Server BackingByServer (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Server RewardLedgers (r:1 w:1)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: Server TotalStake (r:1 w:1)
+	fn delegate() -> Weight {
+		Weight::from_ref_time(79_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	// Storage: Server DelegationByServer (r:1 w:1)
+	// Storage: Server UnbondingTasks (r:2 w:2)
+	// Storage: Server BackingByServer (r:1 w:1)
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Server RewardLedgers (r:1 w:1)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: Server TotalStake (r:1 w:1)
+	fn undelegate() -> Weight {
+		Weight::from_ref_time(66_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	// Storage: Server Tasks (r:1 w:1)
+	// Storage: Server UnbondingTasks (r:1 w:1)
+	// Storage: Server DelegationByServer (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Server BackingByServer (r:1 w:1)
+	fn on_initialize_unbonding() -> Weight {
+		Weight::from_ref_time(71_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	// Storage: Server RewardLedgers (r:1 w:1)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	fn claim_reward() -> Weight {
+		Weight::from_ref_time(58_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	// Storage: Server CurrentEra (r:1 w:1)
+	// Storage: Server TotalStake (r:1 w:0)
+	// Storage: Tipping RewardPotBalance (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Server RewardPerStake (r:1 w:1)
+	// Storage: Server ErasReward (r:0 w:2)
+	fn on_initialize_era() -> Weight {
+		Weight::from_ref_time(63_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Server SlashedOffences (r:1 w:0)
+	// Storage: Server ReportCount (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Server ReportById (r:0 w:1)
+	fn report() -> Weight {
+		Weight::from_ref_time(52_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	// Storage: Server ReportById (r:1 w:1)
+	// Storage: Server SlashedOffences (r:1 w:1)
+	// Storage: Server ServerById (r:1 w:1)
+	// Storage: Server DelegationByServer (r:1 w:1)
+	// Storage: Server BackingByServer (r:1 w:1)
+	// Storage: Server RewardLedgers (r:1 w:1)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: Server TotalStake (r:1 w:1)
+	// Storage: System Account (r:4 w:4)
+	// Storage: Server ServerByOwner (r:0 w:1)
+	/// The range of component `d` is `[0, 100]`.
+	fn slash(d: u32, ) -> Weight {
+		Weight::from_ref_time(121_000_000_u64)
+			// Standard Error: 15_732
+			.saturating_add(Weight::from_ref_time(21_410_000_u64).saturating_mul(d as u64))
+			.saturating_add(T::DbWeight::get().reads(12_u64))
+			.saturating_add(T::DbWeight::get().reads(2_u64.saturating_mul(d as u64)))
+			.saturating_add(T::DbWeight::get().writes(12_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64.saturating_mul(d as u64)))
+	}
+	// Storage: Server ReportById (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn reject_report() -> Weight {
+		Weight::from_ref_time(52_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	// Storage: Server HeartbeatKeyByServer (r:1 w:0)
+	// Storage: Server LastHeartbeat (r:1 w:1)
+	// Storage: Server NextLivenessCheck (r:1 w:1)
+	// Storage: Server LivenessTasks (r:2 w:2)
+	// Storage: Server InactiveServers (r:0 w:1)
+	fn heartbeat() -> Weight {
+		Weight::from_ref_time(47_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	// Storage: Server LivenessTasks (r:1 w:1)
+	// Storage: Server NextLivenessCheck (r:1 w:1)
+	// Storage: Server LastHeartbeat (r:1 w:0)
+	// Storage: Server InactiveServers (r:0 w:1)
+	fn on_initialize_liveness() -> Weight {
+		Weight::from_ref_time(29_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
 	// Storage: Server Tasks (r:1 w:1)
 	// Storage: Server ServerById (r:1 w:1)
 	// Storage: Server ServerCount (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Server DelegationByServer (r:1 w:1)
+	// Storage: Server BackingByServer (r:0 w:1)
 	// Storage: Server ServerByOwner (r:0 w:1)
 	// Storage: Server ServerByApiUrl (r:0 w:1)
-	fn on_initialize_server() -> Weight {
-		Weight::from_ref_time(156_000_000_u64)
-			.saturating_add(T::DbWeight::get().reads(4_u64))
-			.saturating_add(T::DbWeight::get().writes(6_u64))
+	// Storage: Server MetadataDeposit (r:1 w:1)
+	// Storage: Server MetadataByServer (r:0 w:1)
+	// Storage: Server RewardLedgers (r:1 w:1)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: Server TotalStake (r:1 w:1)
+	// Storage: Server HeartbeatKeyByServer (r:1 w:1)
+	// Storage: Server ServerByHeartbeatKey (r:0 w:1)
+	// Storage: Server NextLivenessCheck (r:1 w:1)
+	// Storage: Server LivenessTasks (r:1 w:1)
+	// Storage: Server LastHeartbeat (r:0 w:1)
+	// Storage: Server InactiveServers (r:0 w:1)
+	/// The range of component `d` is `[0, 100]`.
+	fn on_initialize_server(d: u32, ) -> Weight {
+		Weight::from_ref_time(181_000_000_u64)
+			// Standard Error: 18_104
+			.saturating_add(Weight::from_ref_time(33_120_000_u64).saturating_mul(d as u64))
+			.saturating_add(T::DbWeight::get().reads(12_u64))
+			.saturating_add(T::DbWeight::get().reads(4_u64.saturating_mul(d as u64)))
+			.saturating_add(T::DbWeight::get().writes(18_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64.saturating_mul(d as u64)))
 	}
 }
 
@@ -104,29 +272,58 @@ impl WeightInfo for () {
 	// Storage: System Account (r:1 w:1)
 	// Storage: Server ServerById (r:0 w:1)
 	// Storage: Server ServerByOwner (r:0 w:1)
+	// Storage: Server DelegationByServer (r:1 w:0)
+	// Storage: Server RewardLedgers (r:1 w:1)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: Server TotalStake (r:1 w:1)
 	/// The range of component `s` is `[1, 100]`.
 	fn register(s: u32, ) -> Weight {
-		Weight::from_ref_time(157_000_000_u64)
+		Weight::from_ref_time(171_000_000_u64)
 			// Standard Error: 2_258
 			.saturating_add(Weight::from_ref_time(31_163_u64).saturating_mul(s as u64))
-			.saturating_add(RocksDbWeight::get().reads(4_u64))
-			.saturating_add(RocksDbWeight::get().writes(6_u64))
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
 	}
 	// Storage: Server ServerById (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
 	// Storage: Server ServerByOwner (r:0 w:1)
 	// Storage: Server ServerByApiUrl (r:2 w:2)
+	// Storage: Server DelegationByServer (r:2 w:0)
+	// Storage: Server RewardLedgers (r:2 w:2)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: Server TotalStake (r:1 w:1)
 	/// The range of component `s` is `[0, 2]`.
 	fn update_server(s: u32, ) -> Weight {
-		Weight::from_ref_time(87_000_000_u64)
+		Weight::from_ref_time(101_000_000_u64)
 			// Standard Error: 1_129_362
 			.saturating_add(Weight::from_ref_time(614_285_u64).saturating_mul(s as u64))
-			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
 			.saturating_add(RocksDbWeight::get().reads(1_u64.saturating_mul(s as u64)))
-			.saturating_add(RocksDbWeight::get().writes(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64.saturating_mul(s as u64)))
 	}
 	// Storage: Server ServerById (r:1 w:1)
+	// Storage: Server MetadataDeposit (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Server MetadataByServer (r:0 w:1)
+	// Storage: Server ServerByOwner (r:0 w:1)
+	fn update_metadata() -> Weight {
+		Weight::from_ref_time(74_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	// Storage: Server ServerById (r:1 w:1)
+	// Storage: Server ServerByHeartbeatKey (r:1 w:2)
+	// Storage: Server NextLivenessCheck (r:1 w:1)
+	// Storage: Server LivenessTasks (r:2 w:2)
+	// Storage: Server HeartbeatKeyByServer (r:1 w:1)
+	// Storage: Server ServerByOwner (r:0 w:1)
+	fn update_heartbeat_key() -> Weight {
+		Weight::from_ref_time(61_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	// Storage: Server ServerById (r:1 w:1)
 	// Storage: Server Tasks (r:1 w:1)
 	// Storage: Server ServerByOwner (r:0 w:1)
 	fn unregister() -> Weight {
@@ -142,15 +339,142 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(3_u64))
 	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Server DelegationByServer (r:1 w:1)
+	// Storage: Server BackingByServer (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Server RewardLedgers (r:1 w:1)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: Server TotalStake (r:1 w:1)
+	fn delegate() -> Weight {
+		Weight::from_ref_time(79_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	// Storage: Server DelegationByServer (r:1 w:1)
+	// Storage: Server UnbondingTasks (r:2 w:2)
+	// Storage: Server BackingByServer (r:1 w:1)
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Server RewardLedgers (r:1 w:1)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: Server TotalStake (r:1 w:1)
+	fn undelegate() -> Weight {
+		Weight::from_ref_time(66_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	// Storage: Server Tasks (r:1 w:1)
+	// Storage: Server UnbondingTasks (r:1 w:1)
+	// Storage: Server DelegationByServer (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Server BackingByServer (r:1 w:1)
+	fn on_initialize_unbonding() -> Weight {
+		Weight::from_ref_time(71_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	// Storage: Server RewardLedgers (r:1 w:1)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	fn claim_reward() -> Weight {
+		Weight::from_ref_time(58_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	// Storage: Server CurrentEra (r:1 w:1)
+	// Storage: Server TotalStake (r:1 w:0)
+	// Storage: Tipping RewardPotBalance (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Server RewardPerStake (r:1 w:1)
+	// Storage: Server ErasReward (r:0 w:2)
+	fn on_initialize_era() -> Weight {
+		Weight::from_ref_time(63_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Server SlashedOffences (r:1 w:0)
+	// Storage: Server ReportCount (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Server ReportById (r:0 w:1)
+	fn report() -> Weight {
+		Weight::from_ref_time(52_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	// Storage: Server ReportById (r:1 w:1)
+	// Storage: Server SlashedOffences (r:1 w:1)
+	// Storage: Server ServerById (r:1 w:1)
+	// Storage: Server DelegationByServer (r:1 w:1)
+	// Storage: Server BackingByServer (r:1 w:1)
+	// Storage: Server RewardLedgers (r:1 w:1)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: Server TotalStake (r:1 w:1)
+	// Storage: System Account (r:4 w:4)
+	// Storage: Server ServerByOwner (r:0 w:1)
+	/// The range of component `d` is `[0, 100]`.
+	fn slash(d: u32, ) -> Weight {
+		Weight::from_ref_time(121_000_000_u64)
+			// Standard Error: 15_732
+			.saturating_add(Weight::from_ref_time(21_410_000_u64).saturating_mul(d as u64))
+			.saturating_add(RocksDbWeight::get().reads(12_u64))
+			.saturating_add(RocksDbWeight::get().reads(2_u64.saturating_mul(d as u64)))
+			.saturating_add(RocksDbWeight::get().writes(12_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64.saturating_mul(d as u64)))
+	}
+	// Storage: Server ReportById (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn reject_report() -> Weight {
+		Weight::from_ref_time(52_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	// Storage: Server HeartbeatKeyByServer (r:1 w:0)
+	// Storage: Server LastHeartbeat (r:1 w:1)
+	// Storage: Server NextLivenessCheck (r:1 w:1)
+	// Storage: Server LivenessTasks (r:2 w:2)
+	// Storage: Server InactiveServers (r:0 w:1)
+	fn heartbeat() -> Weight {
+		Weight::from_ref_time(47_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	// Storage: Server LivenessTasks (r:1 w:1)
+	// Storage: Server NextLivenessCheck (r:1 w:1)
+	// Storage: Server LastHeartbeat (r:1 w:0)
+	// Storage: Server InactiveServers (r:0 w:1)
+	fn on_initialize_liveness() -> Weight {
+		Weight::from_ref_time(29_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
 	// Storage: Server Tasks (r:1 w:1)
 	// Storage: Server ServerById (r:1 w:1)
 	// Storage: Server ServerCount (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Server DelegationByServer (r:1 w:1)
+	// Storage: Server BackingByServer (r:0 w:1)
 	// Storage: Server ServerByOwner (r:0 w:1)
 	// Storage: Server ServerByApiUrl (r:0 w:1)
-	fn on_initialize_server() -> Weight {
-		Weight::from_ref_time(156_000_000_u64)
-			.saturating_add(RocksDbWeight::get().reads(4_u64))
-			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	// Storage: Server MetadataDeposit (r:1 w:1)
+	// Storage: Server MetadataByServer (r:0 w:1)
+	// Storage: Server RewardLedgers (r:1 w:1)
+	// Storage: Server RewardPerStake (r:1 w:0)
+	// Storage: Server TotalStake (r:1 w:1)
+	// Storage: Server HeartbeatKeyByServer (r:1 w:1)
+	// Storage: Server ServerByHeartbeatKey (r:0 w:1)
+	// Storage: Server NextLivenessCheck (r:1 w:1)
+	// Storage: Server LivenessTasks (r:1 w:1)
+	// Storage: Server LastHeartbeat (r:0 w:1)
+	// Storage: Server InactiveServers (r:0 w:1)
+	/// The range of component `d` is `[0, 100]`.
+	fn on_initialize_server(d: u32, ) -> Weight {
+		Weight::from_ref_time(181_000_000_u64)
+			// Standard Error: 18_104
+			.saturating_add(Weight::from_ref_time(33_120_000_u64).saturating_mul(d as u64))
+			.saturating_add(RocksDbWeight::get().reads(12_u64))
+			.saturating_add(RocksDbWeight::get().reads(4_u64.saturating_mul(d as u64)))
+			.saturating_add(RocksDbWeight::get().writes(18_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64.saturating_mul(d as u64)))
 	}
 }
@@ -0,0 +1,222 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_tipping::runtime_api::TippingApi as TippingRuntimeApi;
+use pallet_tipping::{FtIdentifier, TipStats, TipsBalance};
+
+/// Balances are returned as `NumberOrHex`, so amounts above `u64` don't lose precision in JSON.
+#[rpc(client, server)]
+pub trait TippingApi<BlockHash, AccountId> {
+	/// Every tips balance of a reference, one entry per `FtIdentifier`.
+	#[method(name = "tipping_getTipsBalances")]
+	fn get_tips_balances(
+		&self,
+		server_id: AccountId,
+		reference_type: String,
+		reference_id: String,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<TipsBalance<NumberOrHex, AccountId, AccountId>>>;
+
+	/// Total balance per `FtIdentifier` that an account can claim from a server.
+	#[method(name = "tipping_getClaimableTips")]
+	fn get_claimable_tips(
+		&self,
+		server_id: AccountId,
+		account_id: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(FtIdentifier, NumberOrHex)>>;
+
+	/// Whether the buyer has paid for an unlockable content.
+	#[method(name = "tipping_hasPaid")]
+	fn has_paid(
+		&self,
+		buyer: AccountId,
+		server_id: AccountId,
+		reference_id: String,
+		at: Option<BlockHash>,
+	) -> RpcResult<bool>;
+
+	/// Lifetime totals and tipper count of a reference per `FtIdentifier`.
+	#[method(name = "tipping_getTipStats")]
+	fn get_tip_stats(
+		&self,
+		server_id: AccountId,
+		reference_type: String,
+		reference_id: String,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(FtIdentifier, TipStats<NumberOrHex>)>>;
+
+	/// The biggest tippers of a reference, biggest first.
+	#[method(name = "tipping_getTopTippers")]
+	fn get_top_tippers(
+		&self,
+		server_id: AccountId,
+		reference_type: String,
+		reference_id: String,
+		ft_identifier: String,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(AccountId, NumberOrHex)>>;
+}
+
+/// Provides RPC methods to query tipping balances.
+pub struct Tipping<C, Block, Balance> {
+	client: Arc<C>,
+	_marker: PhantomData<(Block, Balance)>,
+}
+
+impl<C, Block, Balance> Tipping<C, Block, Balance> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+#[async_trait]
+impl<C, Block, AccountId, Balance> TippingApiServer<<Block as BlockT>::Hash, AccountId>
+	for Tipping<C, Block, Balance>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: TippingRuntimeApi<Block, AccountId, Balance>,
+	AccountId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	Balance: Codec + Into<NumberOrHex> + Send + Sync + 'static,
+{
+	fn get_tips_balances(
+		&self,
+		server_id: AccountId,
+		reference_type: String,
+		reference_id: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<TipsBalance<NumberOrHex, AccountId, AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let tips_balances = api
+			.get_tips_balances(
+				&at,
+				server_id,
+				reference_type.into_bytes(),
+				reference_id.into_bytes(),
+			)
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(tips_balances
+			.into_iter()
+			.map(|tips_balance| tips_balance.map_amount(Into::into))
+			.collect())
+	}
+
+	fn get_claimable_tips(
+		&self,
+		server_id: AccountId,
+		account_id: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(FtIdentifier, NumberOrHex)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let claimable_tips = api
+			.get_claimable_tips(&at, server_id, account_id)
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(claimable_tips
+			.into_iter()
+			.map(|(ft_identifier, amount)| (ft_identifier, amount.into()))
+			.collect())
+	}
+
+	fn has_paid(
+		&self,
+		buyer: AccountId,
+		server_id: AccountId,
+		reference_id: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<bool> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.has_paid(&at, buyer, server_id, reference_id.into_bytes())
+			.map_err(runtime_error_into_rpc_err)
+	}
+
+	fn get_tip_stats(
+		&self,
+		server_id: AccountId,
+		reference_type: String,
+		reference_id: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(FtIdentifier, TipStats<NumberOrHex>)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let tip_stats = api
+			.get_tip_stats(&at, server_id, reference_type.into_bytes(), reference_id.into_bytes())
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(tip_stats
+			.into_iter()
+			.map(|(ft_identifier, stats)| (ft_identifier, stats.map_total_tipped(Into::into)))
+			.collect())
+	}
+
+	fn get_top_tippers(
+		&self,
+		server_id: AccountId,
+		reference_type: String,
+		reference_id: String,
+		ft_identifier: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(AccountId, NumberOrHex)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let top_tippers = api
+			.get_top_tippers(
+				&at,
+				server_id,
+				reference_type.into_bytes(),
+				reference_id.into_bytes(),
+				ft_identifier.into_bytes(),
+			)
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(top_tippers
+			.into_iter()
+			.map(|(account_id, amount)| (account_id, amount.into()))
+			.collect())
+	}
+}
+
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query tipping.",
+		Some(format!("{:?}", err)),
+	))
+	.into()
+}
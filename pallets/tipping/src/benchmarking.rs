@@ -4,14 +4,94 @@ use super::*;
 
 #[allow(unused)]
 use crate::{Config, Pallet as Tipping, TippingInterface};
+use codec::Encode;
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
-use frame_support::{sp_runtime::SaturatedConversion, traits::Currency};
-use frame_system::RawOrigin;
-use sp_std::vec;
+use frame_support::{
+	sp_runtime::{traits::Zero, Permill, SaturatedConversion},
+	traits::{Currency, EnsureOrigin, Get, OnIdle, OnInitialize},
+	weights::Weight,
+};
+use frame_system::{Pallet as System, RawOrigin};
+use pallet_server::ActionType;
+use sp_core::{crypto::KeyTypeId, sr25519};
+use sp_std::{vec, vec::Vec};
 
 const SEED: u32 = 0;
+const KEY_TYPE: KeyTypeId = KeyTypeId(*b"tipp");
+
+fn bounded<T: Config>(s: &[u8]) -> BoundedStringOf<T> {
+	s.to_vec().try_into().unwrap()
+}
+
+fn references_of<T: Config>(reference_type: &[u8], reference_id: &[u8]) -> ReferencesOf<T> {
+	References::new(&bounded::<T>(reference_type), &[bounded::<T>(reference_id)]).unwrap()
+}
+
+fn memo<T: Config>() -> MemoOf<T> {
+	let content = vec![0u8; T::MaxMemoLength::get() as usize].try_into().unwrap();
+
+	Memo::new(content, Some(Default::default()), true)
+}
+
+fn register_server<T: Config + pallet_server::Config>(owner: &T::AccountId) -> u64 {
+	let balance = 100_000_000_000_000_000_000_000u128.saturated_into(); // 100_000 MYRIA
+	let _ = <T as pallet_server::Config>::Currency::deposit_creating(owner, balance);
+
+	let server_id = pallet_server::Pallet::<T>::server_index();
+	let api_url = b"https://api.dev.myriad.social".to_vec().try_into().unwrap();
+	let owner_origin =
+		<T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(owner.clone()));
+	let _ = pallet_server::Pallet::<T>::register(owner_origin, api_url, None);
+
+	// Every benchmark tips in native
+	let native = AssetMetadata::new(AssetKind::Native, Zero::zero(), 18);
+	AssetRegistry::<T>::insert(bounded::<T>(b"native"), native);
+
+	server_id
+}
+
+fn tip_splits<T: Config>(s: u32) -> Vec<TipSplitOf<T>> {
+	let share = Permill::from_parts(1_000_000 / s);
+
+	(0..s)
+		.map(|i| {
+			// The first recipient takes what doesn't divide evenly
+			let share = if i == 0 {
+				Permill::from_parts(1_000_000 - share.deconstruct() * (s - 1))
+			} else {
+				share
+			};
+
+			TipSplit::new(SplitRecipient::Account(account("recipient", i, SEED)), share)
+		})
+		.collect()
+}
+
+fn reward_beneficiaries<T: Config>(b: u32) -> Vec<RewardBeneficiaryOf<T>> {
+	let share = Permill::from_parts(1_000_000 / b);
+
+	(0..b)
+		.map(|i| {
+			// The first beneficiary takes what doesn't divide evenly
+			let share = if i == 0 {
+				Permill::from_parts(1_000_000 - share.deconstruct() * (b - 1))
+			} else {
+				share
+			};
+
+			RewardBeneficiary::new(account("beneficiary", i, SEED), share)
+		})
+		.collect()
+}
 
 benchmarks! {
+	where_clause {
+		where
+			T: pallet_server::Config,
+			T::Signature: From<sr25519::Signature>,
+			T::AccountId: From<sr25519::Public>,
+	}
+
 	pay_content {
 		let caller: T::AccountId = whitelisted_caller();
 		let server_id: T::AccountId = account("server_account", 0, SEED);
@@ -24,23 +104,33 @@ benchmarks! {
 		// Caller initial balance
 		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
 
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
 		// Unlockable detail
-		let reference_id = b"unlockable_content_id".to_vec();
-		let reference_type = b"unlockable_content".to_vec();
-		let ft_identifier = b"native".to_vec();
+		let reference_id = bounded::<T>(b"unlockable_content_id");
+		let reference_type = bounded::<T>(b"unlockable_content");
+		let ft_identifier = bounded::<T>(b"native");
 		let tips_balance_info = TipsBalanceInfo::new(
 			&server_id,
 			&reference_type,
 			&reference_id,
 			&ft_identifier
 		);
-	}: _(RawOrigin::Signed(caller), Some(receiver_id), 0, tips_balance_info, amount, None)
+	}: _(
+		RawOrigin::Signed(caller),
+		Some(receiver_id),
+		instance_id,
+		tips_balance_info,
+		amount,
+		None,
+		Some(memo::<T>())
+	)
 
-	withdraw_fee {
+	pay_content_escrow {
 		let caller: T::AccountId = whitelisted_caller();
 		let server_id: T::AccountId = account("server_account", 0, SEED);
 		let receiver_id: T::AccountId = account("receiver_id", 0, SEED);
-		let tipping_account_id: T::AccountId = Tipping::<T>::tipping_account_id();
 
 		// Default balance
 		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
@@ -48,24 +138,227 @@ benchmarks! {
 
 		// Caller initial balance
 		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
-		let _ = <T as Config>::Currency::deposit_creating(&tipping_account_id, balance);
 
-		// Pay content
-		let reference_id = b"unlockable_content_id".to_vec();
-		let reference_type = b"unlockable_content".to_vec();
-		let ft_identifier = b"native".to_vec();
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		// Unlockable detail
+		let reference_id = bounded::<T>(b"unlockable_content_id");
+		let reference_type = bounded::<T>(b"unlockable_content");
+		let ft_identifier = bounded::<T>(b"native");
 		let tips_balance_info = TipsBalanceInfo::new(
 			&server_id,
 			&reference_type,
 			&reference_id,
 			&ft_identifier
 		);
+	}: _(
+		RawOrigin::Signed(caller),
+		receiver_id,
+		instance_id,
+		tips_balance_info,
+		amount,
+		Some(memo::<T>())
+	)
 
-		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller));
-		let _ = Tipping::<T>::pay_content(caller_origin, Some(receiver_id), 0, tips_balance_info, amount, None);
+	confirm_escrow {
+		let caller: T::AccountId = whitelisted_caller();
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+		let receiver_id: T::AccountId = account("receiver_id", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		// Unlockable detail
+		let reference_id = bounded::<T>(b"unlockable_content_id");
+		let reference_type = bounded::<T>(b"unlockable_content");
+		let ft_identifier = bounded::<T>(b"native");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&reference_type,
+			&reference_id,
+			&ft_identifier
+		);
+
+		let receipt_id = Tipping::<T>::receipt_id(&caller, &tips_balance_info, &amount, 0);
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let _ = Tipping::<T>::pay_content_escrow(caller_origin, receiver_id, instance_id, tips_balance_info, amount, None);
+	}: _(RawOrigin::Signed(caller), receipt_id)
+
+	refund_escrow {
+		let caller: T::AccountId = whitelisted_caller();
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+		let receiver_id: T::AccountId = account("receiver_id", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		// Unlockable detail
+		let reference_id = bounded::<T>(b"unlockable_content_id");
+		let reference_type = bounded::<T>(b"unlockable_content");
+		let ft_identifier = bounded::<T>(b"native");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&reference_type,
+			&reference_id,
+			&ft_identifier
+		);
+
+		let receipt_id = Tipping::<T>::receipt_id(&caller, &tips_balance_info, &amount, 0);
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let _ = Tipping::<T>::pay_content_escrow(caller_origin, receiver_id, instance_id, tips_balance_info, amount, None);
+	}: _(RawOrigin::Signed(caller), receipt_id)
+
+	on_initialize_escrow {
+		let caller: T::AccountId = whitelisted_caller();
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+		let receiver_id: T::AccountId = account("receiver_id", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		// Unlockable detail
+		let reference_id = bounded::<T>(b"unlockable_content_id");
+		let reference_type = bounded::<T>(b"unlockable_content");
+		let ft_identifier = bounded::<T>(b"native");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&reference_type,
+			&reference_id,
+			&ft_identifier
+		);
+
+		// Current block
+		let current_block = System::<T>::block_number();
+
+		let receipt_id = Tipping::<T>::receipt_id(&caller, &tips_balance_info, &amount, 0);
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let _ = Tipping::<T>::pay_content_escrow(caller_origin, receiver_id, instance_id, tips_balance_info, amount, None);
+
+		let scheduled_block_number = current_block + T::EscrowPeriod::get();
+
+		// Set blocknumber
+		System::<T>::set_block_number(scheduled_block_number);
+	}: {
+		Tipping::<T>::on_initialize(scheduled_block_number)
+	}
+
+	withdraw_fee {
+		let s in 1 .. T::MaxFeeWithdrawals::get();
+
+		let tipping_account_id: T::AccountId = Tipping::<T>::tipping_account_id();
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		let _ = <T as Config>::Currency::deposit_creating(&tipping_account_id, balance);
+
+		// Fees already collected by tipping, one currency each
+		let amounts: FeeWithdrawalsOf<T> = (0..s)
+			.map(|i| {
+				let ft_identifier = bounded::<T>(&[b"fee".to_vec(), i.encode()].concat());
+				let asset = AssetMetadata::new(AssetKind::Native, Zero::zero(), 18);
+
+				AssetRegistry::<T>::insert(&ft_identifier, asset);
+				WithdrawalBalance::<T>::insert(&ft_identifier, amount);
+
+				(ft_identifier, amount)
+			})
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+
+		let origin = T::WithdrawOrigin::successful_origin();
+	}: _<T::RuntimeOrigin>(origin, amounts)
+
+	propose_fee_spend {
+		let caller: T::AccountId = whitelisted_caller();
+		let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		let native = AssetMetadata::new(AssetKind::Native, Zero::zero(), 18);
+		AssetRegistry::<T>::insert(bounded::<T>(b"native"), native);
+	}: _(RawOrigin::Signed(caller), beneficiary, bounded::<T>(b"native"), amount)
+
+	approve_fee_spend {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin =
+			<T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+		let tipping_account_id: T::AccountId = Tipping::<T>::tipping_account_id();
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+		let _ = <T as Config>::Currency::deposit_creating(&tipping_account_id, balance);
 
-		let receiver: T::AccountId = account("receiver", 0, SEED);
-	}: _(RawOrigin::Root, receiver)
+		let native = AssetMetadata::new(AssetKind::Native, Zero::zero(), 18);
+		AssetRegistry::<T>::insert(bounded::<T>(b"native"), native);
+		WithdrawalBalance::<T>::insert(bounded::<T>(b"native"), amount);
+
+		let _ = Tipping::<T>::propose_fee_spend(
+			caller_origin,
+			beneficiary,
+			bounded::<T>(b"native"),
+			amount
+		);
+
+		let origin = T::WithdrawOrigin::successful_origin();
+	}: _<T::RuntimeOrigin>(origin, 0)
+
+	reject_fee_spend {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin =
+			<T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		let native = AssetMetadata::new(AssetKind::Native, Zero::zero(), 18);
+		AssetRegistry::<T>::insert(bounded::<T>(b"native"), native);
+
+		let _ = Tipping::<T>::propose_fee_spend(
+			caller_origin,
+			beneficiary,
+			bounded::<T>(b"native"),
+			amount
+		);
+
+		let origin = T::WithdrawOrigin::successful_origin();
+	}: _<T::RuntimeOrigin>(origin, 0)
 
 	withdraw_reward {
 		let caller: T::AccountId = whitelisted_caller();
@@ -83,9 +376,9 @@ benchmarks! {
 		let _ = <T as Config>::Currency::deposit_creating(&tipping_account_id, balance);
 
 		// Pay content
-		let reference_id = b"unlockable_content_id".to_vec();
-		let reference_type = b"unlockable_content".to_vec();
-		let ft_identifier = b"native".to_vec();
+		let reference_id = bounded::<T>(b"unlockable_content_id");
+		let reference_type = bounded::<T>(b"unlockable_content");
+		let ft_identifier = bounded::<T>(b"native");
 		let tips_balance_info = TipsBalanceInfo::new(
 			&server_id,
 			&reference_type,
@@ -93,9 +386,10 @@ benchmarks! {
 			&ft_identifier
 		);
 
+		let instance_id = register_server::<T>(&server_id);
 		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller));
-		let _ = Tipping::<T>::pay_content(caller_origin, Some(receiver_id), 0, tips_balance_info, amount, None);
-	}: _(RawOrigin::Signed(server_id), 0)
+		let _ = Tipping::<T>::pay_content(caller_origin, Some(receiver_id), instance_id, tips_balance_info, amount, None, None);
+	}: _(RawOrigin::Signed(server_id), instance_id)
 
 	send_tip {
 		// Initial account
@@ -109,17 +403,86 @@ benchmarks! {
 		// Caller initial balance
 		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
 
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
 		// Send tip
-		let reference_id = b"people_id".to_vec();
-		let reference_type = b"people".to_vec();
-		let ft_identifier = b"native".to_vec();
+		let reference_id = bounded::<T>(b"people_id");
+		let reference_type = bounded::<T>(b"people");
+		let ft_identifier = bounded::<T>(b"native");
 		let tips_balance_info = TipsBalanceInfo::new(
 			&server_id,
 			&reference_type,
 			&reference_id,
 			&ft_identifier
 		);
-	}: _(RawOrigin::Signed(caller), tips_balance_info, amount)
+		let memo = Some(memo::<T>());
+		let commitment = Some(Tipping::<T>::tip_commitment(
+			&caller,
+			&tips_balance_info.key(),
+			&Default::default()
+		));
+	}: _(RawOrigin::Signed(caller), instance_id, tips_balance_info, amount, memo, commitment)
+
+	reveal_tip {
+		let caller: T::AccountId = whitelisted_caller();
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+		let instance_id = register_server::<T>(&server_id);
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"people"),
+			&bounded::<T>(b"people_id"),
+			&bounded::<T>(b"native")
+		);
+		let tips_balance_key = tips_balance_info.key();
+		let salt: HashOf<T> = Default::default();
+		let commitment = Some(Tipping::<T>::tip_commitment(&caller, &tips_balance_key, &salt));
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(
+			RawOrigin::Signed(caller.clone())
+		);
+		let _ = Tipping::<T>::send_tip(caller_origin, instance_id, tips_balance_info, amount, None, commitment);
+	}: _(RawOrigin::Signed(caller), tips_balance_key, salt)
+
+	send_tips {
+		let s in 1 .. T::MaxTipsPerBatch::get();
+
+		// Initial account
+		let caller: T::AccountId = whitelisted_caller();
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		// Send tips to `s` references
+		let reference_type = bounded::<T>(b"people");
+		let ft_identifier = bounded::<T>(b"native");
+		let mut tips = Vec::new();
+
+		for i in 0 .. s {
+			let reference_id = bounded::<T>(&i.to_le_bytes());
+			let tips_balance_info = TipsBalanceInfo::new(
+				&server_id,
+				&reference_type,
+				&reference_id,
+				&ft_identifier
+			);
+
+			tips.push((instance_id, tips_balance_info, amount));
+		}
+
+		let tips: TipsOf<T> = tips.try_into().unwrap();
+	}: _(RawOrigin::Signed(caller), tips)
 
 	claim_reference {
 		// Initial account
@@ -138,34 +501,181 @@ benchmarks! {
 		let _ = <T as Config>::Currency::deposit_creating(&account_2, balance);
 		let _ = <T as Config>::Currency::deposit_creating(&tipping_account_id, balance);
 
+		// Registered server
+		let instance_id = register_server::<T>(&caller);
+
 		// Send Tip by account_1
 		let account_1_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(account_1));
 		let tips_balance_info = TipsBalanceInfo::new(
 			&caller,
-			b"people",
-			b"people_id",
-			b"native"
+			&bounded::<T>(b"people"),
+			&bounded::<T>(b"people_id"),
+			&bounded::<T>(b"native")
 		);
-		let _ = Tipping::<T>::send_tip(account_1_origin, tips_balance_info, tipping_amount);
+		let _ = Tipping::<T>::send_tip(account_1_origin, instance_id, tips_balance_info, tipping_amount, None, None);
 
 		// Send Tip by account_2
 		let account_2_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(account_2));
 		let tips_balance_info = TipsBalanceInfo::new(
 			&caller,
-			b"user",
-			b"user_id",
-			b"native"
+			&bounded::<T>(b"user"),
+			&bounded::<T>(b"user_id"),
+			&bounded::<T>(b"native")
 		);
-		let _ = Tipping::<T>::send_tip(account_2_origin, tips_balance_info, tipping_amount);
+		let _ = Tipping::<T>::send_tip(account_2_origin, instance_id, tips_balance_info, tipping_amount, None, None);
 
 		// Claim reference data
 		let server_id = caller.clone();
 		let trx_fee = 10_000_000_000_000_000u128.saturated_into(); // 0.01 MYRIA
-		let references = References::new(b"people", &[b"people_id".to_vec()]);
-		let main_references = References::new(b"user", &[b"user_id".to_vec()]);
-		let ft_identifiers = vec![b"native".to_vec()];
-	}: _(RawOrigin::Signed(caller), server_id, references, main_references, ft_identifiers,
-account_3, trx_fee)
+		let references = references_of::<T>(b"people", b"people_id");
+		let main_references = references_of::<T>(b"user", b"user_id");
+		let ft_identifiers = vec![bounded::<T>(b"native")];
+	}: _(RawOrigin::Signed(caller), server_id, instance_id, references, main_references,
+ft_identifiers, account_3, trx_fee)
+
+	claim_reference_with_proof {
+		// Initial account
+		let caller: T::AccountId = whitelisted_caller();
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+		let account_1: T::AccountId = account("account_1", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let tipping_amount = 10_000_000_000_000_000_000u128.saturated_into(); // 10 MYRIA
+
+		let _ = <T as Config>::Currency::deposit_creating(&account_1, balance);
+
+		// Registered server with a signing key
+		let instance_id = register_server::<T>(&server_id);
+		let signing_key = sp_io::crypto::sr25519_generate(KEY_TYPE, None);
+		let server_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(server_id.clone()));
+		let action_type = ActionType::UpdateSigningKey(Some(signing_key.into()));
+		let _ = pallet_server::Pallet::<T>::update_server(server_origin, instance_id, action_type);
+
+		// Send Tip by account_1
+		let account_1_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(account_1));
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"people"),
+			&bounded::<T>(b"people_id"),
+			&bounded::<T>(b"native")
+		);
+		let _ = Tipping::<T>::send_tip(account_1_origin, instance_id, tips_balance_info, tipping_amount, None, None);
+
+		// Claim proof signed by the server
+		let references = references_of::<T>(b"people", b"people_id");
+		let account_references = references_of::<T>(b"user", b"user_id");
+		let expires_at = System::<T>::block_number() + 10u32.into();
+		let tx_fee = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+		let tx_fee = Some((bounded::<T>(b"native"), tx_fee));
+		let proof = ClaimProof::new(
+			instance_id,
+			caller.clone(),
+			references,
+			account_references,
+			tx_fee,
+			0,
+			expires_at
+		);
+		let signature =
+			sp_io::crypto::sr25519_sign(KEY_TYPE, &signing_key, &proof.signing_payload()).unwrap();
+		let ft_identifiers = vec![bounded::<T>(b"native")];
+	}: _(RawOrigin::Signed(caller), proof, signature.into(), ft_identifiers)
+
+	reclaim_tip {
+		let caller: T::AccountId = whitelisted_caller();
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		// Tip a reference nobody claims
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"people"),
+			&bounded::<T>(b"people_id"),
+			&bounded::<T>(b"native")
+		);
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let _ = Tipping::<T>::send_tip(caller_origin, instance_id, tips_balance_info.clone(), amount, None, None);
+
+		// Set blocknumber
+		let expire_at = System::<T>::block_number() + T::TipExpiry::get();
+		System::<T>::set_block_number(expire_at);
+	}: _(RawOrigin::Signed(caller), tips_balance_info.key())
+
+	on_idle_tip_expiry {
+		let caller: T::AccountId = whitelisted_caller();
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		// Tip a reference nobody claims
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"people"),
+			&bounded::<T>(b"people_id"),
+			&bounded::<T>(b"native")
+		);
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller));
+		let _ = Tipping::<T>::send_tip(caller_origin, instance_id, tips_balance_info, amount, None, None);
+
+		// Sweep only the expiring block
+		let expire_at = System::<T>::block_number() + T::TipExpiry::get();
+		TipExpiryCursor::<T>::put(expire_at);
+		System::<T>::set_block_number(expire_at);
+	}: {
+		Tipping::<T>::on_idle(expire_at, Weight::MAX)
+	}
+
+	remove_tip_contributions {
+		let c in 1 .. T::MaxTipContributionRemovals::get();
+
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"people"),
+			&bounded::<T>(b"people_id"),
+			&bounded::<T>(b"native")
+		);
+		let key = tips_balance_info.key();
+		let (_, reference_type, reference_id, ft_identifier) = &key;
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		let native = AssetMetadata::new(AssetKind::Native, Zero::zero(), 18);
+		AssetRegistry::<T>::insert(bounded::<T>(b"native"), native);
+
+		// One more than the limit, so the rest is left for a later block
+		for i in 0 .. c + 1 {
+			let tipper: T::AccountId = account("tipper", i, SEED);
+			let contribution_key = (&server_id, reference_type, reference_id, ft_identifier, &tipper);
+
+			let _ = <T as Config>::Currency::deposit_creating(&tipper, balance);
+			let _ = Tipping::<T>::do_hold(ft_identifier, &tipper, amount);
+
+			TipContributions::<T>::mutate(contribution_key, |contribution| {
+				contribution.add_tip(amount, Zero::zero());
+			});
+		}
+	}: {
+		Tipping::<T>::do_remove_tip_contributions(&key, &key)
+	}
 
 	claim_tip {
 		// Initial account
@@ -184,25 +694,28 @@ account_3, trx_fee)
 		let _ = <T as Config>::Currency::deposit_creating(&account_2, balance);
 		let _ = <T as Config>::Currency::deposit_creating(&tipping_account_id, balance);
 
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
 		// Send Tip
 		let account_1_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(account_1));
 		let tips_balance_info = TipsBalanceInfo::new(
 			&server_id,
-			b"people",
-			b"people_id",
-			b"native"
+			&bounded::<T>(b"people"),
+			&bounded::<T>(b"people_id"),
+			&bounded::<T>(b"native")
 		);
-		let _ = Tipping::<T>::send_tip(account_1_origin, tips_balance_info, tipping_amount);
+		let _ = Tipping::<T>::send_tip(account_1_origin, instance_id, tips_balance_info, tipping_amount, None, None);
 
 		// Send Tip
 		let account_2_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(account_2));
 		let tips_balance_info_user = TipsBalanceInfo::new(
 			&server_id,
-			b"user",
-			b"user_id",
-			b"native"
+			&bounded::<T>(b"user"),
+			&bounded::<T>(b"user_id"),
+			&bounded::<T>(b"native")
 		);
-		let _ = Tipping::<T>::send_tip(account_2_origin, tips_balance_info_user, tipping_amount);
+		let _ = Tipping::<T>::send_tip(account_2_origin, instance_id, tips_balance_info_user, tipping_amount, None, None);
 
 		// Claim Reference
 		let server_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(server_id.clone()));
@@ -210,13 +723,436 @@ account_3, trx_fee)
 		let _ = Tipping::<T>::claim_reference(
 			server_origin,
 			server_id.clone(),
-			References::new(b"people", &[b"people_id".to_vec()]),
-			References::new(b"user", &[b"user_id".to_vec()]),
-			vec![b"native".to_vec()],
+			instance_id,
+			references_of::<T>(b"people", b"people_id"),
+			references_of::<T>(b"user", b"user_id"),
+			vec![bounded::<T>(b"native")],
 			caller.clone(),
 			tx_fee,
 		);
-	}: _(RawOrigin::Signed(caller), server_id, b"user".to_vec(), b"user_id".to_vec(),
-vec![b"native".to_vec()]) }
+	}: _(RawOrigin::Signed(caller), server_id, bounded::<T>(b"user"), bounded::<T>(b"user_id"),
+vec![bounded::<T>(b"native")])
+
+	claim_tip_and_bridge {
+		// Initial account
+		let caller: T::AccountId = whitelisted_caller();
+		let account_1: T::AccountId = account("account", 0, SEED);
+		let account_2: T::AccountId = account("account", 2, SEED);
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+		let tipping_account_id: T::AccountId = Tipping::<T>::tipping_account_id();
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let tipping_amount = 10_000_000_000_000_000_000u128.saturated_into(); // 10 MYRIA
+
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+		let _ = <T as Config>::Currency::deposit_creating(&account_1, balance);
+		let _ = <T as Config>::Currency::deposit_creating(&account_2, balance);
+		let _ = <T as Config>::Currency::deposit_creating(&tipping_account_id, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		// Send Tip
+		let account_1_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(account_1));
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"people"),
+			&bounded::<T>(b"people_id"),
+			&bounded::<T>(b"native")
+		);
+		let _ = Tipping::<T>::send_tip(account_1_origin, instance_id, tips_balance_info, tipping_amount, None, None);
+
+		// Send Tip
+		let account_2_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(account_2));
+		let tips_balance_info_user = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"user"),
+			&bounded::<T>(b"user_id"),
+			&bounded::<T>(b"native")
+		);
+		let _ = Tipping::<T>::send_tip(account_2_origin, instance_id, tips_balance_info_user, tipping_amount, None, None);
+
+		// Claim Reference
+		let server_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(server_id.clone()));
+		let tx_fee = 10_000_000_000_000_000u128.saturated_into(); // 0.01 MYRIA
+		let _ = Tipping::<T>::claim_reference(
+			server_origin,
+			server_id.clone(),
+			instance_id,
+			references_of::<T>(b"people", b"people_id"),
+			references_of::<T>(b"user", b"user_id"),
+			vec![bounded::<T>(b"native")],
+			caller.clone(),
+			tx_fee,
+		);
+	}: _(
+		RawOrigin::Signed(caller),
+		server_id,
+		bounded::<T>(b"user"),
+		bounded::<T>(b"user_id"),
+		bounded::<T>(b"native"),
+		bounded::<T>(b"receiver.near")
+	)
+
+	set_default_fee_policy {
+		let fee_policy = FeePolicy::new(Permill::from_percent(5), Permill::from_percent(10));
+	}: _(RawOrigin::Root, fee_policy)
+
+	set_fee_policy_bounds {
+		let bounds = FeePolicyBounds::new(Permill::from_percent(10), Permill::from_percent(10));
+	}: _(RawOrigin::Root, bounds)
+
+	set_server_fee_policy {
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		let fee_policy = FeePolicy::new(Permill::from_percent(5), Permill::from_percent(10));
+	}: _(RawOrigin::Signed(server_id), instance_id, Some(fee_policy))
+
+	set_tip_split {
+		let s in 1 .. T::MaxTipSplits::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+
+		// Registered server
+		let _ = register_server::<T>(&server_id);
+
+		// Reference claimed by the caller
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"user"),
+			&bounded::<T>(b"user_id"),
+			&bounded::<T>(b"native")
+		);
+		let mut tips_balance = TipsBalance::new(&tips_balance_info, &Zero::zero());
+
+		tips_balance.set_account_id(&caller);
+
+		TipsBalanceByReference::<T>::insert(tips_balance_info.key(), tips_balance);
+
+		let reference_type = bounded::<T>(b"user");
+		let reference_id = bounded::<T>(b"user_id");
+		let splits = tip_splits::<T>(s);
+	}: _(RawOrigin::Signed(caller), server_id, reference_type, reference_id, Some(splits))
+
+	split_tip {
+		let s in 1 .. T::MaxTipSplits::get();
+
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+		let tipping_account_id = Tipping::<T>::tipping_account_id();
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		// Tip already received by tipping
+		let _ = <T as Config>::Currency::deposit_creating(&tipping_account_id, balance);
+
+		// Registered server
+		let _ = register_server::<T>(&server_id);
+
+		// Every share is paid out to an account
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"user"),
+			&bounded::<T>(b"user_id"),
+			&bounded::<T>(b"native")
+		);
+		let tips_balance = TipsBalance::new(&tips_balance_info, &amount);
+
+		let splits: TipSplitsOf<T> = tip_splits::<T>(s).try_into().unwrap();
+
+		TipSplits::<T>::insert((&server_id, bounded::<T>(b"user"), bounded::<T>(b"user_id")), splits);
+	}: {
+		let _ = Tipping::<T>::do_store_split_tips_balance(&tips_balance);
+	}
+
+	register_asset {
+		let origin = T::AssetRegistryOrigin::successful_origin();
+		let ft_identifier = bounded::<T>(b"benchmark");
+		let min_tip = 1_000_000_000_000_000u128.saturated_into(); // 0.001 MYRIA
+	}: _<T::RuntimeOrigin>(origin, ft_identifier, AssetKind::Native, min_tip, 18)
+
+	update_asset {
+		let origin = T::AssetRegistryOrigin::successful_origin();
+		let ft_identifier = bounded::<T>(b"benchmark");
+		let min_tip = 1_000_000_000_000_000u128.saturated_into(); // 0.001 MYRIA
+		let asset = AssetMetadata::new(AssetKind::Native, Zero::zero(), 18);
+
+		AssetRegistry::<T>::insert(&ft_identifier, asset);
+	}: _<T::RuntimeOrigin>(origin, ft_identifier, false, min_tip, 18)
+
+	subscribe {
+		// Initial account
+		let caller: T::AccountId = whitelisted_caller();
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"user"),
+			&bounded::<T>(b"user_id"),
+			&bounded::<T>(b"native")
+		);
+		let period = T::MaxSubscriptionPeriod::get();
+	}: _(RawOrigin::Signed(caller), instance_id, tips_balance_info, amount, period)
+
+	cancel_subscription {
+		// Initial account
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"user"),
+			&bounded::<T>(b"user_id"),
+			&bounded::<T>(b"native")
+		);
+		let period = T::MaxSubscriptionPeriod::get();
+
+		let _ = Tipping::<T>::subscribe(caller_origin, instance_id, tips_balance_info, amount, period);
+	}: _(RawOrigin::Signed(caller), 0)
+
+	pause_subscription {
+		// Initial account
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"user"),
+			&bounded::<T>(b"user_id"),
+			&bounded::<T>(b"native")
+		);
+		let period = T::MaxSubscriptionPeriod::get();
+
+		let _ = Tipping::<T>::subscribe(caller_origin, instance_id, tips_balance_info, amount, period);
+	}: _(RawOrigin::Signed(caller), 0)
+
+	resume_subscription {
+		// Initial account
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"user"),
+			&bounded::<T>(b"user_id"),
+			&bounded::<T>(b"native")
+		);
+		let period = T::MaxSubscriptionPeriod::get();
+
+		let _ = Tipping::<T>::subscribe(caller_origin.clone(), instance_id, tips_balance_info, amount, period);
+		let _ = Tipping::<T>::pause_subscription(caller_origin, 0);
+	}: _(RawOrigin::Signed(caller), 0)
+
+	on_initialize_subscription {
+		// Initial account
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(caller.clone()));
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		// Caller initial balance
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"user"),
+			&bounded::<T>(b"user_id"),
+			&bounded::<T>(b"native")
+		);
+		let period = T::MaxSubscriptionPeriod::get();
+
+		// Current block
+		let current_block = System::<T>::block_number();
+
+		let _ = Tipping::<T>::subscribe(caller_origin, instance_id, tips_balance_info, amount, period);
+
+		let scheduled_block_number = current_block + period;
+
+		// Set blocknumber
+		System::<T>::set_block_number(scheduled_block_number);
+	}: {
+		Tipping::<T>::on_initialize(scheduled_block_number)
+	}
+
+	set_reward_distribution {
+		let b in 1 .. T::MaxRewardBeneficiaries::get();
+
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		let beneficiaries = reward_beneficiaries::<T>(b);
+		let period = T::MaxSubscriptionPeriod::get();
+	}: _(RawOrigin::Signed(server_id), instance_id, beneficiaries, period)
+
+	remove_reward_distribution {
+		let u in 1 .. T::MaxRewardTransfers::get();
+
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+		let server_origin =
+			<T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(server_id.clone()));
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		let beneficiaries = reward_beneficiaries::<T>(T::MaxRewardBeneficiaries::get());
+		let period = T::MaxSubscriptionPeriod::get();
+
+		let _ = Tipping::<T>::set_reward_distribution(
+			server_origin,
+			instance_id,
+			beneficiaries,
+			period
+		);
+
+		// Unpaid rewards handed back to the server
+		for i in 0 .. u {
+			let beneficiary: T::AccountId = account("beneficiary", i, SEED);
+			let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+			UnpaidRewards::<T>::insert((instance_id, beneficiary, bounded::<T>(b"native")), amount);
+		}
+	}: _(RawOrigin::Signed(server_id), instance_id)
+
+	on_initialize_reward_payout {
+		let p in 1 .. T::MaxRewardTransfers::get();
+
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+		let server_origin =
+			<T as frame_system::Config>::RuntimeOrigin::from(RawOrigin::Signed(server_id.clone()));
+		let tipping_account_id = Tipping::<T>::tipping_account_id();
+
+		// Default balance
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+
+		// Reward already held by tipping
+		let _ = <T as Config>::Currency::deposit_creating(&tipping_account_id, balance);
+
+		// Registered server
+		let instance_id = register_server::<T>(&server_id);
+
+		RewardBalance::<T>::insert((&server_id, instance_id, bounded::<T>(b"native")), amount);
+
+		// Unpaid rewards retried before the reward balance is split
+		for i in 1 .. p {
+			let beneficiary: T::AccountId = account("unpaid", i, SEED);
+
+			UnpaidRewards::<T>::insert((instance_id, beneficiary, bounded::<T>(b"native")), amount);
+		}
+
+		let beneficiaries = reward_beneficiaries::<T>(1);
+		let period = T::MaxSubscriptionPeriod::get();
+
+		// Current block
+		let current_block = System::<T>::block_number();
+
+		let _ = Tipping::<T>::set_reward_distribution(
+			server_origin,
+			instance_id,
+			beneficiaries,
+			period
+		);
+
+		let scheduled_block_number = current_block + period;
+
+		// Set blocknumber
+		System::<T>::set_block_number(scheduled_block_number);
+	}: {
+		Tipping::<T>::on_initialize(scheduled_block_number)
+	}
+
+	remove_memo {
+		let caller: T::AccountId = whitelisted_caller();
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+
+		let memo_id: HashOf<T> = Default::default();
+		let _ = Tipping::<T>::do_store_memo(&memo_id, &caller, &memo::<T>());
+	}: _(RawOrigin::Signed(caller), memo_id)
+
+	reclaim_anonymous_tip {
+		let caller: T::AccountId = whitelisted_caller();
+		let server_id: T::AccountId = account("server_account", 0, SEED);
+		let balance = 1_000_000_000_000_000_000_000u128.saturated_into(); // 1000 MYRIA
+		let amount = 1_000_000_000_000_000_000u128.saturated_into(); // 1 MYRIA
+		let _ = <T as Config>::Currency::deposit_creating(&caller, balance);
+		let instance_id = register_server::<T>(&server_id);
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded::<T>(b"people"),
+			&bounded::<T>(b"people_id"),
+			&bounded::<T>(b"native")
+		);
+		let tips_balance_key = tips_balance_info.key();
+		let salt: HashOf<T> = Default::default();
+		let commitment = Some(Tipping::<T>::tip_commitment(&caller, &tips_balance_key, &salt));
+		let caller_origin = <T as frame_system::Config>::RuntimeOrigin::from(
+			RawOrigin::Signed(caller.clone())
+		);
+		let _ = Tipping::<T>::send_tip(caller_origin, instance_id, tips_balance_info, amount, None, commitment);
+
+		// Expired anonymous tip
+		let expires_at = System::<T>::block_number() + T::TipExpiry::get();
+		System::<T>::set_block_number(expires_at);
+	}: _(RawOrigin::Signed(caller), tips_balance_key, salt)
+}
 
 impl_benchmark_test_suite! {Server, crate::mock::ExternalityBuilder::build(), crate::mock::Test}
@@ -1,14 +1,25 @@
 use crate::*;
 
+use codec::Encode;
+
 use frame_support::{
 	dispatch::DispatchError,
-	sp_runtime::traits::{AccountIdConversion, SaturatedConversion, Saturating, Zero},
-	traits::{fungibles, Currency, ExistenceRequirement, Get},
+	sp_runtime::{
+		traits::{AccountIdConversion, Hash, One, SaturatedConversion, Saturating, Verify, Zero},
+		PerThing, Permill,
+	},
+	storage::with_storage_layer,
+	traits::{
+		fungibles, BalanceStatus, Currency, ExistenceRequirement, Get, NamedReservableCurrency,
+	},
+	weights::Weight,
 	PalletId,
 };
-use sp_std::vec::*;
+use pallet_server::{ServerId, ServerInfo, ServerProvider};
+use sp_std::{collections::btree_map::BTreeMap, vec, vec::*};
 
 const PALLET_ID: PalletId = PalletId(*b"Tipping!");
+const MEMO_RESERVE_ID: [u8; 8] = *b"tip/memo";
 
 impl<T: Config> Pallet<T> {
 	/// The account ID that holds tipping's funds
@@ -16,118 +27,95 @@ impl<T: Config> Pallet<T> {
 		PALLET_ID.into_account_truncating()
 	}
 
-	pub fn can_update_balance(key: &TipsBalanceKeyOf<T>) -> bool {
-		TipsBalanceByReference::<T>::contains_key(key)
+	/// Bounds the pallet's own identifiers, `integrity_test` makes sure they fit.
+	pub fn bounded_string(value: &[u8]) -> BoundedStringOf<T> {
+		value.to_vec().try_into().unwrap_or_default()
 	}
 
-	/*pub fn can_pay_content(
-			ft_identifier: &[u8],
-			sender: &T::AccountId,
-			amount: &BalanceOf<T>,
-		) -> Result<FeeDetail<BalanceOf<T>>, Error<T>> {
-			let tx_fee_denom = 100u8
-				.checked_div(T::TransactionFee::get())
-				.filter(|value| value <= &100u8)
-				.ok_or(Error::<T>::InsufficientFee)?;
-
-			let fee: BalanceOf<T> = *amount / tx_fee_denom.saturated_into();
-			let total_transfer = *amount + fee;
-
-			let transferable_balance: BalanceOf<T> = if ft_identifier == b"native" {
-				let minimum_balance = CurrencyOf::<T>::minimum_balance();
-				let account_balance = CurrencyOf::<T>::free_balance(sender);
-
-				if account_balance >= minimum_balance {
-					account_balance - minimum_balance
-				} else {
-					Zero::zero()
-				}
-			} else {
-				let asset_id = Self::asset_id(ft_identifier)?;
-				let asset_minimum_balance =
-					<T::Assets as fungibles::Inspect<T::AccountId>>::minimum_balance(asset_id);
-				let asset_account_balance =
-					<T::Assets as fungibles::Inspect<T::AccountId>>::balance(asset_id, sender);
-				let asset_transferable_balance = if asset_account_balance >= asset_minimum_balance {
-					asset_account_balance - asset_minimum_balance
-				} else {
-					0u128
-				};
+	/// The instance is an active server registered by the tipping server id.
+	pub fn can_access_instance(
+		server_id: &ServerIdOf<T>,
+		instance_id: u64,
+	) -> Result<(), Error<T>> {
+		let server = T::Server::get_by_id(instance_id).ok_or(Error::<T>::ServerNotRegister)?;
 
-				asset_transferable_balance.saturated_into()
-			};
+		if server.get_owner() != server_id {
+			return Err(Error::<T>::ServerNotRegister)
+		}
 
-			if total_transfer > transferable_balance {
-				return Err(Error::<T>::InsufficientBalance)
-			}
+		if server.get_unstaked_at().is_some() {
+			return Err(Error::<T>::ServerNotRegister)
+		}
 
-			let admin_fee_denom = 100u8
-				.checked_div(T::AdminFee::get())
-				.filter(|value| value <= &100u8)
-				.ok_or(Error::<T>::InsufficientFee)?;
+		Ok(())
+	}
 
-			let admin_fee = fee / admin_fee_denom.saturated_into();
-			let server_fee = fee - admin_fee;
-			let fee_detail = FeeDetail::new(admin_fee, server_fee, fee);
+	pub fn can_update_balance(key: &TipsBalanceKeyOf<T>) -> bool {
+		TipsBalanceByReference::<T>::contains_key(key)
+	}
 
-			Ok(fee_detail)
+	/// The fee policy set by the server owner, or the default one. Bounds tightened after the
+	/// owner set its policy apply to it as well.
+	pub fn fee_policy(instance_id: u64) -> FeePolicy {
+		match Self::fee_policy_by_server(instance_id) {
+			Some(fee_policy) => Self::fee_bounds().clamp(fee_policy),
+			None => Self::default_fee_policy(),
 		}
+	}
 
-	*/
 	pub fn can_pay_content(
-		ft_identifier: &[u8],
+		instance_id: u64,
+		ft_identifier: &FtIdentifierOf<T>,
 		sender: &T::AccountId,
 		amount: &BalanceOf<T>,
 	) -> Result<FeeDetail<BalanceOf<T>>, Error<T>> {
-		let transferable_balance: BalanceOf<T> = if ft_identifier == b"native" {
-			let minimum_balance = CurrencyOf::<T>::minimum_balance();
-			let account_balance = CurrencyOf::<T>::free_balance(sender);
+		Self::can_tip_with(ft_identifier, amount)?;
 
-			if account_balance >= minimum_balance {
-				account_balance - minimum_balance
-			} else {
-				Zero::zero()
-			}
-		} else {
-			let asset_id = Self::asset_id(ft_identifier)?;
-			let asset_minimum_balance =
-				<T::Assets as fungibles::Inspect<T::AccountId>>::minimum_balance(asset_id);
-			let asset_account_balance =
-				<T::Assets as fungibles::Inspect<T::AccountId>>::balance(asset_id, sender);
-			let asset_transferable_balance = if asset_account_balance >= asset_minimum_balance {
-				asset_account_balance - asset_minimum_balance
-			} else {
-				0u128
-			};
+		let transferable_balance: BalanceOf<T> = match Self::asset_kind(ft_identifier)? {
+			AssetKind::Native => {
+				let minimum_balance = CurrencyOf::<T>::minimum_balance();
 
-			asset_transferable_balance.saturated_into()
+				CurrencyOf::<T>::free_balance(sender).saturating_sub(minimum_balance)
+			},
+			AssetKind::Asset(asset_id) =>
+				<T::Assets as fungibles::Inspect<T::AccountId>>::reducible_balance(
+					asset_id, sender, true,
+				)
+				.saturated_into(),
 		};
 
 		if *amount > transferable_balance {
 			return Err(Error::<T>::InsufficientBalance)
 		}
 
-		let tx_fee_denom = 100u8
-			.checked_div(T::TransactionFee::get())
-			.filter(|value| value <= &100u8)
-			.ok_or(Error::<T>::InsufficientFee)?;
-
-		let fee: BalanceOf<T> = *amount / tx_fee_denom.saturated_into();
-		if fee > *amount {
-			return Err(Error::<T>::InsufficientBalance)
-		}
-		let admin_fee_denom = 100u8
-			.checked_div(T::AdminFee::get())
-			.filter(|value| value <= &100u8)
-			.ok_or(Error::<T>::InsufficientFee)?;
-
-		let admin_fee = fee / admin_fee_denom.saturated_into();
+		let fee_policy = Self::fee_policy(instance_id);
+		let fee = fee_policy.transaction_fee().mul_floor(*amount);
+		let admin_fee = fee_policy.admin_fee().mul_floor(fee);
 		let server_fee = fee - admin_fee;
 		let fee_detail = FeeDetail::new(admin_fee, server_fee, fee);
 
 		Ok(fee_detail)
 	}
 
+	/// Credits the tip fee to the admin and the server, the reference keeps the rest.
+	pub fn do_pay_tip_fee(
+		instance_id: u64,
+		tips_balance_info: &TipsBalanceInfoOf<T>,
+		amount: &BalanceOf<T>,
+	) -> FeeDetail<BalanceOf<T>> {
+		let fee_policy = Self::fee_policy(instance_id);
+		let fee = fee_policy.tip_fee().mul_floor(*amount);
+		let admin_fee = fee_policy.admin_fee().mul_floor(fee);
+		let server_fee = fee - admin_fee;
+
+		if !fee.is_zero() {
+			Self::do_update_withdrawal_balance(tips_balance_info.get_ft_identifier(), admin_fee);
+			Self::do_update_reward_balance(instance_id, tips_balance_info, server_fee);
+		}
+
+		FeeDetail::new(admin_fee, server_fee, fee)
+	}
+
 	pub fn can_pay_fee(key: &TipsBalanceKeyOf<T>, tx_fee: &BalanceOf<T>) -> Result<(), Error<T>> {
 		if tx_fee == &Zero::zero() {
 			return Err(Error::<T>::InsufficientBalance)
@@ -147,6 +135,31 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	pub fn can_claim_with_proof(
+		proof: &ClaimProofOf<T>,
+		signature: &T::Signature,
+	) -> Result<(), Error<T>> {
+		let instance_id = proof.get_instance_id();
+		let current_block_number = <frame_system::Pallet<T>>::block_number();
+
+		if proof.get_expires_at() < current_block_number {
+			return Err(Error::<T>::ProofExpired)
+		}
+
+		if proof.get_nonce() != Self::claim_nonce(instance_id, proof.get_account_id()) {
+			return Err(Error::<T>::InvalidNonce)
+		}
+
+		let signing_key =
+			T::Server::get_signing_key(instance_id).ok_or(Error::<T>::SigningKeyNotSet)?;
+
+		if !signature.verify(&proof.signing_payload()[..], &signing_key) {
+			return Err(Error::<T>::BadSignature)
+		}
+
+		Ok(())
+	}
+
 	pub fn can_claim_tip(
 		key: &TipsBalanceKeyOf<T>,
 		receiver: &AccountIdOf<T>,
@@ -170,12 +183,42 @@ impl<T: Config> Pallet<T> {
 		None
 	}
 
-	pub fn do_update_withdrawal_balance(ft_identifier: &[u8], balance: BalanceOf<T>) {
+	/// Credits the admin fee, setting the `RewardPotShare` of native fees aside for servers.
+	pub fn do_update_withdrawal_balance(ft_identifier: &FtIdentifierOf<T>, balance: BalanceOf<T>) {
+		let reward = match Self::asset_kind(ft_identifier) {
+			Ok(AssetKind::Native) => T::RewardPotShare::get().mul_floor(balance),
+			_ => Zero::zero(),
+		};
+
+		if !reward.is_zero() {
+			RewardPotBalance::<T>::mutate(|value| *value = value.saturating_add(reward));
+		}
+
 		WithdrawalBalance::<T>::mutate(ft_identifier, |value| {
-			*value += balance;
+			*value = value.saturating_add(balance.saturating_sub(reward));
 		});
 	}
 
+	/// Pays admin fees out of the withdrawal balance of the currency.
+	pub fn do_spend_fee(
+		ft_identifier: &FtIdentifierOf<T>,
+		sender: &AccountIdOf<T>,
+		receiver: &AccountIdOf<T>,
+		amount: BalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		let balance = Self::withdrawal_balance(ft_identifier);
+
+		if amount > balance {
+			return Err(Error::<T>::InsufficientBalance.into())
+		}
+
+		Self::do_transfer(ft_identifier, sender, receiver, amount)?;
+
+		WithdrawalBalance::<T>::insert(ft_identifier, balance - amount);
+
+		Ok(())
+	}
+
 	pub fn do_update_reward_balance(
 		instance_id: u64,
 		tips_balance_info: &TipsBalanceInfoOf<T>,
@@ -184,7 +227,7 @@ impl<T: Config> Pallet<T> {
 		let server_id = tips_balance_info.get_server_id();
 		let ft_identifier = tips_balance_info.get_ft_identifier();
 		RewardBalance::<T>::mutate((server_id, instance_id, ft_identifier), |value| {
-			*value += balance;
+			*value = value.saturating_add(balance);
 		});
 	}
 
@@ -206,7 +249,9 @@ impl<T: Config> Pallet<T> {
 				Some(tips_balance) => {
 					if set_empty {
 						tips_balance.set_amount(Zero::zero()); // Set balance to zero
-					} else if tx_fee.is_some() && ft_identifier == b"native" {
+					} else if tx_fee.is_some() &&
+						matches!(Self::asset_kind(ft_identifier), Ok(AssetKind::Native))
+					{
 						// Reduce user balance by the tx fee
 						// As user ask admin server to claim references
 						let current_balance = *tips_balance.get_amount();
@@ -233,86 +278,1322 @@ impl<T: Config> Pallet<T> {
 		total_tip
 	}
 
-	pub fn do_transfer(
-		ft_identifier: &[u8],
-		sender: &AccountIdOf<T>,
-		receiver: &AccountIdOf<T>,
+	/// Only the account that claimed the reference sets its split.
+	pub fn can_set_tip_split(
+		who: &AccountIdOf<T>,
+		server_id: &ServerIdOf<T>,
+		reference_type: &ReferenceTypeOf<T>,
+		reference_id: &ReferenceIdOf<T>,
+	) -> Result<(), Error<T>> {
+		let is_owner = Self::get_tips_balances(server_id, reference_type, reference_id)
+			.iter()
+			.any(|tips_balance| tips_balance.get_account_id().as_ref() == Some(who));
+
+		if !is_owner {
+			return Err(Error::<T>::Unauthorized)
+		}
+
+		Ok(())
+	}
+
+	/// Every share is non zero and together they make up the whole tip.
+	pub fn is_valid_tip_split(splits: &[TipSplitOf<T>]) -> Result<(), Error<T>> {
+		if splits.len() as u32 > T::MaxTipSplits::get() {
+			return Err(Error::<T>::TooManySplits)
+		}
+
+		let total = splits.iter().try_fold(0u32, |total, split| {
+			let share = split.get_share();
+
+			if share.is_zero() {
+				return None
+			}
+
+			total.checked_add(share.deconstruct())
+		});
+
+		if total != Some(Permill::one().deconstruct()) {
+			return Err(Error::<T>::InvalidSplit)
+		}
+
+		Ok(())
+	}
+
+	/// Stores the tip on its reference, or hands it out by the reference's split. Returns the
+	/// tips balances stored on references.
+	pub fn do_store_split_tips_balance(
+		tips_balance: &TipsBalanceOf<T>,
+	) -> Result<Vec<TipsBalanceOf<T>>, DispatchError> {
+		let info = tips_balance.get_tips_balance_info();
+		let server_id = info.get_server_id();
+		let ft_identifier = info.get_ft_identifier();
+		let key = (server_id, info.get_reference_type(), info.get_reference_id());
+
+		let splits = match Self::tip_split(key) {
+			Some(splits) => splits,
+			None => {
+				Self::do_store_tips_balance(tips_balance, false, None);
+
+				return Ok(vec![tips_balance.clone()])
+			},
+		};
+
+		let amount = *tips_balance.get_amount();
+		let mut shares = splits
+			.iter()
+			.map(|split| (split.get_recipient().clone(), split.get_share().mul_floor(amount)))
+			.collect::<Vec<(SplitRecipientOf<T>, BalanceOf<T>)>>();
+		let paid = shares
+			.iter()
+			.fold(BalanceOf::<T>::zero(), |total, (_, share)| total.saturating_add(*share));
+
+		// The first recipient takes the rounding dust
+		if let Some((_, share)) = shares.first_mut() {
+			*share = share.saturating_add(amount.saturating_sub(paid));
+		}
+
+		let sender = Self::tipping_account_id();
+		let tips_balances = with_storage_layer(|| {
+			let mut tips_balances = Vec::new();
+
+			for (recipient, share) in shares.iter() {
+				if share.is_zero() {
+					continue
+				}
+
+				match recipient {
+					SplitRecipient::Account(account_id) =>
+						Self::do_transfer(ft_identifier, &sender, account_id, *share)?,
+					SplitRecipient::Reference(reference_type, reference_id) => {
+						let info = TipsBalanceInfo::new(
+							server_id,
+							reference_type,
+							reference_id,
+							ft_identifier,
+						);
+						let tips_balance = TipsBalance::new(&info, share);
+
+						Self::do_store_tips_balance(&tips_balance, false, None);
+
+						tips_balances.push(tips_balance);
+					},
+				}
+			}
+
+			Ok::<_, DispatchError>(tips_balances)
+		})?;
+
+		Self::deposit_event(Event::TipSplitPaid { tips_balance: tips_balance.clone(), shares });
+
+		Ok(tips_balances)
+	}
+
+	/// Tracks what the tipper sent to a reference nobody claimed yet, it can be reclaimed once
+	/// the tipper goes `TipExpiry` blocks without tipping the reference. Returns whether it was
+	/// tracked.
+	pub fn do_store_tip_contribution(
+		tipper: &AccountIdOf<T>,
+		tips_balance: &TipsBalanceOf<T>,
+	) -> bool {
+		let key = tips_balance.key();
+		let amount = *tips_balance.get_amount();
+		let claimed = Self::tips_balance_by_reference(&key)
+			.map_or(false, |tips_balance| tips_balance.get_account_id().is_some());
+
+		// The reference's old contributions are still being settled and would eat new ones
+		if claimed || amount.is_zero() || TipContributionCleanup::<T>::contains_key(&key) {
+			return false
+		}
+
+		let (server_id, reference_type, reference_id, ft_identifier) = &key;
+		let contribution_key = (server_id, reference_type, reference_id, ft_identifier, tipper);
+		let current_block_number = <frame_system::Pallet<T>>::block_number();
+		let expire_at = current_block_number.saturating_add(T::TipExpiry::get());
+
+		TipContributions::<T>::mutate(contribution_key, |contribution| {
+			contribution.add_tip(amount, expire_at);
+		});
+
+		// The reference is reported once its latest contribution expires
+		if Self::tip_expiry_at(&key) != Some(expire_at) {
+			TipExpiryAt::<T>::insert(&key, expire_at);
+
+			// Not reported when the block is full, the tips can still be reclaimed
+			let _ = TipExpiryTasks::<T>::try_append(expire_at, key);
+		}
+
+		true
+	}
+
+	/// Adds the tip to the lifetime stats of the tipped reference, which claims never reset.
+	pub fn do_update_tip_stats(tipper: &AccountIdOf<T>, tips_balance: &TipsBalanceOf<T>) {
+		let amount = *tips_balance.get_amount();
+
+		if amount.is_zero() {
+			return
+		}
+
+		let key = tips_balance.key();
+		let new_tipper = Self::do_rank_tipper(tipper, &key, amount);
+
+		TipStatsByReference::<T>::mutate(&key, |stats| {
+			stats.add_tip(amount, new_tipper);
+		});
+	}
+
+	/// Adds an anonymous tip to the total of the tipped reference only, the tipper is counted
+	/// when they reveal it.
+	pub fn do_update_anonymous_tip_stats(tips_balance: &TipsBalanceOf<T>) {
+		let amount = *tips_balance.get_amount();
+
+		if amount.is_zero() {
+			return
+		}
+
+		TipStatsByReference::<T>::mutate(tips_balance.key(), |stats| {
+			stats.add_tip(amount, false);
+		});
+	}
+
+	/// Credits a revealed anonymous tip to its tipper, its amount is already in the total.
+	pub fn do_reveal_tip_stats(tipper: &AccountIdOf<T>, tips_balance: &TipsBalanceOf<T>) {
+		let amount = *tips_balance.get_amount();
+
+		if amount.is_zero() {
+			return
+		}
+
+		let key = tips_balance.key();
+
+		if Self::do_rank_tipper(tipper, &key, amount) {
+			TipStatsByReference::<T>::mutate(&key, |stats| {
+				stats.add_tip(Zero::zero(), true);
+			});
+		}
+	}
+
+	/// Adds the amount to the tipper's total and ranks them among the top tippers, returns
+	/// whether it's their first tip to the reference.
+	fn do_rank_tipper(
+		tipper: &AccountIdOf<T>,
+		key: &TipsBalanceKeyOf<T>,
 		amount: BalanceOf<T>,
-	) -> Result<(), DispatchError> {
-		if ft_identifier == b"native" {
-			CurrencyOf::<T>::transfer(sender, receiver, amount, ExistenceRequirement::KeepAlive)?;
+	) -> bool {
+		let (server_id, reference_type, reference_id, ft_identifier) = key;
+		let tipper_key = (server_id, reference_type, reference_id, ft_identifier, tipper);
+
+		let previous_total = Self::tipper_total(tipper_key);
+		let new_total = previous_total.saturating_add(amount);
+
+		TipperTotals::<T>::insert(tipper_key, new_total);
+		TopTippers::<T>::mutate(key, |top_tippers| {
+			top_tippers.retain(|(account_id, _)| account_id != tipper);
+
+			let index = top_tippers
+				.iter()
+				.position(|(_, total)| *total < new_total)
+				.unwrap_or(top_tippers.len());
+
+			// Falls off the list when it's full of bigger tippers
+			let _ = top_tippers.force_insert_keep_left(index, (tipper.clone(), new_total));
+		});
+
+		previous_total.is_zero()
+	}
+
+	/// The commitment an anonymous tipper reveals with the salt they committed to, it only
+	/// matches the reference it was made for.
+	pub fn tip_commitment(
+		tipper: &AccountIdOf<T>,
+		key: &TipsBalanceKeyOf<T>,
+		salt: &HashOf<T>,
+	) -> HashOf<T> {
+		T::Hashing::hash_of(&(tipper, key, salt))
+	}
+
+	/// Pays an expired anonymous tip back to its tipper out of the reserve of the tipping account,
+	/// as long as nobody claimed the reference it was sent to.
+	pub fn do_reclaim_anonymous_tip(
+		tipper: &AccountIdOf<T>,
+		key: &TipsBalanceKeyOf<T>,
+		commitment: &HashOf<T>,
+	) -> Result<(TipsBalanceOf<T>, BalanceOf<T>), DispatchError> {
+		let (anonymous_tip, expires_at) =
+			Self::anonymous_tip(key, commitment).ok_or(Error::<T>::NotExists)?;
+		let mut tips_balance =
+			TipsBalanceByReference::<T>::get(key).ok_or(Error::<T>::NotExists)?;
+
+		if tips_balance.get_account_id().is_some() {
+			return Err(Error::<T>::Unauthorized.into())
+		}
+
+		if <frame_system::Pallet<T>>::block_number() < expires_at {
+			return Err(Error::<T>::NotExpired.into())
+		}
+
+		let amount = (*anonymous_tip.get_amount()).min(*tips_balance.get_amount());
+
+		Self::do_pay_out_tips(
+			tips_balance.get_ft_identifier(),
+			&Self::tipping_account_id(),
+			tipper,
+			amount,
+		)?;
+
+		AnonymousTips::<T>::remove(key, commitment);
+
+		let remaining = *tips_balance.get_amount() - amount;
+
+		tips_balance.set_amount(remaining);
+
+		if remaining.is_zero() {
+			TipsBalanceByReference::<T>::remove(key);
+			TipExpiryAt::<T>::remove(key);
 		} else {
-			let asset_id = Self::asset_id(ft_identifier)?;
-			let _ = <T::Assets as fungibles::Transfer<T::AccountId>>::transfer(
-				asset_id,
-				sender,
-				receiver,
-				amount.saturated_into(),
-				true,
-			)?;
+			TipsBalanceByReference::<T>::insert(key, &tips_balance);
 		}
 
-		Ok(())
+		Ok((tips_balance, amount))
 	}
 
-	pub fn do_store_tips_balances(
-		server_id: &AccountIdOf<T>,
-		references: &References,
-		account_references: &References,
-		ft_identifiers: &[FtIdentifier],
-		account_id: &AccountIdOf<T>,
-		tx_fee: &BalanceOf<T>,
-	) -> Vec<TipsBalanceOf<T>> {
-		let mut account_tips_balances = Vec::<TipsBalanceOf<T>>::new();
+	/// Settles up to `MaxTipContributionRemovals` contributions of the claimed reference into the
+	/// reference it was claimed to, the rest are left to `on_idle`.
+	pub fn do_remove_tip_contributions(
+		key: &TipsBalanceKeyOf<T>,
+		claimed_to: &TipsBalanceKeyOf<T>,
+	) {
+		TipExpiryAt::<T>::remove(key);
 
-		let account_reference_type = account_references.get_reference_type();
-		let account_reference_id = &account_references.get_reference_ids()[0];
+		// Contributions still settling from an earlier claim stay credited where they went
+		let claimed_to = Self::tip_contribution_cleanup(key).unwrap_or_else(|| claimed_to.clone());
 
-		for ft_identifier in ft_identifiers.iter() {
-			let mut tip: BalanceOf<T> = Zero::zero();
+		Self::do_clear_tip_contributions(key, &claimed_to);
+	}
 
-			let reference_type = references.get_reference_type();
-			let reference_ids = references.get_reference_ids();
+	/// Moves what each tipper held for the reference to the tipping account and removes the
+	/// contribution, so every batch starts from the first one left. What couldn't be moved is
+	/// taken off the reference the tips were claimed to.
+	fn do_clear_tip_contributions(key: &TipsBalanceKeyOf<T>, claimed_to: &TipsBalanceKeyOf<T>) {
+		let (server_id, reference_type, reference_id, ft_identifier) = key;
+		let prefix = (server_id, reference_type, reference_id, ft_identifier);
+		let limit = T::MaxTipContributionRemovals::get() as usize;
+		let contributions = TipContributions::<T>::iter_prefix(prefix)
+			.take(limit.saturating_add(1))
+			.collect::<Vec<(AccountIdOf<T>, TipContributionOf<T>)>>();
 
-			// Get balance for references
-			// Store the balance to account reference balance
-			for reference_id in reference_ids {
-				let server_id = server_id.clone();
-				let key = (server_id, reference_type, reference_id, ft_identifier);
-				let tips_balance = TipsBalanceByReference::<T>::take(&key);
+		for (tipper, contribution) in contributions.iter().take(limit) {
+			let amount = contribution.get_amount();
+			let shortfall =
+				amount.saturating_sub(Self::do_settle_tip(ft_identifier, tipper, amount));
 
-				if let Some(tips_balance) = tips_balance {
-					let amount = tips_balance.get_amount();
-					if *amount > Zero::zero() {
-						tip = tip.saturating_add(*amount);
+			if !shortfall.is_zero() {
+				TipsBalanceByReference::<T>::mutate(claimed_to, |tips_balance| {
+					if let Some(tips_balance) = tips_balance {
+						tips_balance
+							.set_amount(tips_balance.get_amount().saturating_sub(shortfall));
 					}
-				}
+				});
 			}
 
-			let account_tips_balance_info = TipsBalanceInfo::new(
+			TipContributions::<T>::remove((
 				server_id,
-				account_reference_type,
-				account_reference_id,
+				reference_type,
+				reference_id,
 				ft_identifier,
+				tipper,
+			));
+		}
+
+		let settling = contributions.len() > limit;
+
+		match (settling, TipContributionCleanup::<T>::contains_key(key)) {
+			(true, false) => {
+				TipContributionCleanup::<T>::insert(key, claimed_to);
+				PendingSettlements::<T>::mutate(claimed_to, |pending| {
+					*pending = pending.saturating_add(1);
+				});
+			},
+			(false, true) => {
+				TipContributionCleanup::<T>::remove(key);
+
+				let pending = Self::pending_settlements(claimed_to).saturating_sub(1);
+
+				if pending.is_zero() {
+					PendingSettlements::<T>::remove(claimed_to);
+
+					Self::do_pay_pending_claim_fee(claimed_to);
+				} else {
+					PendingSettlements::<T>::insert(claimed_to, pending);
+				}
+			},
+			_ => {},
+		}
+	}
+
+	/// Pays the claim fee to the server of the reference out of its claimed tips, or leaves it
+	/// pending until they are settled on the tipping account.
+	pub fn do_pay_claim_fee(
+		key: &TipsBalanceKeyOf<T>,
+		tx_fee: BalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		if !Self::pending_settlements(key).is_zero() {
+			PendingClaimFees::<T>::mutate(key, |pending_fee| {
+				*pending_fee = pending_fee.saturating_add(tx_fee);
+			});
+
+			return Ok(())
+		}
+
+		let (server_id, _, _, ft_identifier) = key;
+
+		Self::do_pay_out_tips(ft_identifier, &Self::tipping_account_id(), server_id, tx_fee)
+	}
+
+	fn do_pay_pending_claim_fee(key: &TipsBalanceKeyOf<T>) {
+		let tx_fee = PendingClaimFees::<T>::take(key);
+
+		if tx_fee.is_zero() {
+			return
+		}
+
+		if let Err(error) = Self::do_pay_claim_fee(key, tx_fee) {
+			log::warn!(
+				target: "runtime::tipping",
+				"Failed to pay a claim fee of {:?}: {:?}",
+				tx_fee,
+				error,
 			);
+		}
+	}
 
-			let mut account_tips_balance = TipsBalance::new(&account_tips_balance_info, &tip);
+	/// The weight of removing the contributions of every reference a claim collects from.
+	pub fn claim_contributions_weight(
+		references: &ReferencesOf<T>,
+		ft_identifiers: usize,
+	) -> Weight {
+		// The account reference is claimed too
+		let keys = references.get_reference_ids().len().saturating_add(1);
+		let weight = T::WeightInfo::remove_tip_contributions(T::MaxTipContributionRemovals::get());
 
-			account_tips_balance.set_account_id(account_id);
+		weight.saturating_mul(keys.saturating_mul(ft_identifiers) as u64)
+	}
 
-			let tips = Self::do_store_tips_balance(&account_tips_balance, false, Some(*tx_fee));
+	/// Keeps settling the contributions of claimed references, as far as the idle weight goes.
+	pub fn do_clear_tip_contribution_backlog(remaining_weight: Weight) -> Weight {
+		let base_weight = T::DbWeight::get().reads(1);
+		let weight = T::WeightInfo::remove_tip_contributions(T::MaxTipContributionRemovals::get());
 
-			account_tips_balance.set_amount(tips);
-			account_tips_balances.push(account_tips_balance);
+		if base_weight.ref_time() > remaining_weight.ref_time() {
+			return Weight::zero()
 		}
 
-		account_tips_balances
+		let count =
+			remaining_weight.saturating_sub(base_weight).ref_time() / weight.ref_time().max(1);
+		let backlog = TipContributionCleanup::<T>::iter()
+			.take(count.saturated_into())
+			.collect::<Vec<(TipsBalanceKeyOf<T>, TipsBalanceKeyOf<T>)>>();
+
+		for (key, claimed_to) in backlog.iter() {
+			Self::do_clear_tip_contributions(key, claimed_to);
+		}
+
+		base_weight.saturating_add(weight.saturating_mul(backlog.len() as u64))
 	}
 
-	pub fn asset_id(ft_identifier: &[u8]) -> Result<u32, Error<T>> {
-		let str_num =
-			String::from_utf8(ft_identifier.to_vec()).map_err(|_| Error::<T>::WrongFormat)?;
+	/// Reports the references whose tips expired with `TipsExpired`, as far as the idle weight
+	/// goes. This is only a notification, no funds move until each tipper calls `reclaim_tip`.
+	pub fn do_sweep_expired_tips(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+		let mut total_weight = T::DbWeight::get().reads_writes(1, 1);
+		let mut cursor = Self::tip_expiry_cursor();
+
+		while cursor <= now {
+			let tasks = TipExpiryTasks::<T>::decode_len(cursor).unwrap_or_default();
+			let weight = T::DbWeight::get()
+				.reads_writes(1, 1)
+				.saturating_add(T::WeightInfo::on_idle_tip_expiry().saturating_mul(tasks as u64));
+
+			if total_weight.saturating_add(weight).ref_time() > remaining_weight.ref_time() {
+				break
+			}
+
+			total_weight = total_weight.saturating_add(weight);
+
+			for key in TipExpiryTasks::<T>::take(cursor) {
+				// A later tip pushed the expiry back
+				if Self::tip_expiry_at(&key) != Some(cursor) {
+					continue
+				}
+
+				if let Some(tips_balance) = Self::tips_balance_by_reference(&key) {
+					if tips_balance.get_account_id().is_none() &&
+						!tips_balance.get_amount().is_zero()
+					{
+						Self::deposit_event(Event::TipsExpired { tips_balance });
+					}
+				}
+			}
+
+			cursor = cursor.saturating_add(One::one());
+		}
+
+		TipExpiryCursor::<T>::put(cursor);
+
+		total_weight
+	}
 
-		str_num.parse::<u32>().map_err(|_| Error::<T>::WrongFormat)
+	/// Native tips are reserved under the pallet id, on the tipping account once their reference
+	/// is claimed and on the tipper before. Fees and the existential deposit stay free. Asset tips
+	/// sit on the tipping account, above the asset's minimum balance, or frozen on the tipper.
+	pub fn do_transfer(
+		ft_identifier: &FtIdentifierOf<T>,
+		sender: &AccountIdOf<T>,
+		receiver: &AccountIdOf<T>,
+		amount: BalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		with_storage_layer(|| {
+			match Self::asset_kind(ft_identifier)? {
+				AssetKind::Native => CurrencyOf::<T>::transfer(
+					sender,
+					receiver,
+					amount,
+					ExistenceRequirement::KeepAlive,
+				)?,
+				AssetKind::Asset(asset_id) => {
+					let _ = <T::Assets as fungibles::Transfer<T::AccountId>>::transfer(
+						asset_id,
+						sender,
+						receiver,
+						amount.saturated_into(),
+						true,
+					)?;
+				},
+			}
+
+			Ok(())
+		})
+	}
+
+	/// Sets aside tips the tipping account received for references.
+	pub fn do_reserve_tips(
+		ft_identifier: &FtIdentifierOf<T>,
+		amount: BalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		if let AssetKind::Native = Self::asset_kind(ft_identifier)? {
+			CurrencyOf::<T>::reserve_named(&PALLET_ID.0, &Self::tipping_account_id(), amount)?;
+		}
+
+		Ok(())
+	}
+
+	/// Pays tips out of the reserve of the tipping account.
+	pub fn do_pay_out_tips(
+		ft_identifier: &FtIdentifierOf<T>,
+		sender: &AccountIdOf<T>,
+		receiver: &AccountIdOf<T>,
+		amount: BalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		with_storage_layer(|| {
+			if let AssetKind::Native = Self::asset_kind(ft_identifier)? {
+				let remaining = CurrencyOf::<T>::unreserve_named(&PALLET_ID.0, sender, amount);
+
+				if !remaining.is_zero() {
+					return Err(Error::<T>::InsufficientBalance.into())
+				}
+			}
+
+			Self::do_transfer(ft_identifier, sender, receiver, amount)
+		})
+	}
+
+	/// Holds funds on the account, it keeps them until they are released or settled.
+	pub fn do_hold(
+		ft_identifier: &FtIdentifierOf<T>,
+		who: &AccountIdOf<T>,
+		amount: BalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		match Self::asset_kind(ft_identifier)? {
+			AssetKind::Native => CurrencyOf::<T>::reserve_named(&PALLET_ID.0, who, amount)?,
+			AssetKind::Asset(asset_id) => {
+				let amount: AssetBalance = amount.saturated_into();
+				let balance = <T::Assets as fungibles::Inspect<T::AccountId>>::reducible_balance(
+					asset_id, who, true,
+				);
+
+				if amount > balance {
+					return Err(Error::<T>::InsufficientBalance.into())
+				}
+
+				FrozenAssets::<T>::mutate(asset_id, who, |frozen| {
+					*frozen = frozen.saturating_add(amount);
+				});
+			},
+		}
+
+		Ok(())
+	}
+
+	/// Hands tips the tipping account just received back to the tipper, held until the
+	/// reference is claimed or the tipper reclaims them.
+	pub fn do_hold_tip(
+		ft_identifier: &FtIdentifierOf<T>,
+		tipper: &AccountIdOf<T>,
+		amount: BalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		with_storage_layer(|| {
+			Self::do_transfer(ft_identifier, &Self::tipping_account_id(), tipper, amount)?;
+			Self::do_hold(ft_identifier, tipper, amount)
+		})
+	}
+
+	/// Gives the account back what it held.
+	pub fn do_release(
+		ft_identifier: &FtIdentifierOf<T>,
+		who: &AccountIdOf<T>,
+		amount: BalanceOf<T>,
+	) {
+		match Self::asset_kind(ft_identifier) {
+			Ok(AssetKind::Native) => {
+				let _ = CurrencyOf::<T>::unreserve_named(&PALLET_ID.0, who, amount);
+			},
+			Ok(AssetKind::Asset(asset_id)) => Self::do_unfreeze(asset_id, who, amount),
+			Err(_) => {},
+		}
+	}
+
+	/// Moves what the tipper held for a claimed reference into the reserve of the tipping
+	/// account and returns how much it moved. Only a forced transfer or slash of the tipper's
+	/// funds leaves less to move, a failed move gives the tipper back what they held.
+	pub fn do_settle_tip(
+		ft_identifier: &FtIdentifierOf<T>,
+		tipper: &AccountIdOf<T>,
+		amount: BalanceOf<T>,
+	) -> BalanceOf<T> {
+		let tipping_account_id = Self::tipping_account_id();
+
+		let result = match Self::asset_kind(ft_identifier) {
+			Ok(AssetKind::Native) => CurrencyOf::<T>::repatriate_reserved_named(
+				&PALLET_ID.0,
+				tipper,
+				&tipping_account_id,
+				amount,
+				BalanceStatus::Reserved,
+			)
+			.map(|remaining| amount.saturating_sub(remaining)),
+			Ok(AssetKind::Asset(asset_id)) => {
+				Self::do_unfreeze(asset_id, tipper, amount);
+
+				let balance = <T::Assets as fungibles::Inspect<T::AccountId>>::reducible_balance(
+					asset_id, tipper, false,
+				);
+
+				<T::Assets as fungibles::Transfer<T::AccountId>>::transfer(
+					asset_id,
+					tipper,
+					&tipping_account_id,
+					balance.min(amount.saturated_into()),
+					false,
+				)
+				.map(|settled| settled.saturated_into())
+			},
+			Err(error) => Err(error.into()),
+		};
+
+		match result {
+			Ok(settled) => settled,
+			Err(error) => {
+				Self::do_release(ft_identifier, tipper, amount);
+
+				log::warn!(
+					target: "runtime::tipping",
+					"Failed to settle a tip of {:?}: {:?}",
+					amount,
+					error,
+				);
+
+				Zero::zero()
+			},
+		}
+	}
+
+	fn do_unfreeze(asset_id: AssetId, who: &AccountIdOf<T>, amount: BalanceOf<T>) {
+		let amount: AssetBalance = amount.saturated_into();
+
+		FrozenAssets::<T>::mutate_exists(asset_id, who, |frozen| {
+			*frozen = frozen
+				.map(|frozen| frozen.saturating_sub(amount))
+				.filter(|frozen| !frozen.is_zero());
+		});
+	}
+
+	/// Holds the tip on the tipper while its reference is unclaimed, so the tipper can reclaim
+	/// it, and reserves it on the tipping account otherwise.
+	pub fn do_hold_tips_balance(
+		tipper: Option<&AccountIdOf<T>>,
+		tips_balance: &TipsBalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		let ft_identifier = tips_balance.get_ft_identifier();
+		let amount = *tips_balance.get_amount();
+
+		match tipper {
+			Some(tipper) if Self::do_store_tip_contribution(tipper, tips_balance) =>
+				Self::do_hold_tip(ft_identifier, tipper, amount),
+			_ => Self::do_reserve_tips(ft_identifier, amount),
+		}
+	}
+
+	/// The tipping account holds the minimum balance of the asset, so payouts never reap it.
+	pub fn is_asset_funded(kind: AssetKind) -> bool {
+		match kind {
+			AssetKind::Native => true,
+			AssetKind::Asset(asset_id) => {
+				let tipping_account_id = Self::tipping_account_id();
+				let minimum_balance =
+					<T::Assets as fungibles::Inspect<T::AccountId>>::minimum_balance(asset_id);
+				let balance = <T::Assets as fungibles::Inspect<T::AccountId>>::balance(
+					asset_id,
+					&tipping_account_id,
+				);
+
+				balance >= minimum_balance
+			},
+		}
+	}
+
+	pub fn do_store_tips_balances(
+		server_id: &AccountIdOf<T>,
+		references: &ReferencesOf<T>,
+		account_references: &ReferencesOf<T>,
+		ft_identifiers: &[FtIdentifierOf<T>],
+		account_id: &AccountIdOf<T>,
+		tx_fee: &BalanceOf<T>,
+	) -> Vec<TipsBalanceOf<T>> {
+		let mut account_tips_balances = Vec::<TipsBalanceOf<T>>::new();
+
+		let account_reference_type = account_references.get_reference_type();
+		let account_reference_id = &account_references.get_reference_ids()[0];
+
+		for ft_identifier in ft_identifiers.iter() {
+			let mut tip: BalanceOf<T> = Zero::zero();
+
+			let reference_type = references.get_reference_type();
+			let reference_ids = references.get_reference_ids();
+
+			let account_tips_balance_info = TipsBalanceInfo::new(
+				server_id,
+				account_reference_type,
+				account_reference_id,
+				ft_identifier,
+			);
+			let account_key = account_tips_balance_info.key();
+
+			// The account reference is claimed from now on
+			let mut claimed_keys = vec![account_key.clone()];
+
+			// Get balance for references
+			// Store the balance to account reference balance
+			for reference_id in reference_ids.iter() {
+				let server_id = server_id.clone();
+				let key = (
+					server_id,
+					reference_type.clone(),
+					reference_id.clone(),
+					ft_identifier.clone(),
+				);
+				let tips_balance = TipsBalanceByReference::<T>::take(&key);
+
+				if let Some(tips_balance) = tips_balance {
+					let amount = tips_balance.get_amount();
+					if *amount > Zero::zero() {
+						tip = tip.saturating_add(*amount);
+					}
+				}
+
+				claimed_keys.push(key);
+			}
+
+			let mut account_tips_balance = TipsBalance::new(&account_tips_balance_info, &tip);
+
+			account_tips_balance.set_account_id(account_id);
+
+			let tips = Self::do_store_tips_balance(&account_tips_balance, false, Some(*tx_fee));
+
+			account_tips_balance.set_amount(tips);
+
+			// Settled once the account reference is credited, so what can't be moved comes off it
+			for key in claimed_keys.iter() {
+				Self::do_remove_tip_contributions(key, &account_key);
+			}
+
+			if let Some(tips_balance) = Self::tips_balance_by_reference(&account_key) {
+				account_tips_balance.set_amount(*tips_balance.get_amount());
+			}
+
+			account_tips_balances.push(account_tips_balance);
+		}
+
+		account_tips_balances
+	}
+
+	pub fn can_schedule_subscription(when: T::BlockNumber) -> Result<(), Error<T>> {
+		let scheduled = SubscriptionTasks::<T>::decode_len(when).unwrap_or_default();
+
+		if scheduled as u32 >= T::MaxSubscriptionsPerBlock::get() {
+			return Err(Error::<T>::FailedToSchedule)
+		}
+
+		Ok(())
+	}
+
+	pub fn do_schedule_subscription(
+		subscription_id: SubscriptionId,
+		when: T::BlockNumber,
+	) -> Result<(), Error<T>> {
+		SubscriptionTasks::<T>::try_append(when, subscription_id)
+			.map_err(|_| Error::<T>::FailedToSchedule)
+	}
+
+	pub fn do_unschedule_subscription(subscription_id: SubscriptionId, when: T::BlockNumber) {
+		let mut tasks = SubscriptionTasks::<T>::take(when);
+
+		tasks.retain(|e| *e != subscription_id);
+
+		if !tasks.is_empty() {
+			SubscriptionTasks::<T>::insert(when, tasks);
+		}
+	}
+
+	pub fn do_charge_subscriptions(when: T::BlockNumber, tasks: Vec<SubscriptionId>) -> Weight {
+		let mut total_weight = Weight::zero();
+		let receiver = Self::tipping_account_id();
+
+		for subscription_id in tasks.iter() {
+			let subscription_id = *subscription_id;
+			let subscription = SubscriptionById::<T>::get(subscription_id);
+
+			if let Some(mut subscription) = subscription {
+				if subscription.get_status() != SubscriptionStatus::Active {
+					continue
+				}
+
+				total_weight += T::WeightInfo::on_initialize_subscription()
+					.saturating_add(T::WeightInfo::split_tip(T::MaxTipSplits::get()));
+
+				let subscriber = subscription.get_subscriber();
+				let tips_balance_info = subscription.get_tips_balance_info();
+				let ft_identifier = tips_balance_info.get_ft_identifier();
+				let amount = *subscription.get_amount();
+				let next_payment_at = when.saturating_add(subscription.get_period());
+
+				let instance_id = subscription.get_instance_id();
+				let charged = Self::can_tip_with(ft_identifier, &amount)
+					.and_then(|_| {
+						Self::can_access_instance(tips_balance_info.get_server_id(), instance_id)
+					})
+					.map_err(DispatchError::from)
+					.and_then(|_| {
+						// The split may fail after the transfer, charge all or nothing
+						with_storage_layer(|| {
+							Self::do_transfer(ft_identifier, subscriber, &receiver, amount)?;
+
+							let fee_detail =
+								Self::do_pay_tip_fee(instance_id, tips_balance_info, &amount);
+							let net_tip = amount - fee_detail.total_fee();
+							let tips_balance = TipsBalance::new(tips_balance_info, &net_tip);
+
+							for stored_tips_balance in
+								Self::do_store_split_tips_balance(&tips_balance)?
+							{
+								Self::do_hold_tips_balance(Some(subscriber), &stored_tips_balance)?;
+							}
+
+							Self::do_schedule_subscription(subscription_id, next_payment_at)?;
+
+							Ok(tips_balance)
+						})
+					});
+
+				match charged {
+					Ok(tips_balance) => {
+						Self::do_update_tip_stats(subscriber, &tips_balance);
+
+						subscription.set_next_payment_at(Some(next_payment_at));
+
+						SubscriptionById::<T>::insert(subscription_id, &subscription);
+
+						Self::deposit_event(Event::SubscriptionCharged {
+							subscription_id,
+							tips_balance,
+							next_payment_at,
+						});
+					},
+					Err(_) => {
+						// Suspend instead of retrying, the subscriber resumes it manually
+						subscription.set_status(SubscriptionStatus::Suspended);
+						subscription.set_next_payment_at(None);
+
+						SubscriptionById::<T>::insert(subscription_id, &subscription);
+
+						Self::deposit_event(Event::SubscriptionSuspended { subscription_id, when });
+					},
+				}
+			}
+		}
+
+		total_weight
+	}
+
+	/// Every beneficiary has a non zero share and together they make up the whole reward.
+	pub fn is_valid_reward_distribution(
+		beneficiaries: &[RewardBeneficiaryOf<T>],
+		period: T::BlockNumber,
+	) -> Result<(), Error<T>> {
+		if period.is_zero() {
+			return Err(Error::<T>::InvalidPeriod)
+		}
+
+		if beneficiaries.len() as u32 > T::MaxRewardBeneficiaries::get() {
+			return Err(Error::<T>::TooManyBeneficiaries)
+		}
+
+		let total = beneficiaries.iter().try_fold(0u32, |total, beneficiary| {
+			let share = beneficiary.get_share();
+
+			if share.is_zero() {
+				return None
+			}
+
+			total.checked_add(share.deconstruct())
+		});
+
+		if total != Some(Permill::one().deconstruct()) {
+			return Err(Error::<T>::InvalidBeneficiaries)
+		}
+
+		Ok(())
+	}
+
+	pub fn do_schedule_reward_payout(
+		instance_id: ServerId,
+		when: T::BlockNumber,
+	) -> Result<(), Error<T>> {
+		RewardPayoutTasks::<T>::try_append(when, instance_id)
+			.map_err(|_| Error::<T>::FailedToSchedule)
+	}
+
+	pub fn do_unschedule_reward_payout(instance_id: ServerId, when: T::BlockNumber) {
+		let mut tasks = RewardPayoutTasks::<T>::take(when);
+
+		tasks.retain(|e| *e != instance_id);
+
+		if !tasks.is_empty() {
+			RewardPayoutTasks::<T>::insert(when, tasks);
+		}
+	}
+
+	/// Hands up to `MaxRewardTransfers` unpaid rewards back to the server, it withdraws them
+	/// with `withdraw_reward`. Returns the unscheduled distribution while some are left.
+	pub fn do_remove_reward_distribution(
+		instance_id: ServerId,
+		mut distribution: RewardDistributionOf<T>,
+	) -> Option<RewardDistributionOf<T>> {
+		let server_id = distribution.get_server_id().clone();
+
+		if let Some(when) = distribution.get_next_payout_at() {
+			Self::do_unschedule_reward_payout(instance_id, when);
+		}
+
+		let limit = T::MaxRewardTransfers::get() as usize;
+		let unpaid = UnpaidRewards::<T>::iter_prefix(instance_id)
+			.take(limit)
+			.collect::<Vec<((AccountIdOf<T>, FtIdentifierOf<T>), BalanceOf<T>)>>();
+
+		for ((account_id, ft_identifier), amount) in unpaid.into_iter() {
+			UnpaidRewards::<T>::remove((instance_id, account_id, &ft_identifier));
+			RewardBalance::<T>::mutate((&server_id, instance_id, ft_identifier), |value| {
+				*value = value.saturating_add(amount);
+			});
+		}
+
+		if UnpaidRewards::<T>::iter_prefix(instance_id).next().is_some() {
+			distribution.set_next_payout_at(None);
+			RewardDistributionByInstance::<T>::insert(instance_id, &distribution);
+
+			return Some(distribution)
+		}
+
+		RewardDistributionByInstance::<T>::remove(instance_id);
+
+		None
+	}
+
+	/// Retries the unpaid rewards of the instance, then splits its reward balances between the
+	/// beneficiaries, making at most `MaxRewardTransfers` transfers. Whatever fails to transfer
+	/// or doesn't fit is kept for the next payout.
+	pub fn do_distribute_reward(
+		instance_id: ServerId,
+		distribution: &RewardDistributionOf<T>,
+	) -> (Vec<RewardPayoutOf<T>>, Vec<RewardPayoutOf<T>>) {
+		let sender = Self::tipping_account_id();
+		let server_id = distribution.get_server_id();
+		let beneficiaries = distribution.get_beneficiaries();
+		let limit = T::MaxRewardTransfers::get() as usize;
+
+		let mut payouts = UnpaidRewards::<T>::iter_prefix(instance_id)
+			.take(limit)
+			.map(|((account_id, ft_identifier), amount)| (account_id, ft_identifier, amount))
+			.collect::<Vec<RewardPayoutOf<T>>>();
+
+		for (account_id, ft_identifier, _) in payouts.iter() {
+			UnpaidRewards::<T>::remove((instance_id, account_id, ft_identifier));
+		}
+
+		// Each currency is split between all the beneficiaries
+		let currencies = limit.saturating_sub(payouts.len()) / beneficiaries.len().max(1);
+		let reward_balances = RewardBalance::<T>::iter_prefix((server_id, instance_id))
+			.take(currencies)
+			.collect::<Vec<(FtIdentifierOf<T>, BalanceOf<T>)>>();
+
+		for (ft_identifier, amount) in reward_balances.into_iter() {
+			RewardBalance::<T>::remove((server_id, instance_id, &ft_identifier));
+
+			let mut shares = beneficiaries
+				.iter()
+				.map(|beneficiary| {
+					let share = beneficiary.get_share().mul_floor(amount);
+
+					(beneficiary.get_account_id().clone(), ft_identifier.clone(), share)
+				})
+				.collect::<Vec<RewardPayoutOf<T>>>();
+			let paid = shares
+				.iter()
+				.fold(BalanceOf::<T>::zero(), |total, (_, _, share)| total.saturating_add(*share));
+
+			// The first beneficiary takes the rounding dust
+			if let Some((_, _, share)) = shares.first_mut() {
+				*share = share.saturating_add(amount.saturating_sub(paid));
+			}
+
+			payouts.append(&mut shares);
+		}
+
+		let mut success = Vec::new();
+		let mut failed = Vec::new();
+
+		for payout in payouts.into_iter() {
+			let (account_id, ft_identifier, amount) = &payout;
+
+			if amount.is_zero() {
+				continue
+			}
+
+			if Self::do_transfer(ft_identifier, &sender, account_id, *amount).is_ok() {
+				success.push(payout);
+			} else {
+				UnpaidRewards::<T>::mutate((instance_id, account_id, ft_identifier), |value| {
+					*value = value.saturating_add(*amount);
+				});
+
+				failed.push(payout);
+			}
+		}
+
+		(success, failed)
+	}
+
+	pub fn do_pay_out_rewards(when: T::BlockNumber, tasks: Vec<ServerId>) -> Weight {
+		let mut total_weight = Weight::zero();
+
+		for instance_id in tasks.iter() {
+			let instance_id = *instance_id;
+			let distribution = Self::reward_distribution(instance_id);
+
+			// Skips payouts moved by a later `set_reward_distribution`
+			let mut distribution = match distribution {
+				Some(distribution) if distribution.get_next_payout_at() == Some(when) =>
+					distribution,
+				_ => continue,
+			};
+
+			let (success, failed) = Self::do_distribute_reward(instance_id, &distribution);
+			let transfers = success.len().saturating_add(failed.len()) as u32;
+
+			total_weight += T::WeightInfo::on_initialize_reward_payout(transfers);
+
+			let next_payout_at = when.saturating_add(distribution.get_period());
+			let scheduled = Self::do_schedule_reward_payout(instance_id, next_payout_at).is_ok();
+
+			distribution.set_next_payout_at(if scheduled { Some(next_payout_at) } else { None });
+
+			RewardDistributionByInstance::<T>::insert(instance_id, &distribution);
+
+			Self::deposit_event(Event::RewardDistributed { instance_id, success, failed });
+
+			if !scheduled {
+				Self::deposit_event(Event::RewardPayoutSuspended { instance_id, when });
+			}
+		}
+
+		total_weight
+	}
+
+	pub fn receipt_id(
+		sender: &AccountIdOf<T>,
+		info: &TipsBalanceInfoOf<T>,
+		amount: &BalanceOf<T>,
+		nonce: u64,
+	) -> HashOf<T> {
+		T::Hashing::hash_of(&(sender, info, amount, nonce))
+	}
+
+	pub fn do_generate_receipt_id(
+		sender: &AccountIdOf<T>,
+		info: &TipsBalanceInfoOf<T>,
+		amount: &BalanceOf<T>,
+	) -> HashOf<T> {
+		let nonce = Self::receipt_nonce();
+
+		ReceiptNonce::<T>::put(nonce.wrapping_add(1));
+
+		Self::receipt_id(sender, info, amount, nonce)
+	}
+
+	/// The base deposit plus the per byte deposit for the encoded memo.
+	pub fn memo_deposit_of(memo: &MemoOf<T>) -> BalanceOf<T> {
+		let bytes = BalanceOf::<T>::from(memo.encoded_size() as u32);
+
+		T::MemoDepositPerByte::get()
+			.saturating_mul(bytes)
+			.saturating_add(T::MemoDepositBase::get())
+	}
+
+	/// Stores the memo against a deposit reserved from the sender, returned when they remove it.
+	pub fn do_store_memo(
+		memo_id: &HashOf<T>,
+		sender: &AccountIdOf<T>,
+		memo: &MemoOf<T>,
+	) -> Result<(), Error<T>> {
+		let deposit = Self::memo_deposit_of(memo);
+
+		CurrencyOf::<T>::reserve_named(&MEMO_RESERVE_ID, sender, deposit)
+			.map_err(|_| Error::<T>::InsufficientBalance)?;
+
+		Memos::<T>::insert(memo_id, memo);
+		MemoDeposit::<T>::insert(memo_id, (sender, deposit));
+
+		Ok(())
+	}
+
+	pub fn do_remove_memo(
+		memo_id: &HashOf<T>,
+		who: &AccountIdOf<T>,
+	) -> Result<BalanceOf<T>, Error<T>> {
+		let (depositor, deposit) = Self::memo_deposit(memo_id).ok_or(Error::<T>::NotExists)?;
+
+		if &depositor != who {
+			return Err(Error::<T>::Unauthorized)
+		}
+
+		CurrencyOf::<T>::unreserve_named(&MEMO_RESERVE_ID, &depositor, deposit);
+		Memos::<T>::remove(memo_id);
+		MemoDeposit::<T>::remove(memo_id);
+
+		Ok(deposit)
+	}
+
+	pub fn do_store_receipt_by_content(receipt: &ReceiptOf<T>) {
+		let info = receipt.get_info();
+		let key = (info.get_server_id(), info.get_reference_id(), receipt.get_from());
+
+		ReceiptByContent::<T>::insert(key, receipt.get_id());
+	}
+
+	/// Revokes access, unless the buyer paid again with a newer receipt.
+	pub fn do_remove_receipt_by_content(receipt: &ReceiptOf<T>) {
+		let info = receipt.get_info();
+		let key = (info.get_server_id(), info.get_reference_id(), receipt.get_from());
+
+		if ReceiptByContent::<T>::get(key) == Some(receipt.get_id()) {
+			ReceiptByContent::<T>::remove(key);
+		}
+	}
+
+	pub fn can_schedule_escrow(when: T::BlockNumber) -> Result<(), Error<T>> {
+		let scheduled = EscrowTasks::<T>::decode_len(when).unwrap_or_default();
+
+		if scheduled as u32 >= T::MaxEscrowsPerBlock::get() {
+			return Err(Error::<T>::FailedToSchedule)
+		}
+
+		Ok(())
+	}
+
+	pub fn do_unschedule_escrow(receipt_id: &HashOf<T>, when: T::BlockNumber) {
+		let mut tasks = EscrowTasks::<T>::take(when);
+
+		tasks.retain(|e| e != receipt_id);
+
+		if !tasks.is_empty() {
+			EscrowTasks::<T>::insert(when, tasks);
+		}
+	}
+
+	pub fn do_release_escrow(receipt_id: &HashOf<T>) -> Result<ReceiptOf<T>, DispatchError> {
+		let mut receipt = Receipts::<T>::get(receipt_id).ok_or(Error::<T>::NotExists)?;
+
+		if receipt.get_status() != ReceiptStatus::Escrowed {
+			return Err(Error::<T>::InvalidStatus.into())
+		}
+
+		let escrow = Escrows::<T>::get(receipt_id).ok_or(Error::<T>::NotExists)?;
+		let receiver = receipt.get_to().clone().ok_or(Error::<T>::NotExists)?;
+		let info = receipt.get_info().clone();
+		let ft_identifier = info.get_ft_identifier();
+		let fee_detail = escrow.get_fee_detail();
+		let buyer = receipt.get_from();
+		let net_pay = receipt.get_amount().saturating_sub(fee_detail.total_fee());
+
+		with_storage_layer(|| {
+			Self::do_release(ft_identifier, buyer, *receipt.get_amount());
+			Self::do_transfer(ft_identifier, buyer, &receiver, net_pay)?;
+			Self::do_transfer(
+				ft_identifier,
+				buyer,
+				&Self::tipping_account_id(),
+				fee_detail.total_fee(),
+			)
+		})?;
+
+		Self::do_update_withdrawal_balance(ft_identifier, fee_detail.admin_fee());
+		Self::do_update_reward_balance(escrow.get_instance_id(), &info, fee_detail.server_fee());
+
+		receipt.set_status(ReceiptStatus::Released);
+
+		Receipts::<T>::insert(receipt_id, &receipt);
+		Escrows::<T>::remove(receipt_id);
+
+		Ok(receipt)
+	}
+
+	pub fn do_release_escrows(when: T::BlockNumber, tasks: Vec<HashOf<T>>) -> Weight {
+		let mut total_weight = Weight::zero();
+
+		for receipt_id in tasks.iter() {
+			total_weight += T::WeightInfo::on_initialize_escrow();
+
+			match Self::do_release_escrow(receipt_id) {
+				Ok(receipt) => {
+					let to = receipt.get_to().clone().unwrap_or_else(Self::tipping_account_id);
+					let amount = receipt.get_amount().saturating_sub(*receipt.get_fee());
+
+					Self::deposit_event(Event::EscrowReleased {
+						receipt_id: *receipt_id,
+						to,
+						amount,
+					});
+				},
+				Err(_) => {
+					// Left escrowed, the buyer can still confirm or refund it
+					Self::deposit_event(Event::EscrowReleaseFailed {
+						receipt_id: *receipt_id,
+						when,
+					});
+				},
+			}
+		}
+
+		total_weight
+	}
+
+	pub fn get_tips_balances(
+		server_id: &ServerIdOf<T>,
+		reference_type: &ReferenceTypeOf<T>,
+		reference_id: &ReferenceIdOf<T>,
+	) -> Vec<TipsBalanceOf<T>> {
+		TipsBalanceByReference::<T>::iter_prefix_values((server_id, reference_type, reference_id))
+			.collect()
+	}
+
+	pub fn has_paid(
+		buyer: &AccountIdOf<T>,
+		server_id: &ServerIdOf<T>,
+		reference_id: &ReferenceIdOf<T>,
+	) -> bool {
+		ReceiptByContent::<T>::get((server_id, reference_id, buyer))
+			.and_then(Receipts::<T>::get)
+			.filter(|receipt| receipt.get_status() != ReceiptStatus::Refunded)
+			.is_some()
+	}
+
+	pub fn get_claimable_tips(
+		server_id: &ServerIdOf<T>,
+		account_id: &AccountIdOf<T>,
+	) -> Vec<(FtIdentifierOf<T>, BalanceOf<T>)> {
+		let mut claimable_tips = BTreeMap::<FtIdentifierOf<T>, BalanceOf<T>>::new();
+
+		for tips_balance in TipsBalanceByReference::<T>::iter_prefix_values((server_id,)) {
+			if tips_balance.get_account_id().as_ref() != Some(account_id) {
+				continue
+			}
+
+			let amount = *tips_balance.get_amount();
+
+			if amount.is_zero() {
+				continue
+			}
+
+			let ft_identifier = tips_balance.get_ft_identifier().clone();
+			let total = claimable_tips.entry(ft_identifier).or_insert_with(Zero::zero);
+
+			*total = total.saturating_add(amount);
+		}
+
+		claimable_tips.into_iter().collect()
+	}
+
+	pub fn get_tip_stats(
+		server_id: &ServerIdOf<T>,
+		reference_type: &ReferenceTypeOf<T>,
+		reference_id: &ReferenceIdOf<T>,
+	) -> Vec<(FtIdentifierOf<T>, TipStatsOf<T>)> {
+		TipStatsByReference::<T>::iter_prefix((server_id, reference_type, reference_id)).collect()
+	}
+
+	pub fn get_top_tippers(
+		server_id: &ServerIdOf<T>,
+		reference_type: &ReferenceTypeOf<T>,
+		reference_id: &ReferenceIdOf<T>,
+		ft_identifier: &FtIdentifierOf<T>,
+	) -> Vec<(AccountIdOf<T>, BalanceOf<T>)> {
+		Self::top_tippers((server_id, reference_type, reference_id, ft_identifier)).into_inner()
+	}
+
+	/// The registered asset behind the ft identifier, disabled assets can still be paid out.
+	pub fn asset_kind(ft_identifier: &FtIdentifierOf<T>) -> Result<AssetKind, Error<T>> {
+		Self::asset_registry(ft_identifier)
+			.map(|asset| asset.get_kind())
+			.ok_or(Error::<T>::AssetNotRegistered)
+	}
+
+	pub fn asset_exists(asset_id: AssetId) -> bool {
+		// Assets can't be created with a zero minimum balance
+		!<T::Assets as fungibles::Inspect<T::AccountId>>::minimum_balance(asset_id).is_zero()
+	}
+
+	/// Only enabled assets can be tipped, and never below their minimum tip.
+	pub fn can_tip_with(
+		ft_identifier: &FtIdentifierOf<T>,
+		amount: &BalanceOf<T>,
+	) -> Result<(), Error<T>> {
+		let asset = Self::asset_registry(ft_identifier).ok_or(Error::<T>::AssetNotRegistered)?;
+
+		if !asset.is_enabled() {
+			return Err(Error::<T>::AssetDisabled)
+		}
+
+		if amount < asset.get_min_tip() {
+			return Err(Error::<T>::BelowMinimumTip)
+		}
+
+		Ok(())
 	}
 }
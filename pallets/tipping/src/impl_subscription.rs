@@ -0,0 +1,149 @@
+use super::*;
+
+use frame_support::{
+	dispatch::DispatchError,
+	sp_runtime::traits::{Saturating, Zero},
+	traits::Get,
+};
+
+impl<T: Config> SubscriptionInterface<T> for Pallet<T> {
+	type Error = DispatchError;
+	type Subscription = SubscriptionOf<T>;
+	type TipsBalanceInfo = TipsBalanceInfoOf<T>;
+	type Balance = BalanceOf<T>;
+
+	fn subscribe(
+		subscriber: &T::AccountId,
+		instance_id: u64,
+		tips_balance_info: &Self::TipsBalanceInfo,
+		amount: &Self::Balance,
+		period: T::BlockNumber,
+	) -> Result<Self::Subscription, Self::Error> {
+		if period.is_zero() || period > T::MaxSubscriptionPeriod::get() {
+			return Err(Error::<T>::InvalidPeriod.into())
+		}
+
+		if amount.is_zero() {
+			return Err(Error::<T>::InsufficientBalance.into())
+		}
+
+		Self::can_access_instance(tips_balance_info.get_server_id(), instance_id)?;
+
+		Self::can_tip_with(tips_balance_info.get_ft_identifier(), amount)?;
+
+		let index = Self::subscription_index();
+		let updated_index = index.checked_add(1).ok_or(Error::<T>::Overflow)?;
+
+		let current_block_number = <frame_system::Pallet<T>>::block_number();
+		let next_payment_at = current_block_number.saturating_add(period);
+
+		Self::can_schedule_subscription(next_payment_at)?;
+
+		// The first tip is paid when subscribing
+		let receiver = Self::tipping_account_id();
+		let ft_identifier = tips_balance_info.get_ft_identifier();
+
+		Self::do_transfer(ft_identifier, subscriber, &receiver, *amount)?;
+
+		let fee_detail = Self::do_pay_tip_fee(instance_id, tips_balance_info, amount);
+		let net_tip = *amount - fee_detail.total_fee();
+		let tips_balance = TipsBalance::new(tips_balance_info, &net_tip);
+
+		for stored_tips_balance in Self::do_store_split_tips_balance(&tips_balance)? {
+			Self::do_hold_tips_balance(Some(subscriber), &stored_tips_balance)?;
+		}
+
+		Self::do_update_tip_stats(subscriber, &tips_balance);
+
+		Self::do_schedule_subscription(index, next_payment_at)?;
+
+		let subscription = Subscription::new(
+			index,
+			instance_id,
+			subscriber,
+			tips_balance_info,
+			amount,
+			period,
+			next_payment_at,
+		);
+
+		SubscriptionIndex::<T>::set(updated_index);
+		SubscriptionById::<T>::insert(index, &subscription);
+
+		Ok(subscription)
+	}
+
+	fn cancel_subscription(
+		subscription_id: u64,
+		subscriber: &T::AccountId,
+	) -> Result<Self::Subscription, Self::Error> {
+		let subscription = SubscriptionById::<T>::get(subscription_id)
+			.ok_or(Error::<T>::NotExists)?
+			.is_authorized(subscriber)
+			.ok_or(Error::<T>::Unauthorized)?;
+
+		if let Some(next_payment_at) = subscription.get_next_payment_at() {
+			Self::do_unschedule_subscription(subscription_id, next_payment_at);
+		}
+
+		SubscriptionById::<T>::remove(subscription_id);
+
+		Ok(subscription)
+	}
+
+	fn pause_subscription(
+		subscription_id: u64,
+		subscriber: &T::AccountId,
+	) -> Result<Self::Subscription, Self::Error> {
+		let mut subscription = SubscriptionById::<T>::get(subscription_id)
+			.ok_or(Error::<T>::NotExists)?
+			.is_authorized(subscriber)
+			.ok_or(Error::<T>::Unauthorized)?;
+
+		if subscription.get_status() != SubscriptionStatus::Active {
+			return Err(Error::<T>::InvalidStatus.into())
+		}
+
+		if let Some(next_payment_at) = subscription.get_next_payment_at() {
+			Self::do_unschedule_subscription(subscription_id, next_payment_at);
+		}
+
+		subscription.set_status(SubscriptionStatus::Paused);
+		subscription.set_next_payment_at(None);
+
+		SubscriptionById::<T>::insert(subscription_id, &subscription);
+
+		Ok(subscription)
+	}
+
+	fn resume_subscription(
+		subscription_id: u64,
+		subscriber: &T::AccountId,
+	) -> Result<Self::Subscription, Self::Error> {
+		let mut subscription = SubscriptionById::<T>::get(subscription_id)
+			.ok_or(Error::<T>::NotExists)?
+			.is_authorized(subscriber)
+			.ok_or(Error::<T>::Unauthorized)?;
+
+		if subscription.get_status() == SubscriptionStatus::Active {
+			return Err(Error::<T>::InvalidStatus.into())
+		}
+
+		Self::can_access_instance(
+			subscription.get_tips_balance_info().get_server_id(),
+			subscription.get_instance_id(),
+		)?;
+
+		let current_block_number = <frame_system::Pallet<T>>::block_number();
+		let next_payment_at = current_block_number.saturating_add(subscription.get_period());
+
+		Self::do_schedule_subscription(subscription_id, next_payment_at)?;
+
+		subscription.set_status(SubscriptionStatus::Active);
+		subscription.set_next_payment_at(Some(next_payment_at));
+
+		SubscriptionById::<T>::insert(subscription_id, &subscription);
+
+		Ok(subscription)
+	}
+}
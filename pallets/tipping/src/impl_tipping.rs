@@ -1,7 +1,13 @@
 use super::*;
 
-use frame_support::{dispatch::DispatchError, sp_runtime::traits::Zero};
-use sp_std::vec::Vec;
+use frame_support::{
+	dispatch::DispatchError,
+	sp_runtime::traits::{Saturating, Zero},
+	storage::with_storage_layer,
+	traits::{Currency, ExistenceRequirement, Get},
+};
+use pallet_server::{ServerInfo, ServerProvider};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 
 impl<T: Config> TippingInterface<T> for Pallet<T> {
 	type Error = DispatchError;
@@ -9,9 +15,15 @@ impl<T: Config> TippingInterface<T> for Pallet<T> {
 	type TipsBalanceInfo = TipsBalanceInfoOf<T>;
 	type TipsBalanceKey = TipsBalanceKeyOf<T>;
 	type Balance = BalanceOf<T>;
-	type References = References;
+	type References = ReferencesOf<T>;
 	type Receipt = ReceiptOf<T>;
-	type WithdrawalResult = Vec<(FtIdentifier, BalanceOf<T>)>;
+	type ReceiptId = HashOf<T>;
+	type FeeDetail = FeeDetail<BalanceOf<T>>;
+	type ClaimProof = ClaimProofOf<T>;
+	type Signature = T::Signature;
+	type FtIdentifier = FtIdentifierOf<T>;
+	type ReferenceId = ReferenceIdOf<T>;
+	type WithdrawalResult = Vec<(FtIdentifierOf<T>, BalanceOf<T>)>;
 
 	fn pay_content(
 		sender: &T::AccountId,
@@ -19,7 +31,7 @@ impl<T: Config> TippingInterface<T> for Pallet<T> {
 		receiver: &Option<T::AccountId>,
 		tips_balance_info: &Self::TipsBalanceInfo,
 		amount: &Self::Balance,
-		account_reference: &Option<Vec<u8>>,
+		account_reference: &Option<Self::ReferenceId>,
 	) -> Result<Self::Receipt, Self::Error> {
 		if let Some(receiver) = receiver {
 			if sender == receiver {
@@ -27,8 +39,10 @@ impl<T: Config> TippingInterface<T> for Pallet<T> {
 			}
 		}
 
+		Self::can_access_instance(tips_balance_info.get_server_id(), instance_id)?;
+
 		let ft_identifier = tips_balance_info.get_ft_identifier();
-		let fee_detail = Self::can_pay_content(ft_identifier, sender, amount)?;
+		let fee_detail = Self::can_pay_content(instance_id, ft_identifier, sender, amount)?;
 		let admin_fee = fee_detail.admin_fee();
 		let server_fee = fee_detail.server_fee();
 		let total_fee = fee_detail.total_fee();
@@ -36,7 +50,7 @@ impl<T: Config> TippingInterface<T> for Pallet<T> {
 
 		let info = TipsBalanceInfo::new(
 			tips_balance_info.get_server_id(),
-			b"unlockable_content",
+			&Self::bounded_string(b"unlockable_content"),
 			tips_balance_info.get_reference_id(),
 			tips_balance_info.get_ft_identifier(),
 		);
@@ -54,21 +68,139 @@ impl<T: Config> TippingInterface<T> for Pallet<T> {
 			let account_reference = account_reference.as_ref().unwrap();
 			let account_info = TipsBalanceInfo::new(
 				tips_balance_info.get_server_id(),
-				b"user",
+				&Self::bounded_string(b"user"),
 				account_reference,
 				tips_balance_info.get_ft_identifier(),
 			);
 			let tips_balance = TipsBalance::new(&account_info, &net_pay);
 
 			Self::do_transfer(ft_identifier, sender, &escrow_id, *amount)?;
-			Self::do_store_tips_balance(&tips_balance, false, None);
+
+			for stored_tips_balance in Self::do_store_split_tips_balance(&tips_balance)? {
+				Self::do_hold_tips_balance(None, &stored_tips_balance)?;
+			}
 		}
 
 		Self::do_update_withdrawal_balance(ft_identifier, admin_fee);
 		Self::do_update_reward_balance(instance_id, &info, server_fee);
 
+		let receipt_id = Self::do_generate_receipt_id(sender, &info, amount);
 		let now = T::TimeProvider::now().as_millis();
-		let receipt = Receipt::new(sender, receiver, &info, amount, &total_fee, now);
+		let status = ReceiptStatus::Paid;
+		let receipt =
+			Receipt::new(receipt_id, sender, receiver, &info, amount, &total_fee, now, status);
+
+		Receipts::<T>::insert(receipt_id, &receipt);
+		Self::do_store_receipt_by_content(&receipt);
+
+		Ok(receipt)
+	}
+
+	fn pay_content_escrow(
+		sender: &T::AccountId,
+		instance_id: u64,
+		receiver: &T::AccountId,
+		tips_balance_info: &Self::TipsBalanceInfo,
+		amount: &Self::Balance,
+	) -> Result<Self::Receipt, Self::Error> {
+		if sender == receiver {
+			return Err(DispatchError::BadOrigin)
+		}
+
+		Self::can_access_instance(tips_balance_info.get_server_id(), instance_id)?;
+
+		let ft_identifier = tips_balance_info.get_ft_identifier();
+		let fee_detail = Self::can_pay_content(instance_id, ft_identifier, sender, amount)?;
+		let total_fee = fee_detail.total_fee();
+
+		let info = TipsBalanceInfo::new(
+			tips_balance_info.get_server_id(),
+			&Self::bounded_string(b"unlockable_content"),
+			tips_balance_info.get_reference_id(),
+			tips_balance_info.get_ft_identifier(),
+		);
+
+		let current_block_number = <frame_system::Pallet<T>>::block_number();
+		let release_at = current_block_number.saturating_add(T::EscrowPeriod::get());
+
+		Self::can_schedule_escrow(release_at)?;
+
+		// The buyer holds the payment, fees are only credited when the escrow is released
+		Self::do_hold(ft_identifier, sender, *amount)?;
+
+		let receipt_id = Self::do_generate_receipt_id(sender, &info, amount);
+		let now = T::TimeProvider::now().as_millis();
+		let status = ReceiptStatus::Escrowed;
+		let receiver = Some(receiver.clone());
+		let receipt =
+			Receipt::new(receipt_id, sender, &receiver, &info, amount, &total_fee, now, status);
+		let escrow = Escrow::new(instance_id, &fee_detail, release_at);
+
+		Receipts::<T>::insert(receipt_id, &receipt);
+		Escrows::<T>::insert(receipt_id, &escrow);
+		Self::do_store_receipt_by_content(&receipt);
+		EscrowTasks::<T>::try_append(release_at, receipt_id)
+			.map_err(|_| Error::<T>::FailedToSchedule)?;
+
+		Ok(receipt)
+	}
+
+	fn confirm_escrow(
+		sender: &T::AccountId,
+		receipt_id: &Self::ReceiptId,
+	) -> Result<Self::Receipt, Self::Error> {
+		let receipt = Receipts::<T>::get(receipt_id).ok_or(Error::<T>::NotExists)?;
+
+		if receipt.get_from() != sender {
+			return Err(Error::<T>::Unauthorized.into())
+		}
+
+		if receipt.get_status() != ReceiptStatus::Escrowed {
+			return Err(Error::<T>::InvalidStatus.into())
+		}
+
+		let escrow = Escrows::<T>::get(receipt_id).ok_or(Error::<T>::NotExists)?;
+		let receipt = Self::do_release_escrow(receipt_id)?;
+
+		Self::do_unschedule_escrow(receipt_id, escrow.get_release_at());
+
+		Ok(receipt)
+	}
+
+	fn refund_escrow(
+		sender: &T::AccountId,
+		receipt_id: &Self::ReceiptId,
+	) -> Result<Self::Receipt, Self::Error> {
+		let mut receipt = Receipts::<T>::get(receipt_id).ok_or(Error::<T>::NotExists)?;
+
+		if receipt.get_status() != ReceiptStatus::Escrowed {
+			return Err(Error::<T>::InvalidStatus.into())
+		}
+
+		let escrow = Escrows::<T>::get(receipt_id).ok_or(Error::<T>::NotExists)?;
+
+		// The buyer or the server owner, acting as arbiter, can refund
+		if receipt.get_from() != sender {
+			let server = T::Server::get_by_id(escrow.get_instance_id());
+
+			if server.filter(|server| server.get_owner() == sender).is_none() {
+				return Err(Error::<T>::Unauthorized.into())
+			}
+		}
+
+		let buyer = receipt.get_from().clone();
+		let ft_identifier = receipt.get_info().get_ft_identifier().clone();
+		let amount = *receipt.get_amount();
+
+		Self::do_release(&ft_identifier, &buyer, amount);
+
+		receipt.set_status(ReceiptStatus::Refunded);
+
+		Receipts::<T>::insert(receipt_id, &receipt);
+		Escrows::<T>::remove(receipt_id);
+		Self::do_remove_receipt_by_content(&receipt);
+
+		Self::do_unschedule_escrow(receipt_id, escrow.get_release_at());
 
 		Ok(receipt)
 	}
@@ -76,25 +208,28 @@ impl<T: Config> TippingInterface<T> for Pallet<T> {
 	fn withdraw_fee(
 		sender: &T::AccountId,
 		receiver: &T::AccountId,
+		amounts: &[(Self::FtIdentifier, Self::Balance)],
 	) -> Result<(Self::WithdrawalResult, Self::WithdrawalResult), Self::Error> {
 		let mut success_withdrawal = Vec::new();
 		let mut failed_withdrawal = Vec::new();
 
-		WithdrawalBalance::<T>::translate(|ft: Vec<u8>, amount: BalanceOf<T>| {
+		for (ft_identifier, amount) in amounts.iter() {
 			if amount.is_zero() {
-				return None
+				continue
 			}
 
-			let result = Self::do_transfer(&ft, sender, receiver, amount);
+			if *amount > Self::withdrawal_balance(ft_identifier) {
+				return Err(Error::<T>::InsufficientBalance.into())
+			}
+
+			let result = Self::do_spend_fee(ft_identifier, sender, receiver, *amount);
 
 			if result.is_err() {
-				failed_withdrawal.push((ft, amount));
-				Some(amount)
+				failed_withdrawal.push((ft_identifier.clone(), *amount));
 			} else {
-				success_withdrawal.push((ft, amount));
-				None
+				success_withdrawal.push((ft_identifier.clone(), *amount));
 			}
-		});
+		}
 
 		Ok((success_withdrawal, failed_withdrawal))
 	}
@@ -127,25 +262,88 @@ impl<T: Config> TippingInterface<T> for Pallet<T> {
 
 	fn send_tip(
 		sender: &T::AccountId,
+		instance_id: u64,
 		receiver: &T::AccountId,
 		tips_balance_info: &Self::TipsBalanceInfo,
 		amount: &Self::Balance,
-	) -> Result<Self::TipsBalance, Self::Error> {
+		anonymous: bool,
+	) -> Result<(Self::TipsBalance, Self::FeeDetail), Self::Error> {
+		Self::can_access_instance(tips_balance_info.get_server_id(), instance_id)?;
+
 		let tip_amount = *amount;
 		let ft_identifier = tips_balance_info.get_ft_identifier();
-		let tips_balance = TipsBalance::new(tips_balance_info, amount);
+
+		Self::can_tip_with(ft_identifier, amount)?;
 
 		Self::do_transfer(ft_identifier, sender, receiver, tip_amount)?;
-		Self::do_store_tips_balance(&tips_balance, false, None);
 
-		Ok(tips_balance)
+		let fee_detail = Self::do_pay_tip_fee(instance_id, tips_balance_info, amount);
+		let net_tip = tip_amount - fee_detail.total_fee();
+		let tips_balance = TipsBalance::new(tips_balance_info, &net_tip);
+
+		// Contributions are keyed by the tipper, so anonymous tips can't be reclaimed
+		let tipper = if anonymous { None } else { Some(sender) };
+
+		for stored_tips_balance in Self::do_store_split_tips_balance(&tips_balance)? {
+			Self::do_hold_tips_balance(tipper, &stored_tips_balance)?;
+		}
+
+		if anonymous {
+			Self::do_update_anonymous_tip_stats(&tips_balance);
+		} else {
+			Self::do_update_tip_stats(sender, &tips_balance);
+		}
+
+		Ok((tips_balance, fee_detail))
+	}
+
+	fn send_tips(
+		sender: &T::AccountId,
+		receiver: &T::AccountId,
+		tips: &[(u64, Self::TipsBalanceInfo, Self::Balance)],
+	) -> Result<(Vec<Self::TipsBalance>, Self::WithdrawalResult), Self::Error> {
+		let mut total = BTreeMap::<FtIdentifierOf<T>, BalanceOf<T>>::new();
+		let mut tips_balances = Vec::new();
+
+		for (instance_id, tips_balance_info, amount) in tips.iter() {
+			Self::can_access_instance(tips_balance_info.get_server_id(), *instance_id)?;
+
+			let ft_identifier = tips_balance_info.get_ft_identifier().clone();
+
+			Self::can_tip_with(&ft_identifier, amount)?;
+
+			let ft_total = total.entry(ft_identifier).or_insert_with(Zero::zero);
+
+			*ft_total = ft_total.saturating_add(*amount);
+		}
+
+		// One transfer per currency, the whole batch fails if any of them fails
+		for (ft_identifier, amount) in total.iter() {
+			Self::do_transfer(ft_identifier, sender, receiver, *amount)?;
+		}
+
+		for (instance_id, tips_balance_info, amount) in tips.iter() {
+			let fee_detail = Self::do_pay_tip_fee(*instance_id, tips_balance_info, amount);
+			let net_tip = *amount - fee_detail.total_fee();
+			let tips_balance = TipsBalance::new(tips_balance_info, &net_tip);
+
+			for stored_tips_balance in Self::do_store_split_tips_balance(&tips_balance)? {
+				Self::do_hold_tips_balance(Some(sender), &stored_tips_balance)?;
+			}
+
+			Self::do_update_tip_stats(sender, &tips_balance);
+
+			tips_balances.push(tips_balance);
+		}
+
+		Ok((tips_balances, total.into_iter().collect()))
 	}
 
 	fn claim_tip(
 		sender: &T::AccountId,
 		receiver: &T::AccountId,
 		tips_balance_key: &Self::TipsBalanceKey,
-		ft_identifiers: &[Vec<u8>],
+		ft_identifiers: &[Self::FtIdentifier],
 	) -> Result<(Self::WithdrawalResult, Self::WithdrawalResult), Self::Error> {
 		let mut tips_balance_key = tips_balance_key.clone();
 		let mut success_claim = Vec::new();
@@ -163,28 +361,65 @@ impl<T: Config> TippingInterface<T> for Pallet<T> {
 			let tips_balance = can_claim_tip.unwrap();
 			let amount = *tips_balance.get_amount();
 
-			match Self::do_transfer(ft, sender, receiver, amount) {
+			// Claimed tips still held by their tippers aren't on the tipping account yet
+			if !Self::pending_settlements(&tips_balance_key).is_zero() {
+				failed_claim.push((ft.clone(), amount));
+				continue
+			}
+
+			match Self::do_pay_out_tips(ft, sender, receiver, amount) {
 				Ok(_) => {
 					Self::do_store_tips_balance(&tips_balance, true, None);
 
-					success_claim.push((ft.to_vec(), amount));
+					success_claim.push((ft.clone(), amount));
 				},
-				Err(_) => failed_claim.push((ft.to_vec(), amount)),
+				Err(_) => failed_claim.push((ft.clone(), amount)),
 			};
 		}
 
 		Ok((success_claim, failed_claim))
 	}
 
+	fn claim_tip_and_bridge(
+		sender: &T::AccountId,
+		receiver: &T::AccountId,
+		tips_balance_key: &Self::TipsBalanceKey,
+		receiver_id: &[u8],
+	) -> Result<Self::Balance, Self::Error> {
+		let tips_balance =
+			Self::can_claim_tip(tips_balance_key, receiver).ok_or(Error::<T>::NotExists)?;
+		let ft_identifier = tips_balance.get_ft_identifier();
+		let amount = *tips_balance.get_amount();
+		let asset = Self::asset_kind(ft_identifier)?;
+
+		if !Self::pending_settlements(tips_balance_key).is_zero() {
+			return Err(Error::<T>::TipsNotSettled.into())
+		}
+
+		// The tip stays claimable when the bridge rejects it
+		with_storage_layer(|| {
+			Self::do_pay_out_tips(ft_identifier, sender, receiver, amount)?;
+
+			T::Bridge::bridge(receiver, asset, receiver_id.to_vec(), amount)
+		})?;
+
+		Self::do_store_tips_balance(&tips_balance, true, None);
+
+		Ok(amount)
+	}
+
 	fn claim_reference(
 		receiver: &T::AccountId,
 		server_id: &T::AccountId,
+		instance_id: u64,
 		references: &Self::References,
 		account_references: &Self::References,
-		ft_identifiers: &[Vec<u8>],
+		ft_identifiers: &[Self::FtIdentifier],
 		account_id: &T::AccountId,
 		tx_fee: &Self::Balance,
 	) -> Result<Vec<Self::TipsBalance>, Self::Error> {
+		Self::can_access_instance(server_id, instance_id)?;
+
 		let account_ref_type = account_references.get_reference_type().clone();
 		let account_ref_ids = account_references.get_reference_ids().clone();
 
@@ -197,12 +432,11 @@ impl<T: Config> TippingInterface<T> for Pallet<T> {
 		}
 
 		// Pay Fee to Server Admin
-		let sender = Self::tipping_account_id();
 		let account_reference_id = account_ref_ids[0].clone();
-		let key = (server_id.clone(), account_ref_type, account_reference_id, b"native".to_vec());
+		let native = Self::bounded_string(b"native");
+		let key = (server_id.clone(), account_ref_type, account_reference_id, native);
 
 		Self::can_pay_fee(&key, tx_fee)?;
-		Self::do_transfer(b"native", &sender, receiver, *tx_fee)?;
 
 		// Recap total tips belong to account
 		let tips_balances = Self::do_store_tips_balances(
@@ -214,6 +448,164 @@ impl<T: Config> TippingInterface<T> for Pallet<T> {
 			tx_fee,
 		);
 
+		// Paid once the claimed tips are settled on the tipping account
+		Self::do_pay_claim_fee(&key, *tx_fee)?;
+
 		Ok(tips_balances)
 	}
+
+	fn reclaim_tip(
+		tipper: &T::AccountId,
+		tips_balance_key: &Self::TipsBalanceKey,
+	) -> Result<(Self::TipsBalance, Self::Balance), Self::Error> {
+		let mut tips_balance =
+			TipsBalanceByReference::<T>::get(tips_balance_key).ok_or(Error::<T>::NotExists)?;
+
+		if tips_balance.get_account_id().is_some() {
+			return Err(Error::<T>::Unauthorized.into())
+		}
+
+		let (server_id, reference_type, reference_id, ft_identifier) = tips_balance_key;
+		let contribution_key = (server_id, reference_type, reference_id, ft_identifier, tipper);
+		let contribution = TipContributions::<T>::get(contribution_key);
+		let amount = contribution.get_amount().min(*tips_balance.get_amount());
+
+		if amount.is_zero() {
+			return Err(Error::<T>::NotExists.into())
+		}
+
+		// Every tipper's contribution expires on its own, later tips don't hold it back
+		let current_block_number = <frame_system::Pallet<T>>::block_number();
+
+		if current_block_number < contribution.get_expires_at() {
+			return Err(Error::<T>::NotExpired.into())
+		}
+
+		// The tipper held the tip all along
+		Self::do_release(ft_identifier, tipper, amount);
+
+		TipContributions::<T>::remove(contribution_key);
+
+		let remaining = *tips_balance.get_amount() - amount;
+
+		tips_balance.set_amount(remaining);
+
+		if remaining.is_zero() {
+			TipsBalanceByReference::<T>::remove(tips_balance_key);
+			TipExpiryAt::<T>::remove(tips_balance_key);
+		} else {
+			TipsBalanceByReference::<T>::insert(tips_balance_key, &tips_balance);
+		}
+
+		Ok((tips_balance, amount))
+	}
+
+	fn claim_reference_with_proof(
+		receiver: &T::AccountId,
+		proof: &Self::ClaimProof,
+		signature: &Self::Signature,
+		ft_identifiers: &[Self::FtIdentifier],
+	) -> Result<Vec<Self::TipsBalance>, Self::Error> {
+		let instance_id = proof.get_instance_id();
+		let server = T::Server::get_by_id(instance_id).ok_or(Error::<T>::ServerNotRegister)?;
+		let server_id = server.get_owner();
+
+		Self::can_access_instance(server_id, instance_id)?;
+		Self::can_claim_with_proof(proof, signature)?;
+
+		if proof.get_account_references().get_reference_ids().is_empty() {
+			return Err(DispatchError::BadOrigin)
+		}
+
+		with_storage_layer(|| {
+			ClaimNonce::<T>::insert(instance_id, receiver, proof.get_nonce().saturating_add(1));
+
+			let mut tips_balances = Self::do_store_tips_balances(
+				server_id,
+				proof.get_references(),
+				proof.get_account_references(),
+				ft_identifiers,
+				receiver,
+				&Zero::zero(),
+			);
+
+			// The fee comes out of the account reference once the claimed tips are added to it
+			if let Some((ft_identifier, tx_fee)) = proof.get_tx_fee() {
+				if !tx_fee.is_zero() {
+					let account_references = proof.get_account_references();
+					let key = (
+						server_id.clone(),
+						account_references.get_reference_type().clone(),
+						account_references.get_reference_ids()[0].clone(),
+						ft_identifier.clone(),
+					);
+
+					Self::can_pay_fee(&key, tx_fee)?;
+					Self::do_pay_claim_fee(&key, *tx_fee)?;
+
+					TipsBalanceByReference::<T>::mutate(&key, |tips_balance| {
+						if let Some(tips_balance) = tips_balance {
+							tips_balance
+								.set_amount(tips_balance.get_amount().saturating_sub(*tx_fee));
+						}
+					});
+
+					for tips_balance in tips_balances.iter_mut() {
+						if tips_balance.key() == key {
+							tips_balance
+								.set_amount(tips_balance.get_amount().saturating_sub(*tx_fee));
+						}
+					}
+				}
+			}
+
+			Ok(tips_balances)
+		})
+	}
+}
+
+/// Moves the native admin fees set aside in `RewardPotBalance` into the server reward pot.
+impl<T: Config> pallet_server::RewardFunding<T::AccountId, BalanceOf<T>> for Pallet<T> {
+	fn fund(pot: &T::AccountId) -> BalanceOf<T> {
+		let amount = Self::reward_pot_balance();
+
+		if amount.is_zero() {
+			return amount
+		}
+
+		let result = with_storage_layer(|| -> Result<(), DispatchError> {
+			CurrencyOf::<T>::transfer(
+				&Self::tipping_account_id(),
+				pot,
+				amount,
+				ExistenceRequirement::KeepAlive,
+			)?;
+
+			RewardPotBalance::<T>::kill();
+
+			Ok(())
+		});
+
+		match result {
+			Ok(_) => amount,
+			Err(_) => Zero::zero(),
+		}
+	}
+}
+
+/// Keeps the asset tips held on their tipper frozen.
+impl<T: Config> pallet_assets::FrozenBalance<AssetId, T::AccountId, AssetBalance> for Pallet<T> {
+	fn frozen_balance(asset: AssetId, who: &T::AccountId) -> Option<AssetBalance> {
+		let frozen = Self::frozen_assets(asset, who);
+
+		if frozen.is_zero() {
+			None
+		} else {
+			Some(frozen)
+		}
+	}
+
+	fn died(asset: AssetId, who: &T::AccountId) {
+		FrozenAssets::<T>::remove(asset, who);
+	}
 }
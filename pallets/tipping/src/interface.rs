@@ -1,5 +1,19 @@
+use crate::AssetKind;
+use frame_support::dispatch::DispatchResult;
 use sp_std::vec::Vec;
 
+/// Sends tokens of this chain to an account on the other side of a bridge.
+pub trait TokenBridge<AccountId, Balance> {
+	/// Locks the native token or burns the bridged asset of `sender`, releasing `amount` to
+	/// `receiver_id` on the other chain.
+	fn bridge(
+		sender: &AccountId,
+		asset: AssetKind,
+		receiver_id: Vec<u8>,
+		amount: Balance,
+	) -> DispatchResult;
+}
+
 pub trait TippingInterface<T: frame_system::Config> {
 	type Error;
 	type TipsBalance;
@@ -8,6 +22,12 @@ pub trait TippingInterface<T: frame_system::Config> {
 	type Balance;
 	type References;
 	type Receipt;
+	type ReceiptId;
+	type FeeDetail;
+	type ClaimProof;
+	type Signature;
+	type FtIdentifier;
+	type ReferenceId;
 	type WithdrawalResult;
 
 	fn pay_content(
@@ -16,12 +36,31 @@ pub trait TippingInterface<T: frame_system::Config> {
 		receiver: &Option<T::AccountId>,
 		tips_balance_info: &Self::TipsBalanceInfo,
 		amount: &Self::Balance,
-		account_reference: &Option<Vec<u8>>,
+		account_reference: &Option<Self::ReferenceId>,
+	) -> Result<Self::Receipt, Self::Error>;
+
+	fn pay_content_escrow(
+		sender: &T::AccountId,
+		instance_id: u64,
+		receiver: &T::AccountId,
+		tips_balance_info: &Self::TipsBalanceInfo,
+		amount: &Self::Balance,
+	) -> Result<Self::Receipt, Self::Error>;
+
+	fn confirm_escrow(
+		sender: &T::AccountId,
+		receipt_id: &Self::ReceiptId,
+	) -> Result<Self::Receipt, Self::Error>;
+
+	fn refund_escrow(
+		sender: &T::AccountId,
+		receipt_id: &Self::ReceiptId,
 	) -> Result<Self::Receipt, Self::Error>;
 
 	fn withdraw_fee(
 		sender: &T::AccountId,
 		receiver: &T::AccountId,
+		amounts: &[(Self::FtIdentifier, Self::Balance)],
 	) -> Result<(Self::WithdrawalResult, Self::WithdrawalResult), Self::Error>;
 
 	fn withdraw_reward(
@@ -32,25 +71,83 @@ pub trait TippingInterface<T: frame_system::Config> {
 
 	fn send_tip(
 		sender: &T::AccountId,
+		instance_id: u64,
 		receiver: &T::AccountId,
 		tips_balance_info: &Self::TipsBalanceInfo,
 		amount: &Self::Balance,
-	) -> Result<Self::TipsBalance, Self::Error>;
+		anonymous: bool,
+	) -> Result<(Self::TipsBalance, Self::FeeDetail), Self::Error>;
+
+	fn send_tips(
+		sender: &T::AccountId,
+		receiver: &T::AccountId,
+		tips: &[(u64, Self::TipsBalanceInfo, Self::Balance)],
+	) -> Result<(Vec<Self::TipsBalance>, Self::WithdrawalResult), Self::Error>;
 
 	fn claim_tip(
 		sender: &T::AccountId,
 		receiver: &T::AccountId,
 		tips_balance_key: &Self::TipsBalanceKey,
-		ft_identifiers: &[Vec<u8>],
+		ft_identifiers: &[Self::FtIdentifier],
 	) -> Result<(Self::WithdrawalResult, Self::WithdrawalResult), Self::Error>;
 
+	fn claim_tip_and_bridge(
+		sender: &T::AccountId,
+		receiver: &T::AccountId,
+		tips_balance_key: &Self::TipsBalanceKey,
+		receiver_id: &[u8],
+	) -> Result<Self::Balance, Self::Error>;
+
 	fn claim_reference(
 		receiver: &T::AccountId,
 		server_id: &T::AccountId,
+		instance_id: u64,
 		references: &Self::References,
 		main_references: &Self::References,
-		ft_identifiers: &[Vec<u8>],
+		ft_identifiers: &[Self::FtIdentifier],
 		account_id: &T::AccountId,
 		tx_fee: &Self::Balance,
 	) -> Result<Vec<Self::TipsBalance>, Self::Error>;
+
+	fn reclaim_tip(
+		tipper: &T::AccountId,
+		tips_balance_key: &Self::TipsBalanceKey,
+	) -> Result<(Self::TipsBalance, Self::Balance), Self::Error>;
+
+	fn claim_reference_with_proof(
+		receiver: &T::AccountId,
+		proof: &Self::ClaimProof,
+		signature: &Self::Signature,
+		ft_identifiers: &[Self::FtIdentifier],
+	) -> Result<Vec<Self::TipsBalance>, Self::Error>;
+}
+
+pub trait SubscriptionInterface<T: frame_system::Config> {
+	type Error;
+	type Subscription;
+	type TipsBalanceInfo;
+	type Balance;
+
+	fn subscribe(
+		subscriber: &T::AccountId,
+		instance_id: u64,
+		tips_balance_info: &Self::TipsBalanceInfo,
+		amount: &Self::Balance,
+		period: T::BlockNumber,
+	) -> Result<Self::Subscription, Self::Error>;
+
+	fn cancel_subscription(
+		subscription_id: u64,
+		subscriber: &T::AccountId,
+	) -> Result<Self::Subscription, Self::Error>;
+
+	fn pause_subscription(
+		subscription_id: u64,
+		subscriber: &T::AccountId,
+	) -> Result<Self::Subscription, Self::Error>;
+
+	fn resume_subscription(
+		subscription_id: u64,
+		subscriber: &T::AccountId,
+	) -> Result<Self::Subscription, Self::Error>;
 }
@@ -11,20 +11,25 @@ pub use pallet::*;
 pub use scale_info::{prelude::string::*, TypeInfo};
 
 pub mod functions;
+pub mod impl_subscription;
 pub mod impl_tipping;
 pub mod interface;
 pub mod migrations;
+pub mod runtime_api;
 pub mod types;
 pub mod weights;
 
-pub use crate::interface::TippingInterface;
+pub use crate::interface::{SubscriptionInterface, TippingInterface, TokenBridge};
 pub use types::*;
 pub use weights::WeightInfo;
 
 pub use frame_support::traits::{StorageVersion, UnixTime};
 
 /// The current storage version.
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+/// Fee spend proposal deposits are reserved under this id.
+const FEE_SPEND_RESERVE_ID: [u8; 8] = *b"tip/spnd";
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -33,10 +38,15 @@ pub mod pallet {
 	use frame_support::{
 		dispatch::DispatchResultWithPostInfo,
 		pallet_prelude::*,
-		traits::{tokens::fungibles, Currency, Get},
+		sp_runtime::{
+			traits::{IdentifyAccount, Saturating, Verify},
+			Permill,
+		},
+		traits::{tokens::fungibles, Currency, Get, NamedReservableCurrency},
 		Blake2_128Concat,
 	};
 	use frame_system::pallet_prelude::*;
+	use pallet_server::{ServerId, ServerProvider};
 	use sp_std::vec::Vec;
 
 	#[pallet::config]
@@ -44,23 +54,123 @@ pub mod pallet {
 		type RuntimeCall: From<Call<Self>>;
 		type TimeProvider: UnixTime;
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
-		type Currency: Currency<<Self as frame_system::Config>::AccountId>;
+		type Currency: NamedReservableCurrency<
+			<Self as frame_system::Config>::AccountId,
+			ReserveIdentifier = [u8; 8],
+		>;
 		type Assets: fungibles::Transfer<
 			<Self as frame_system::Config>::AccountId,
 			AssetId = AssetId,
 			Balance = AssetBalance,
 		>;
+		type Server: ServerProvider<Self>;
 		type WeightInfo: WeightInfo;
 
+		/// The maximum number of tips sent in one `send_tips` call.
+		#[pallet::constant]
+		type MaxTipsPerBatch: Get<u32>;
+
+		/// The longest period, in blocks, between two subscription tips.
+		#[pallet::constant]
+		type MaxSubscriptionPeriod: Get<Self::BlockNumber>;
+
+		#[pallet::constant]
+		type MaxSubscriptionsPerBlock: Get<u32>;
+
+		/// The number of blocks an escrowed content payment waits before it is released.
+		#[pallet::constant]
+		type EscrowPeriod: Get<Self::BlockNumber>;
+
+		#[pallet::constant]
+		type MaxEscrowsPerBlock: Get<u32>;
+
+		/// The origin that registers and updates tipping assets.
+		type AssetRegistryOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The origin that withdraws admin fees and approves or rejects fee spend proposals.
+		type WithdrawOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The account admin fees are withdrawn to, e.g. a treasury account.
+		type FeeDestination: Get<Self::AccountId>;
+
+		/// The maximum number of currencies withdrawn in one `withdraw_fee` call.
+		#[pallet::constant]
+		type MaxFeeWithdrawals: Get<u32>;
+
+		/// The deposit reserved from the proposer of a fee spend, slashed when rejected.
+		#[pallet::constant]
+		type FeeSpendDeposit: Get<BalanceOf<Self>>;
+
+		/// The share of native admin fees that funds the server era rewards.
+		#[pallet::constant]
+		type RewardPotShare: Get<Permill>;
+
+		/// The number of blocks after the last tip before tippers can reclaim an unclaimed
+		/// reference.
+		#[pallet::constant]
+		type TipExpiry: Get<Self::BlockNumber>;
+
+		/// The maximum number of recipients a reference splits its tips between.
+		#[pallet::constant]
+		type MaxTipSplits: Get<u32>;
+
+		/// The maximum length of a reference type, reference id or asset identifier.
+		#[pallet::constant]
+		type MaxStringLength: Get<u32>;
+
+		/// The maximum number of tips expiring at the same block.
+		#[pallet::constant]
+		type MaxTipExpiriesPerBlock: Get<u32>;
+
+		/// The maximum number of tip contributions removed at once when a reference is claimed,
+		/// `on_idle` removes the rest.
+		#[pallet::constant]
+		type MaxTipContributionRemovals: Get<u32>;
+
+		/// Sends claimed tips to an account on another chain.
+		type Bridge: TokenBridge<Self::AccountId, BalanceOf<Self>>;
+
+		/// The maximum length of a memo attached to a tip or content payment.
+		#[pallet::constant]
+		type MaxMemoLength: Get<u32>;
+
+		/// The deposit reserved from the sender for storing a memo.
+		#[pallet::constant]
+		type MemoDepositBase: Get<BalanceOf<Self>>;
+
+		/// The deposit reserved per byte of a memo.
+		#[pallet::constant]
+		type MemoDepositPerByte: Get<BalanceOf<Self>>;
+
+		/// The maximum number of top tippers kept per reference and asset.
+		#[pallet::constant]
+		type MaxTopTippers: Get<u32>;
+
+		/// The maximum number of accounts a server instance pays its rewards out to.
+		#[pallet::constant]
+		type MaxRewardBeneficiaries: Get<u32>;
+
+		/// The maximum number of reference ids claimed at once.
+		#[pallet::constant]
+		type MaxReferenceIds: Get<u32>;
+
+		/// The maximum number of server instances paid out at the same block.
+		#[pallet::constant]
+		type MaxRewardPayoutsPerBlock: Get<u32>;
+
+		/// The maximum number of transfers an instance payout makes, or unpaid rewards a removed
+		/// distribution hands back at once. The rest waits for the next payout or removal.
 		#[pallet::constant]
-		type TransactionFee: Get<u8>;
-		type AdminFee: Get<u8>;
+		type MaxRewardTransfers: Get<u32>;
+
+		/// Signature of a server claim proof, checked against the server signing key.
+		type Signature: Verify<Signer = Self::Signer> + Parameter;
+		type Signer: IdentifyAccount<AccountId = Self::AccountId>;
 	}
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::generate_store(pub(super) trait Store)]
-	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
@@ -69,9 +179,9 @@ pub mod pallet {
 		_,
 		(
 			NMapKey<Blake2_128Concat, ServerIdOf<T>>,
-			NMapKey<Blake2_128Concat, ReferenceType>,
-			NMapKey<Blake2_128Concat, ReferenceId>,
-			NMapKey<Blake2_128Concat, FtIdentifier>,
+			NMapKey<Blake2_128Concat, ReferenceTypeOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceIdOf<T>>,
+			NMapKey<Blake2_128Concat, FtIdentifierOf<T>>,
 		),
 		TipsBalanceOf<T>,
 	>;
@@ -79,7 +189,12 @@ pub mod pallet {
 	#[pallet::storage]
 	#[pallet::getter(fn withdrawal_balance)]
 	pub(super) type WithdrawalBalance<T: Config> =
-		StorageMap<_, Blake2_128Concat, FtIdentifier, BalanceOf<T>, ValueQuery>;
+		StorageMap<_, Blake2_128Concat, FtIdentifierOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// Native admin fees set aside for the server era rewards, moved out every era.
+	#[pallet::storage]
+	#[pallet::getter(fn reward_pot_balance)]
+	pub type RewardPotBalance<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn reward_balance)]
@@ -88,34 +203,497 @@ pub mod pallet {
 		(
 			NMapKey<Blake2_128Concat, ServerIdOf<T>>,
 			NMapKey<Blake2_128Concat, u64>,
-			NMapKey<Blake2_128Concat, FtIdentifier>,
+			NMapKey<Blake2_128Concat, FtIdentifierOf<T>>,
+		),
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn default_fee_policy)]
+	pub type DefaultFeePolicy<T> = StorageValue<_, FeePolicy, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn fee_bounds)]
+	pub type FeeBounds<T> = StorageValue<_, FeePolicyBounds, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn fee_policy_by_server)]
+	pub(super) type FeePolicyByServer<T: Config> =
+		StorageMap<_, Blake2_128Concat, ServerId, FeePolicy>;
+
+	/// Accounts a server instance pays its rewards out to, and how often.
+	#[pallet::storage]
+	#[pallet::getter(fn reward_distribution)]
+	pub(super) type RewardDistributionByInstance<T: Config> =
+		StorageMap<_, Blake2_128Concat, ServerId, RewardDistributionOf<T>>;
+
+	/// Reward payouts that failed to transfer, retried on the next payout of the instance.
+	#[pallet::storage]
+	#[pallet::getter(fn unpaid_reward)]
+	pub(super) type UnpaidRewards<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, ServerId>,
+			NMapKey<Blake2_128Concat, AccountIdOf<T>>,
+			NMapKey<Blake2_128Concat, FtIdentifierOf<T>>,
+		),
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn reward_payout_tasks)]
+	pub(super) type RewardPayoutTasks<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<ServerId, T::MaxRewardPayoutsPerBlock>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn fee_spend_proposal_count)]
+	pub type FeeSpendProposalCount<T> = StorageValue<_, FeeSpendProposalId, ValueQuery>;
+
+	/// Proposals to pay admin fees out to a beneficiary, waiting for `WithdrawOrigin`.
+	#[pallet::storage]
+	#[pallet::getter(fn fee_spend_proposal)]
+	pub(super) type FeeSpendProposals<T: Config> =
+		StorageMap<_, Blake2_128Concat, FeeSpendProposalId, FeeSpendProposalOf<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn asset_registry)]
+	pub type AssetRegistry<T: Config> =
+		StorageMap<_, Blake2_128Concat, FtIdentifierOf<T>, AssetMetadataOf<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn claim_nonce)]
+	pub(super) type ClaimNonce<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		ServerId,
+		Blake2_128Concat,
+		AccountIdOf<T>,
+		u64,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn tip_contributions)]
+	pub(super) type TipContributions<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, ServerIdOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceTypeOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceIdOf<T>>,
+			NMapKey<Blake2_128Concat, FtIdentifierOf<T>>,
+			NMapKey<Blake2_128Concat, AccountIdOf<T>>,
+		),
+		TipContributionOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn tip_expiry_at)]
+	pub(super) type TipExpiryAt<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, ServerIdOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceTypeOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceIdOf<T>>,
+			NMapKey<Blake2_128Concat, FtIdentifierOf<T>>,
+		),
+		BlockNumberFor<T>,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn tip_expiry_tasks)]
+	pub(super) type TipExpiryTasks<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<TipsBalanceKeyOf<T>, T::MaxTipExpiriesPerBlock>,
+		ValueQuery,
+	>;
+
+	/// Claimed references whose tip contributions are still being settled, with the reference
+	/// they were claimed to.
+	#[pallet::storage]
+	#[pallet::getter(fn tip_contribution_cleanup)]
+	pub(super) type TipContributionCleanup<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, ServerIdOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceTypeOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceIdOf<T>>,
+			NMapKey<Blake2_128Concat, FtIdentifierOf<T>>,
+		),
+		TipsBalanceKeyOf<T>,
+	>;
+
+	/// The number of claimed references whose tips are still being settled into the reference,
+	/// its tips aren't paid out until they are all settled.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_settlements)]
+	pub(super) type PendingSettlements<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, ServerIdOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceTypeOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceIdOf<T>>,
+			NMapKey<Blake2_128Concat, FtIdentifierOf<T>>,
+		),
+		u32,
+		ValueQuery,
+	>;
+
+	/// Claim fees taken from the reference, paid to its server once its tips are settled.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_claim_fee)]
+	pub(super) type PendingClaimFees<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, ServerIdOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceTypeOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceIdOf<T>>,
+			NMapKey<Blake2_128Concat, FtIdentifierOf<T>>,
+		),
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// Asset tips held on their tipper until the reference is claimed, `pallet_assets` keeps
+	/// them frozen through the `FrozenBalance` of this pallet.
+	#[pallet::storage]
+	#[pallet::getter(fn frozen_assets)]
+	pub(super) type FrozenAssets<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		AssetId,
+		Blake2_128Concat,
+		AccountIdOf<T>,
+		AssetBalance,
+		ValueQuery,
+	>;
+
+	/// The next block the `on_idle` sweeper reports expired tips for.
+	#[pallet::storage]
+	#[pallet::getter(fn tip_expiry_cursor)]
+	pub(super) type TipExpiryCursor<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+	/// Shares a claimed reference hands its incoming tips out by.
+	#[pallet::storage]
+	#[pallet::getter(fn tip_split)]
+	pub(super) type TipSplits<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, ServerIdOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceTypeOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceIdOf<T>>,
+		),
+		TipSplitsOf<T>,
+	>;
+
+	/// Lifetime totals of a reference per asset, kept after its tips are claimed.
+	#[pallet::storage]
+	#[pallet::getter(fn tip_stats_by_reference)]
+	pub(super) type TipStatsByReference<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, ServerIdOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceTypeOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceIdOf<T>>,
+			NMapKey<Blake2_128Concat, FtIdentifierOf<T>>,
+		),
+		TipStatsOf<T>,
+		ValueQuery,
+	>;
+
+	/// Lifetime amount each tipper sent to a reference per asset.
+	#[pallet::storage]
+	#[pallet::getter(fn tipper_total)]
+	pub(super) type TipperTotals<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, ServerIdOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceTypeOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceIdOf<T>>,
+			NMapKey<Blake2_128Concat, FtIdentifierOf<T>>,
+			NMapKey<Blake2_128Concat, AccountIdOf<T>>,
 		),
 		BalanceOf<T>,
 		ValueQuery,
 	>;
 
+	/// The biggest tippers of a reference per asset, sorted by their lifetime amount.
+	#[pallet::storage]
+	#[pallet::getter(fn top_tippers)]
+	pub(super) type TopTippers<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, ServerIdOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceTypeOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceIdOf<T>>,
+			NMapKey<Blake2_128Concat, FtIdentifierOf<T>>,
+		),
+		TopTippersOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn subscription_index)]
+	pub type SubscriptionIndex<T> = StorageValue<_, SubscriptionId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn subscription_by_id)]
+	pub(super) type SubscriptionById<T: Config> =
+		StorageMap<_, Blake2_128Concat, SubscriptionId, SubscriptionOf<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn subscription_tasks)]
+	pub(super) type SubscriptionTasks<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<SubscriptionId, T::MaxSubscriptionsPerBlock>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn receipt_nonce)]
+	pub type ReceiptNonce<T> = StorageValue<_, u64, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn receipts)]
+	pub(super) type Receipts<T: Config> = StorageMap<_, Blake2_128Concat, HashOf<T>, ReceiptOf<T>>;
+
+	/// Memos of tips and content payments, by tip id or receipt id.
+	#[pallet::storage]
+	#[pallet::getter(fn memos)]
+	pub(super) type Memos<T: Config> = StorageMap<_, Blake2_128Concat, HashOf<T>, MemoOf<T>>;
+
+	/// The account that reserved the deposit of a memo, and how much.
+	#[pallet::storage]
+	#[pallet::getter(fn memo_deposit)]
+	pub(super) type MemoDeposit<T: Config> =
+		StorageMap<_, Blake2_128Concat, HashOf<T>, (AccountIdOf<T>, BalanceOf<T>)>;
+
+	/// Anonymous tips waiting to be revealed, by the tipped reference and the hash of the tipper,
+	/// that reference and a salt, with the block they can be reclaimed from. A commitment is
+	/// used once per reference.
+	#[pallet::storage]
+	#[pallet::getter(fn anonymous_tip)]
+	pub(super) type AnonymousTips<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		TipsBalanceKeyOf<T>,
+		Blake2_128Concat,
+		HashOf<T>,
+		(TipsBalanceOf<T>, BlockNumberFor<T>),
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn receipt_by_content)]
+	pub(super) type ReceiptByContent<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, ServerIdOf<T>>,
+			NMapKey<Blake2_128Concat, ReferenceIdOf<T>>,
+			NMapKey<Blake2_128Concat, AccountIdOf<T>>,
+		),
+		HashOf<T>,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn escrows)]
+	pub(super) type Escrows<T: Config> = StorageMap<_, Blake2_128Concat, HashOf<T>, EscrowOf<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn escrow_tasks)]
+	pub(super) type EscrowTasks<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<HashOf<T>, T::MaxEscrowsPerBlock>,
+		ValueQuery,
+	>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub assets: Vec<(FtIdentifier, AssetMetadataOf<T>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { assets: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			// Keep the tipping account alive, held tips are reserved on top of it
+			let tipping_account_id = Pallet::<T>::tipping_account_id();
+			let minimum_balance = CurrencyOf::<T>::minimum_balance();
+
+			if CurrencyOf::<T>::free_balance(&tipping_account_id) < minimum_balance {
+				let _ = CurrencyOf::<T>::make_free_balance_be(&tipping_account_id, minimum_balance);
+			}
+
+			for (ft_identifier, asset) in self.assets.iter() {
+				let ft_identifier: FtIdentifierOf<T> =
+					ft_identifier.clone().try_into().expect("Asset identifier is too long");
+
+				AssetRegistry::<T>::insert(ft_identifier, asset);
+			}
+		}
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// Send tip success. { from, to, tips_balance }
-		SendTip { from: T::AccountId, to: T::AccountId, tips_balance: TipsBalanceOf<T> },
+		/// Send tip success, the tips balance is net of the fee. { from, to, tips_balance,
+		/// fee_detail, memo }
+		SendTip {
+			from: T::AccountId,
+			to: T::AccountId,
+			tips_balance: TipsBalanceOf<T>,
+			fee_detail: FeeDetail<BalanceOf<T>>,
+			memo: Option<(HashOf<T>, MemoOf<T>)>,
+		},
+		/// Send anonymous tip success, the tipper can reveal the commitment later. The signed
+		/// extrinsic and the balance events still name the tipper. { commitment, to,
+		/// tips_balance, fee_detail, memo }
+		SendAnonymousTip {
+			commitment: HashOf<T>,
+			to: T::AccountId,
+			tips_balance: TipsBalanceOf<T>,
+			fee_detail: FeeDetail<BalanceOf<T>>,
+			memo: Option<(HashOf<T>, MemoOf<T>)>,
+		},
+		/// Anonymous tip revealed. { commitment, from, tips_balance }
+		TipRevealed { commitment: HashOf<T>, from: T::AccountId, tips_balance: TipsBalanceOf<T> },
+		/// Send batch of tips success. { from, to, tips_balances, total }
+		SendTips {
+			from: T::AccountId,
+			to: T::AccountId,
+			tips_balances: Vec<TipsBalanceOf<T>>,
+			total: Vec<(FtIdentifierOf<T>, BalanceOf<T>)>,
+		},
 		/// Claim tip success { from, to, success, failed }
 		ClaimTip {
 			from: T::AccountId,
 			to: T::AccountId,
-			success: Vec<(FtIdentifier, BalanceOf<T>)>,
-			failed: Vec<(FtIdentifier, BalanceOf<T>)>,
+			success: Vec<(FtIdentifierOf<T>, BalanceOf<T>)>,
+			failed: Vec<(FtIdentifierOf<T>, BalanceOf<T>)>,
+		},
+		/// Claim tip and bridge success. { from, to, receiver_id, ft_identifier, amount }
+		ClaimTipAndBridge {
+			from: T::AccountId,
+			to: T::AccountId,
+			receiver_id: BoundedStringOf<T>,
+			ft_identifier: FtIdentifierOf<T>,
+			amount: BalanceOf<T>,
 		},
 		/// Claim reference success. [Vec<tips_balance>]
 		ClaimReference(Vec<TipsBalanceOf<T>>),
-		/// Pay unlockable content success. { from, to, receipt }
-		PayUnlockableContent { from: T::AccountId, to: Option<T::AccountId>, receipt: ReceiptOf<T> },
+		/// Pay unlockable content success, the memo is stored under the receipt id. { from, to,
+		/// receipt, memo }
+		PayUnlockableContent {
+			from: T::AccountId,
+			to: Option<T::AccountId>,
+			receipt: ReceiptOf<T>,
+			memo: Option<MemoOf<T>>,
+		},
+		/// Memo removed and its deposit returned. { memo_id, deposit }
+		MemoRemoved { memo_id: HashOf<T>, deposit: BalanceOf<T> },
+		/// Subscribe success, the first tip is paid. { subscription }
+		Subscribed { subscription: SubscriptionOf<T> },
+		/// Subscription tip paid. { subscription_id, tips_balance, next_payment_at }
+		SubscriptionCharged {
+			subscription_id: SubscriptionId,
+			tips_balance: TipsBalanceOf<T>,
+			next_payment_at: BlockNumberFor<T>,
+		},
+		/// Subscription tip failed and the subscription is suspended. { subscription_id, when }
+		SubscriptionSuspended { subscription_id: SubscriptionId, when: BlockNumberFor<T> },
+		/// Subscription paused. { subscription_id }
+		SubscriptionPaused { subscription_id: SubscriptionId },
+		/// Subscription resumed. { subscription_id, next_payment_at }
+		SubscriptionResumed { subscription_id: SubscriptionId, next_payment_at: BlockNumberFor<T> },
+		/// Subscription cancelled. { subscription_id }
+		SubscriptionCancelled { subscription_id: SubscriptionId },
+		/// Escrowed content payment released to the creator. { receipt_id, to, amount }
+		EscrowReleased { receipt_id: HashOf<T>, to: T::AccountId, amount: BalanceOf<T> },
+		/// Escrowed content payment refunded to the buyer. { receipt_id, to, amount }
+		EscrowRefunded { receipt_id: HashOf<T>, to: T::AccountId, amount: BalanceOf<T> },
+		/// Escrowed content payment failed to release. { receipt_id, when }
+		EscrowReleaseFailed { receipt_id: HashOf<T>, when: BlockNumberFor<T> },
+		/// Default fee policy updated. [fee_policy]
+		DefaultFeePolicyUpdated(FeePolicy),
+		/// Fee policy bounds updated. [bounds]
+		FeePolicyBoundsUpdated(FeePolicyBounds),
+		/// Server fee policy updated, `None` falls back to the default. { instance_id, fee_policy
+		/// }
+		ServerFeePolicyUpdated { instance_id: ServerId, fee_policy: Option<FeePolicy> },
+		/// Asset registered for tipping. { ft_identifier, asset }
+		AssetRegistered { ft_identifier: FtIdentifierOf<T>, asset: AssetMetadataOf<T> },
+		/// Asset status or metadata updated. { ft_identifier, asset }
+		AssetUpdated { ft_identifier: FtIdentifierOf<T>, asset: AssetMetadataOf<T> },
+		/// Unclaimed reference expired, its tippers can reclaim their tips. { tips_balance }
+		TipsExpired { tips_balance: TipsBalanceOf<T> },
+		/// Tipper reclaimed its tips from an expired reference. { tipper, tips_balance, amount }
+		TipReclaimed { tipper: T::AccountId, tips_balance: TipsBalanceOf<T>, amount: BalanceOf<T> },
+		/// Tipper reclaimed an expired anonymous tip, which names them. { commitment, tipper,
+		/// tips_balance, amount }
+		AnonymousTipReclaimed {
+			commitment: HashOf<T>,
+			tipper: T::AccountId,
+			tips_balance: TipsBalanceOf<T>,
+			amount: BalanceOf<T>,
+		},
+		/// Reference tip split updated, `None` removes it. { server_id, reference_type,
+		/// reference_id, splits }
+		TipSplitUpdated {
+			server_id: ServerIdOf<T>,
+			reference_type: ReferenceTypeOf<T>,
+			reference_id: ReferenceIdOf<T>,
+			splits: Option<TipSplitsOf<T>>,
+		},
+		/// Tip to a split reference handed out to its recipients. { tips_balance, shares }
+		TipSplitPaid {
+			tips_balance: TipsBalanceOf<T>,
+			shares: Vec<(SplitRecipientOf<T>, BalanceOf<T>)>,
+		},
+		/// Server instance reward distribution set. { instance_id, distribution }
+		RewardDistributionUpdated { instance_id: ServerId, distribution: RewardDistributionOf<T> },
+		/// Server instance reward distribution removed. { instance_id }
+		RewardDistributionRemoved { instance_id: ServerId },
+		/// Server instance rewards paid out to its beneficiaries, the failed ones are retried on
+		/// the next payout. { instance_id, success, failed }
+		RewardDistributed {
+			instance_id: ServerId,
+			success: Vec<RewardPayoutOf<T>>,
+			failed: Vec<RewardPayoutOf<T>>,
+		},
+		/// Reward payout couldn't be rescheduled, the server sets the distribution again to
+		/// resume it. { instance_id, when }
+		RewardPayoutSuspended { instance_id: ServerId, when: BlockNumberFor<T> },
+		/// Admin fee spend proposed. { proposal_id, proposal }
+		FeeSpendProposed { proposal_id: FeeSpendProposalId, proposal: FeeSpendProposalOf<T> },
+		/// Admin fee spend paid to the beneficiary, the deposit is returned. { proposal_id,
+		/// proposal }
+		FeeSpendApproved { proposal_id: FeeSpendProposalId, proposal: FeeSpendProposalOf<T> },
+		/// Admin fee spend rejected, the deposit is slashed. { proposal_id, proposal }
+		FeeSpendRejected { proposal_id: FeeSpendProposalId, proposal: FeeSpendProposalOf<T> },
 		/// Withdrawal succes { from, to, success, failed }
 		Withdrawal {
 			from: T::AccountId,
 			to: T::AccountId,
-			success: Vec<(FtIdentifier, BalanceOf<T>)>,
-			failed: Vec<(FtIdentifier, BalanceOf<T>)>,
+			success: Vec<(FtIdentifierOf<T>, BalanceOf<T>)>,
+			failed: Vec<(FtIdentifierOf<T>, BalanceOf<T>)>,
 		},
 	}
 
@@ -127,25 +705,82 @@ pub mod pallet {
 		WrongFormat,
 		NotExists,
 		InsufficientFee,
+		EmptyTips,
+		InvalidPeriod,
+		InvalidStatus,
+		FailedToSchedule,
+		Overflow,
+		FeePolicyOutOfBounds,
+		AssetNotRegistered,
+		AssetAlreadyRegistered,
+		AssetDisabled,
+		BelowMinimumTip,
+		AssetNotFunded,
+		SigningKeyNotSet,
+		BadSignature,
+		InvalidNonce,
+		ProofExpired,
+		NotExpired,
+		InvalidSplit,
+		TooManySplits,
+		InvalidBeneficiaries,
+		TooManyBeneficiaries,
+		TipsNotSettled,
+		CommitmentExists,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let tasks = SubscriptionTasks::<T>::take(n);
+			let escrows = EscrowTasks::<T>::take(n);
+			let payouts = RewardPayoutTasks::<T>::take(n);
+
+			Self::do_charge_subscriptions(n, tasks.into_inner())
+				.saturating_add(Self::do_release_escrows(n, escrows.into_inner()))
+				.saturating_add(Self::do_pay_out_rewards(n, payouts.into_inner()))
+		}
+
+		fn on_idle(n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let weight = migrations::migrate_on_idle::<T>(remaining_weight);
+			let weight = weight.saturating_add(Self::do_clear_tip_contribution_backlog(
+				remaining_weight.saturating_sub(weight),
+			));
+
+			weight.saturating_add(Self::do_sweep_expired_tips(
+				n,
+				remaining_weight.saturating_sub(weight),
+			))
+		}
+
 		fn on_runtime_upgrade() -> Weight {
 			migrations::migrate::<T>()
 		}
+
+		fn integrity_test() {
+			// Content payments are stored under this reference type.
+			assert!(T::MaxStringLength::get() as usize >= b"unlockable_content".len());
+			// The v3 migration moves references over the bound under their hex encoded hash.
+			assert!(T::MaxStringLength::get() >= 64);
+			// A payout splits at least one currency between all the beneficiaries.
+			assert!(T::MaxRewardTransfers::get() >= T::MaxRewardBeneficiaries::get());
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(T::WeightInfo::pay_content())]
+		#[pallet::weight(
+			T::WeightInfo::pay_content()
+				.saturating_add(T::WeightInfo::split_tip(T::MaxTipSplits::get()))
+		)]
 		pub fn pay_content(
 			origin: OriginFor<T>,
 			receiver: Option<AccountIdOf<T>>,
 			instance_id: u64,
 			tips_balance_info: TipsBalanceInfoOf<T>,
 			amount: BalanceOf<T>,
-			account_reference: Option<Vec<u8>>,
+			account_reference: Option<ReferenceIdOf<T>>,
+			memo: Option<MemoOf<T>>,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
 			let receipt = <Self as TippingInterface<T>>::pay_content(
@@ -157,23 +792,91 @@ pub mod pallet {
 				&account_reference,
 			)?;
 
+			if let Some(memo) = &memo {
+				Self::do_store_memo(&receipt.get_id(), &sender, memo)?;
+			}
+
 			Self::deposit_event(Event::PayUnlockableContent {
 				from: sender,
 				to: receiver,
 				receipt,
+				memo,
 			});
 			Ok(().into())
 		}
 
-		#[pallet::weight(T::WeightInfo::withdraw_fee())]
-		pub fn withdraw_fee(
+		#[pallet::weight(T::WeightInfo::pay_content_escrow())]
+		pub fn pay_content_escrow(
 			origin: OriginFor<T>,
-			receiver: T::AccountId,
+			receiver: AccountIdOf<T>,
+			instance_id: u64,
+			tips_balance_info: TipsBalanceInfoOf<T>,
+			amount: BalanceOf<T>,
+			memo: Option<MemoOf<T>>,
 		) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
+			let sender = ensure_signed(origin)?;
+			let receipt = <Self as TippingInterface<T>>::pay_content_escrow(
+				&sender,
+				instance_id,
+				&receiver,
+				&tips_balance_info,
+				&amount,
+			)?;
+
+			if let Some(memo) = &memo {
+				Self::do_store_memo(&receipt.get_id(), &sender, memo)?;
+			}
+
+			Self::deposit_event(Event::PayUnlockableContent {
+				from: sender,
+				to: Some(receiver),
+				receipt,
+				memo,
+			});
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::confirm_escrow())]
+		pub fn confirm_escrow(
+			origin: OriginFor<T>,
+			receipt_id: HashOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let receipt = <Self as TippingInterface<T>>::confirm_escrow(&sender, &receipt_id)?;
+
+			let to = receipt.get_to().clone().ok_or(Error::<T>::NotExists)?;
+			let amount = receipt.get_amount().saturating_sub(*receipt.get_fee());
+
+			Self::deposit_event(Event::EscrowReleased { receipt_id, to, amount });
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::refund_escrow())]
+		pub fn refund_escrow(
+			origin: OriginFor<T>,
+			receipt_id: HashOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let receipt = <Self as TippingInterface<T>>::refund_escrow(&sender, &receipt_id)?;
+
+			let to = receipt.get_from().clone();
+			let amount = *receipt.get_amount();
+
+			Self::deposit_event(Event::EscrowRefunded { receipt_id, to, amount });
+			Ok(().into())
+		}
+
+		/// Withdraws admin fees to `FeeDestination`, the amount of each currency is given.
+		#[pallet::weight(T::WeightInfo::withdraw_fee(amounts.len() as u32))]
+		pub fn withdraw_fee(
+			origin: OriginFor<T>,
+			amounts: FeeWithdrawalsOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::WithdrawOrigin::ensure_origin(origin)?;
 
 			let sender = Self::tipping_account_id();
-			let result = <Self as TippingInterface<T>>::withdraw_fee(&sender, &receiver)?;
+			let receiver = T::FeeDestination::get();
+			let result = <Self as TippingInterface<T>>::withdraw_fee(&sender, &receiver, &amounts)?;
 
 			let (success, failed) = result;
 
@@ -181,6 +884,85 @@ pub mod pallet {
 			Ok(().into())
 		}
 
+		/// Proposes paying admin fees out to the beneficiary, reserving `FeeSpendDeposit`.
+		#[pallet::weight(T::WeightInfo::propose_fee_spend())]
+		pub fn propose_fee_spend(
+			origin: OriginFor<T>,
+			beneficiary: AccountIdOf<T>,
+			ft_identifier: FtIdentifierOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let proposer = ensure_signed(origin)?;
+
+			Self::asset_kind(&ft_identifier)?;
+
+			let proposal_id = Self::fee_spend_proposal_count();
+			let next_proposal_id = proposal_id.checked_add(1).ok_or(Error::<T>::Overflow)?;
+			let deposit = T::FeeSpendDeposit::get();
+
+			CurrencyOf::<T>::reserve_named(&FEE_SPEND_RESERVE_ID, &proposer, deposit)
+				.map_err(|_| Error::<T>::InsufficientBalance)?;
+
+			let proposal =
+				FeeSpendProposal::new(proposer, beneficiary, ft_identifier, amount, deposit);
+
+			FeeSpendProposals::<T>::insert(proposal_id, &proposal);
+			FeeSpendProposalCount::<T>::put(next_proposal_id);
+
+			Self::deposit_event(Event::FeeSpendProposed { proposal_id, proposal });
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::approve_fee_spend())]
+		pub fn approve_fee_spend(
+			origin: OriginFor<T>,
+			proposal_id: FeeSpendProposalId,
+		) -> DispatchResultWithPostInfo {
+			T::WithdrawOrigin::ensure_origin(origin)?;
+
+			let proposal = Self::fee_spend_proposal(proposal_id).ok_or(Error::<T>::NotExists)?;
+
+			Self::do_spend_fee(
+				proposal.get_ft_identifier(),
+				&Self::tipping_account_id(),
+				proposal.get_beneficiary(),
+				*proposal.get_amount(),
+			)?;
+
+			CurrencyOf::<T>::unreserve_named(
+				&FEE_SPEND_RESERVE_ID,
+				proposal.get_proposer(),
+				*proposal.get_deposit(),
+			);
+			FeeSpendProposals::<T>::remove(proposal_id);
+
+			Self::deposit_event(Event::FeeSpendApproved { proposal_id, proposal });
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::reject_fee_spend())]
+		pub fn reject_fee_spend(
+			origin: OriginFor<T>,
+			proposal_id: FeeSpendProposalId,
+		) -> DispatchResultWithPostInfo {
+			T::WithdrawOrigin::ensure_origin(origin)?;
+
+			let proposal =
+				FeeSpendProposals::<T>::take(proposal_id).ok_or(Error::<T>::NotExists)?;
+
+			// The deposit goes to the fee destination
+			let (slashed, _) = CurrencyOf::<T>::slash_reserved_named(
+				&FEE_SPEND_RESERVE_ID,
+				proposal.get_proposer(),
+				*proposal.get_deposit(),
+			);
+
+			CurrencyOf::<T>::resolve_creating(&T::FeeDestination::get(), slashed);
+
+			Self::deposit_event(Event::FeeSpendRejected { proposal_id, proposal });
+			Ok(().into())
+		}
+
 		#[pallet::weight(T::WeightInfo::withdraw_reward())]
 		pub fn withdraw_reward(
 			origin: OriginFor<T>,
@@ -197,20 +979,135 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		#[pallet::weight(T::WeightInfo::send_tip())]
+		/// Tips the reference. With a `commitment` the tipper is left out of the pallet's stats
+		/// and events until they reveal it. The extrinsic is still signed and the balance events
+		/// still name the tipper, so this doesn't hide them from the chain.
+		#[pallet::weight(
+			T::WeightInfo::send_tip()
+				.saturating_add(T::WeightInfo::split_tip(T::MaxTipSplits::get()))
+		)]
 		pub fn send_tip(
 			origin: OriginFor<T>,
+			instance_id: u64,
 			info: TipsBalanceInfoOf<T>,
 			amount: BalanceOf<T>,
+			memo: Option<MemoOf<T>>,
+			commitment: Option<HashOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let receiver = Self::tipping_account_id();
+
+			ensure!(
+				info.get_reference_type().as_slice() != b"unlockable_content",
+				Error::<T>::Unauthorized
+			);
+
+			// Anyone could add to a tip under someone else's commitment and have it credited to
+			// them on reveal
+			if let Some(commitment) = &commitment {
+				ensure!(
+					!AnonymousTips::<T>::contains_key(info.key(), commitment),
+					Error::<T>::CommitmentExists
+				);
+			}
+
+			let (tips_balance, fee_detail) = <Self as TippingInterface<T>>::send_tip(
+				&sender,
+				instance_id,
+				&receiver,
+				&info,
+				&amount,
+				commitment.is_some(),
+			)?;
+
+			let memo = match memo {
+				Some(memo) => {
+					let memo_id = Self::do_generate_receipt_id(&sender, &info, &amount);
+					Self::do_store_memo(&memo_id, &sender, &memo)?;
+					Some((memo_id, memo))
+				},
+				None => None,
+			};
+
+			match commitment {
+				Some(commitment) => {
+					let current_block_number = <frame_system::Pallet<T>>::block_number();
+					let expires_at = current_block_number.saturating_add(T::TipExpiry::get());
+
+					AnonymousTips::<T>::insert(
+						info.key(),
+						commitment,
+						(tips_balance.clone(), expires_at),
+					);
+
+					Self::deposit_event(Event::SendAnonymousTip {
+						commitment,
+						to: receiver,
+						tips_balance,
+						fee_detail,
+						memo,
+					});
+				},
+				None => {
+					Self::deposit_event(Event::SendTip {
+						from: sender,
+						to: receiver,
+						tips_balance,
+						fee_detail,
+						memo,
+					});
+				},
+			}
+
+			Ok(().into())
+		}
+
+		/// Proves the signer sent the anonymous tip committed to as the hash of their account, the
+		/// tipped reference and the salt, and credits it to them in the reference's stats.
+		#[pallet::weight(T::WeightInfo::reveal_tip())]
+		pub fn reveal_tip(
+			origin: OriginFor<T>,
+			tips_balance_key: TipsBalanceKeyOf<T>,
+			salt: HashOf<T>,
 		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let commitment = Self::tip_commitment(&sender, &tips_balance_key, &salt);
+			let (tips_balance, _) = AnonymousTips::<T>::take(&tips_balance_key, commitment)
+				.ok_or(Error::<T>::NotExists)?;
+
+			Self::do_reveal_tip_stats(&sender, &tips_balance);
+
+			Self::deposit_event(Event::TipRevealed { commitment, from: sender, tips_balance });
+			Ok(().into())
+		}
+
+		/// Sends a batch of tips, each paid through the given instance of its server.
+		#[pallet::weight(
+			T::WeightInfo::send_tips(tips.len() as u32).saturating_add(
+				T::WeightInfo::split_tip(T::MaxTipSplits::get()).saturating_mul(tips.len() as u64)
+			)
+		)]
+		pub fn send_tips(origin: OriginFor<T>, tips: TipsOf<T>) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
 			let receiver = Self::tipping_account_id();
 
-			ensure!(info.get_reference_type() != b"unlockable_content", Error::<T>::Unauthorized);
+			ensure!(!tips.is_empty(), Error::<T>::EmptyTips);
+			ensure!(
+				tips.iter()
+					.all(|(_, info, _)| info.get_reference_type().as_slice() !=
+						b"unlockable_content"),
+				Error::<T>::Unauthorized
+			);
 
-			let data = <Self as TippingInterface<T>>::send_tip(&sender, &receiver, &info, &amount)?;
+			let (tips_balances, total) =
+				<Self as TippingInterface<T>>::send_tips(&sender, &receiver, &tips)?;
 
-			Self::deposit_event(Event::SendTip { from: sender, to: receiver, tips_balance: data });
+			Self::deposit_event(Event::SendTips {
+				from: sender,
+				to: receiver,
+				tips_balances,
+				total,
+			});
 			Ok(().into())
 		}
 
@@ -218,9 +1115,9 @@ pub mod pallet {
 		pub fn claim_tip(
 			origin: OriginFor<T>,
 			server_id: ServerIdOf<T>,
-			reference_type: ReferenceType,
-			reference_id: ReferenceId,
-			ft_identifiers: Vec<FtIdentifier>,
+			reference_type: ReferenceTypeOf<T>,
+			reference_id: ReferenceIdOf<T>,
+			ft_identifiers: Vec<FtIdentifierOf<T>>,
 		) -> DispatchResultWithPostInfo {
 			let receiver = ensure_signed(origin)?;
 			let sender = Self::tipping_account_id();
@@ -230,7 +1127,8 @@ pub mod pallet {
 			ft_identifiers.sort_unstable();
 			ft_identifiers.dedup();
 
-			let tips_balance_key = (server_id, reference_type, reference_id, b"".to_vec());
+			let tips_balance_key =
+				(server_id, reference_type, reference_id, FtIdentifierOf::<T>::default());
 			let (success, failed) = <Self as TippingInterface<T>>::claim_tip(
 				&sender,
 				&receiver,
@@ -242,13 +1140,50 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		#[pallet::weight(T::WeightInfo::claim_reference())]
+		/// Claims the tips of one asset and sends them through the bridge to `receiver_id`, e.g. a
+		/// NEAR account. Nothing is claimed when the bridge fails.
+		#[pallet::weight(T::WeightInfo::claim_tip_and_bridge())]
+		pub fn claim_tip_and_bridge(
+			origin: OriginFor<T>,
+			server_id: ServerIdOf<T>,
+			reference_type: ReferenceTypeOf<T>,
+			reference_id: ReferenceIdOf<T>,
+			ft_identifier: FtIdentifierOf<T>,
+			receiver_id: BoundedStringOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let receiver = ensure_signed(origin)?;
+			let sender = Self::tipping_account_id();
+
+			let tips_balance_key = (server_id, reference_type, reference_id, ft_identifier.clone());
+			let amount = <Self as TippingInterface<T>>::claim_tip_and_bridge(
+				&sender,
+				&receiver,
+				&tips_balance_key,
+				&receiver_id,
+			)?;
+
+			Self::deposit_event(Event::ClaimTipAndBridge {
+				from: sender,
+				to: receiver,
+				receiver_id,
+				ft_identifier,
+				amount,
+			});
+			Ok(().into())
+		}
+
+		#[pallet::weight(
+			T::WeightInfo::claim_reference().saturating_add(
+				Pallet::<T>::claim_contributions_weight(references, ft_identifiers.len())
+			)
+		)]
 		pub fn claim_reference(
 			origin: OriginFor<T>,
 			server_id: ServerIdOf<T>,
-			references: References,
-			account_references: References,
-			ft_identifiers: Vec<FtIdentifier>,
+			instance_id: u64,
+			references: ReferencesOf<T>,
+			account_references: ReferencesOf<T>,
+			ft_identifiers: Vec<FtIdentifierOf<T>>,
 			account_id: AccountIdOf<T>,
 			tx_fee: BalanceOf<T>,
 		) -> DispatchResultWithPostInfo {
@@ -264,6 +1199,7 @@ pub mod pallet {
 			let tips_balances = <Self as TippingInterface<T>>::claim_reference(
 				&who,
 				&server_id,
+				instance_id,
 				&references,
 				&account_references,
 				&ft_identifiers,
@@ -274,5 +1210,380 @@ pub mod pallet {
 			Self::deposit_event(Event::ClaimReference(tips_balances));
 			Ok(().into())
 		}
+
+		/// Claims the references with a proof signed by the server, instead of the server
+		/// submitting `claim_reference`. The proof sets the fee and the currency it's paid in.
+		#[pallet::weight(
+			T::WeightInfo::claim_reference_with_proof().saturating_add(
+				Pallet::<T>::claim_contributions_weight(proof.get_references(), ft_identifiers.len())
+			)
+		)]
+		pub fn claim_reference_with_proof(
+			origin: OriginFor<T>,
+			proof: ClaimProofOf<T>,
+			signature: T::Signature,
+			ft_identifiers: Vec<FtIdentifierOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			ensure!(&who == proof.get_account_id(), Error::<T>::Unauthorized);
+
+			let mut ft_identifiers = ft_identifiers;
+
+			ft_identifiers.sort_unstable();
+			ft_identifiers.dedup();
+
+			let tips_balances = <Self as TippingInterface<T>>::claim_reference_with_proof(
+				&who,
+				&proof,
+				&signature,
+				&ft_identifiers,
+			)?;
+
+			Self::deposit_event(Event::ClaimReference(tips_balances));
+			Ok(().into())
+		}
+
+		/// Gives the tipper its share of an unclaimed reference back once the tips expired.
+		#[pallet::weight(T::WeightInfo::reclaim_tip())]
+		pub fn reclaim_tip(
+			origin: OriginFor<T>,
+			tips_balance_key: TipsBalanceKeyOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let tipper = ensure_signed(origin)?;
+
+			let (tips_balance, amount) =
+				<Self as TippingInterface<T>>::reclaim_tip(&tipper, &tips_balance_key)?;
+
+			Self::deposit_event(Event::TipReclaimed { tipper, tips_balance, amount });
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::set_default_fee_policy())]
+		pub fn set_default_fee_policy(
+			origin: OriginFor<T>,
+			fee_policy: FeePolicy,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			ensure!(Self::fee_bounds().contains(&fee_policy), Error::<T>::FeePolicyOutOfBounds);
+
+			DefaultFeePolicy::<T>::put(fee_policy);
+
+			Self::deposit_event(Event::DefaultFeePolicyUpdated(fee_policy));
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::set_fee_policy_bounds())]
+		pub fn set_fee_policy_bounds(
+			origin: OriginFor<T>,
+			bounds: FeePolicyBounds,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			// Server policies outside the new bounds are clamped when charged
+			ensure!(bounds.contains(&Self::default_fee_policy()), Error::<T>::FeePolicyOutOfBounds);
+
+			FeeBounds::<T>::put(bounds);
+
+			Self::deposit_event(Event::FeePolicyBoundsUpdated(bounds));
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::set_server_fee_policy())]
+		pub fn set_server_fee_policy(
+			origin: OriginFor<T>,
+			instance_id: ServerId,
+			fee_policy: Option<FeePolicy>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::can_access_instance(&who, instance_id)?;
+
+			match fee_policy {
+				Some(fee_policy) => {
+					ensure!(
+						Self::fee_bounds().contains(&fee_policy),
+						Error::<T>::FeePolicyOutOfBounds
+					);
+
+					FeePolicyByServer::<T>::insert(instance_id, fee_policy);
+				},
+				None => FeePolicyByServer::<T>::remove(instance_id),
+			}
+
+			Self::deposit_event(Event::ServerFeePolicyUpdated { instance_id, fee_policy });
+			Ok(().into())
+		}
+
+		/// Pays the rewards of a server instance out to the beneficiaries every `period` blocks,
+		/// instead of the server owner withdrawing them.
+		#[pallet::weight(T::WeightInfo::set_reward_distribution(beneficiaries.len() as u32))]
+		pub fn set_reward_distribution(
+			origin: OriginFor<T>,
+			instance_id: ServerId,
+			beneficiaries: Vec<RewardBeneficiaryOf<T>>,
+			period: BlockNumberFor<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::can_access_instance(&who, instance_id)?;
+			Self::is_valid_reward_distribution(&beneficiaries, period)?;
+
+			let beneficiaries: RewardBeneficiariesOf<T> =
+				beneficiaries.try_into().map_err(|_| Error::<T>::TooManyBeneficiaries)?;
+
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+			let next_payout_at = current_block_number.saturating_add(period);
+
+			let scheduled_at =
+				Self::reward_distribution(instance_id).and_then(|e| e.get_next_payout_at());
+
+			if let Some(when) = scheduled_at {
+				Self::do_unschedule_reward_payout(instance_id, when);
+			}
+
+			Self::do_schedule_reward_payout(instance_id, next_payout_at)?;
+
+			let distribution = RewardDistribution::new(who, beneficiaries, period, next_payout_at);
+
+			RewardDistributionByInstance::<T>::insert(instance_id, &distribution);
+
+			Self::deposit_event(Event::RewardDistributionUpdated { instance_id, distribution });
+			Ok(().into())
+		}
+
+		/// Stops paying out the rewards, the server owner withdraws them again along with the
+		/// unpaid ones. Hands back up to `MaxRewardTransfers` unpaid rewards per call, the
+		/// distribution stays unscheduled until the last of them is handed back.
+		#[pallet::weight(T::WeightInfo::remove_reward_distribution(T::MaxRewardTransfers::get()))]
+		pub fn remove_reward_distribution(
+			origin: OriginFor<T>,
+			instance_id: ServerId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::can_access_instance(&who, instance_id)?;
+
+			let distribution =
+				Self::reward_distribution(instance_id).ok_or(Error::<T>::NotExists)?;
+
+			match Self::do_remove_reward_distribution(instance_id, distribution) {
+				Some(distribution) => Self::deposit_event(Event::RewardDistributionUpdated {
+					instance_id,
+					distribution,
+				}),
+				None => Self::deposit_event(Event::RewardDistributionRemoved { instance_id }),
+			}
+
+			Ok(().into())
+		}
+
+		/// Sets how the claimed owner of a reference splits the tips it receives.
+		#[pallet::weight(
+			T::WeightInfo::set_tip_split(splits.as_ref().map_or(0, |splits| splits.len() as u32))
+		)]
+		pub fn set_tip_split(
+			origin: OriginFor<T>,
+			server_id: ServerIdOf<T>,
+			reference_type: ReferenceTypeOf<T>,
+			reference_id: ReferenceIdOf<T>,
+			splits: Option<Vec<TipSplitOf<T>>>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::can_set_tip_split(&who, &server_id, &reference_type, &reference_id)?;
+
+			let key = (&server_id, &reference_type, &reference_id);
+
+			let splits = match splits {
+				Some(splits) => {
+					Self::is_valid_tip_split(&splits)?;
+
+					let splits: TipSplitsOf<T> =
+						splits.try_into().map_err(|_| Error::<T>::TooManySplits)?;
+
+					TipSplits::<T>::insert(key, &splits);
+
+					Some(splits)
+				},
+				None => {
+					TipSplits::<T>::remove(key);
+
+					None
+				},
+			};
+
+			Self::deposit_event(Event::TipSplitUpdated {
+				server_id,
+				reference_type,
+				reference_id,
+				splits,
+			});
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::register_asset())]
+		pub fn register_asset(
+			origin: OriginFor<T>,
+			ft_identifier: FtIdentifierOf<T>,
+			kind: AssetKind,
+			min_tip: BalanceOf<T>,
+			decimals: u8,
+		) -> DispatchResultWithPostInfo {
+			T::AssetRegistryOrigin::ensure_origin(origin)?;
+
+			ensure!(
+				!AssetRegistry::<T>::contains_key(&ft_identifier),
+				Error::<T>::AssetAlreadyRegistered
+			);
+
+			if let AssetKind::Asset(asset_id) = kind {
+				ensure!(Self::asset_exists(asset_id), Error::<T>::NotExists);
+			}
+
+			ensure!(Self::is_asset_funded(kind), Error::<T>::AssetNotFunded);
+
+			let asset = AssetMetadata::new(kind, min_tip, decimals);
+
+			AssetRegistry::<T>::insert(&ft_identifier, &asset);
+
+			Self::deposit_event(Event::AssetRegistered { ft_identifier, asset });
+			Ok(().into())
+		}
+
+		/// The asset kind can't change, balances are already held in it.
+		#[pallet::weight(T::WeightInfo::update_asset())]
+		pub fn update_asset(
+			origin: OriginFor<T>,
+			ft_identifier: FtIdentifierOf<T>,
+			enabled: bool,
+			min_tip: BalanceOf<T>,
+			decimals: u8,
+		) -> DispatchResultWithPostInfo {
+			T::AssetRegistryOrigin::ensure_origin(origin)?;
+
+			let asset = Self::asset_registry(&ft_identifier)
+				.ok_or(Error::<T>::AssetNotRegistered)?
+				.set_enabled(enabled)
+				.set_min_tip(min_tip)
+				.set_decimals(decimals);
+
+			if enabled {
+				ensure!(Self::is_asset_funded(asset.get_kind()), Error::<T>::AssetNotFunded);
+			}
+
+			AssetRegistry::<T>::insert(&ft_identifier, &asset);
+
+			Self::deposit_event(Event::AssetUpdated { ft_identifier, asset });
+			Ok(().into())
+		}
+
+		#[pallet::weight(
+			T::WeightInfo::subscribe()
+				.saturating_add(T::WeightInfo::split_tip(T::MaxTipSplits::get()))
+		)]
+		pub fn subscribe(
+			origin: OriginFor<T>,
+			instance_id: u64,
+			info: TipsBalanceInfoOf<T>,
+			amount: BalanceOf<T>,
+			period: BlockNumberFor<T>,
+		) -> DispatchResultWithPostInfo {
+			let subscriber = ensure_signed(origin)?;
+
+			ensure!(
+				info.get_reference_type().as_slice() != b"unlockable_content",
+				Error::<T>::Unauthorized
+			);
+
+			let subscription = <Self as SubscriptionInterface<T>>::subscribe(
+				&subscriber,
+				instance_id,
+				&info,
+				&amount,
+				period,
+			)?;
+
+			Self::deposit_event(Event::Subscribed { subscription });
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::cancel_subscription())]
+		pub fn cancel_subscription(
+			origin: OriginFor<T>,
+			subscription_id: SubscriptionId,
+		) -> DispatchResultWithPostInfo {
+			let subscriber = ensure_signed(origin)?;
+
+			<Self as SubscriptionInterface<T>>::cancel_subscription(subscription_id, &subscriber)?;
+
+			Self::deposit_event(Event::SubscriptionCancelled { subscription_id });
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::pause_subscription())]
+		pub fn pause_subscription(
+			origin: OriginFor<T>,
+			subscription_id: SubscriptionId,
+		) -> DispatchResultWithPostInfo {
+			let subscriber = ensure_signed(origin)?;
+
+			<Self as SubscriptionInterface<T>>::pause_subscription(subscription_id, &subscriber)?;
+
+			Self::deposit_event(Event::SubscriptionPaused { subscription_id });
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::resume_subscription())]
+		pub fn resume_subscription(
+			origin: OriginFor<T>,
+			subscription_id: SubscriptionId,
+		) -> DispatchResultWithPostInfo {
+			let subscriber = ensure_signed(origin)?;
+
+			let subscription = <Self as SubscriptionInterface<T>>::resume_subscription(
+				subscription_id,
+				&subscriber,
+			)?;
+			let next_payment_at = subscription.get_next_payment_at().unwrap_or_default();
+
+			Self::deposit_event(Event::SubscriptionResumed { subscription_id, next_payment_at });
+			Ok(().into())
+		}
+
+		/// Removes a memo the signer stored and returns its deposit.
+		#[pallet::weight(T::WeightInfo::remove_memo())]
+		pub fn remove_memo(origin: OriginFor<T>, memo_id: HashOf<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let deposit = Self::do_remove_memo(&memo_id, &who)?;
+
+			Self::deposit_event(Event::MemoRemoved { memo_id, deposit });
+			Ok(().into())
+		}
+
+		/// Takes back an anonymous tip once it expired if its reference is still unclaimed. The
+		/// tipper proves it with the salt, like revealing it, so this names them.
+		#[pallet::weight(T::WeightInfo::reclaim_anonymous_tip())]
+		pub fn reclaim_anonymous_tip(
+			origin: OriginFor<T>,
+			tips_balance_key: TipsBalanceKeyOf<T>,
+			salt: HashOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let tipper = ensure_signed(origin)?;
+			let commitment = Self::tip_commitment(&tipper, &tips_balance_key, &salt);
+
+			let (tips_balance, amount) =
+				Self::do_reclaim_anonymous_tip(&tipper, &tips_balance_key, &commitment)?;
+
+			Self::deposit_event(Event::AnonymousTipReclaimed {
+				commitment,
+				tipper,
+				tips_balance,
+				amount,
+			});
+			Ok(().into())
+		}
 	}
 }
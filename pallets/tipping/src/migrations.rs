@@ -15,10 +15,20 @@ pub fn migrate<T: Config>() -> Weight {
 		version = StorageVersion::new(2);
 	}
 
+	if version == 2 {
+		weight = weight.saturating_add(version::v3::migrate::<T>());
+		version = StorageVersion::new(3);
+	}
+
 	version.put::<Pallet<T>>();
 	weight
 }
 
+/// Carries on the migrations that take more than the upgrade block, returns the weight used.
+pub fn migrate_on_idle<T: Config>(remaining_weight: Weight) -> Weight {
+	version::v3::migrate_tips_balances::<T>(remaining_weight)
+}
+
 mod version {
 	use super::*;
 
@@ -83,10 +93,7 @@ mod version {
 		use frame_support::storage_alias;
 		use sp_std::vec::Vec;
 
-		use crate::{
-			AccountIdOf, BalanceOf, TipsBalance,
-			TipsBalanceByReference as NewTipsBalanceByReference, TipsBalanceInfo,
-		};
+		use crate::{AccountIdOf, BalanceOf, TipsBalance, TipsBalanceInfo};
 
 		pub fn migrate<T: Config>() -> Weight {
 			let mut weight = T::DbWeight::get().writes(1);
@@ -149,8 +156,9 @@ mod version {
 						new_tips_balance.set_account_id(account_id);
 					}
 
+					// Written in the v2 layout, v3 bounds it like every other entry
 					if old_server_id == b"0" {
-						NewTipsBalanceByReference::<T>::insert(
+						TipsBalanceByReference::<T>::insert(
 							new_tips_balance.key(),
 							new_tips_balance,
 						);
@@ -165,4 +173,380 @@ mod version {
 			weight
 		}
 	}
+
+	pub mod v3 {
+		use super::*;
+		use crate::{
+			AccountIdOf, AssetKind, AssetMetadata, AssetRegistry, BalanceOf, BoundedStringOf,
+			CurrencyOf, DefaultFeePolicy, FeeBounds, FeePolicy, FeePolicyBounds, FtIdentifierOf,
+			RewardBalance, TipExpiryCursor, TipsBalance, TipsBalanceByReference, TipsBalanceInfo,
+			TipsBalanceKey, TipsBalanceOf, WithdrawalBalance,
+		};
+		use frame_support::{
+			sp_runtime::{
+				traits::{Saturating, Zero},
+				Permill,
+			},
+			storage_alias,
+			traits::Currency,
+		};
+		use scale_info::prelude::string::ToString;
+		use sp_io::hashing::blake2_256;
+		use sp_std::{collections::btree_set::BTreeSet, str, vec::Vec};
+
+		type OldTipsBalanceOf<T> = TipsBalance<BalanceOf<T>, AccountIdOf<T>, AccountIdOf<T>>;
+		type OldTipsBalanceKeyOf<T> = TipsBalanceKey<AccountIdOf<T>>;
+
+		/// Tips of references longer than `MaxStringLength` are moved under this reference type,
+		/// with the hex encoded blake2 256 hash of their v2 key as reference id.
+		pub const UNBOUNDED_REFERENCE_TYPE: &[u8] = b"unbounded_reference";
+
+		mod v2 {
+			use super::*;
+
+			#[storage_alias]
+			pub type TipsBalanceByReference<T: Config> = StorageNMap<
+				Tipping,
+				(
+					NMapKey<Blake2_128Concat, AccountIdOf<T>>,
+					NMapKey<Blake2_128Concat, Vec<u8>>,
+					NMapKey<Blake2_128Concat, Vec<u8>>,
+					NMapKey<Blake2_128Concat, Vec<u8>>,
+				),
+				OldTipsBalanceOf<T>,
+			>;
+
+			#[storage_alias]
+			pub type WithdrawalBalance<T: Config> =
+				StorageMap<Tipping, Blake2_128Concat, Vec<u8>, BalanceOf<T>, ValueQuery>;
+
+			#[storage_alias]
+			pub type RewardBalance<T: Config> = StorageNMap<
+				Tipping,
+				(
+					NMapKey<Blake2_128Concat, AccountIdOf<T>>,
+					NMapKey<Blake2_128Concat, u64>,
+					NMapKey<Blake2_128Concat, Vec<u8>>,
+				),
+				BalanceOf<T>,
+				ValueQuery,
+			>;
+		}
+
+		/// The raw key of the last tips balance bounded by `migrate_tips_balances`, empty before
+		/// the first one. Removed once every tips balance is bounded.
+		#[storage_alias]
+		type TipsBalanceCursor = StorageValue<Tipping, Vec<u8>>;
+
+		/// Moves the v2 layout to the current one: fee policies, the asset registry, the held
+		/// tips and bounded storage. Admin fees and rewards are bounded in the upgrade block, the
+		/// tips balances are too many for it and are bounded `on_idle` afterwards.
+		pub fn migrate<T: Config>() -> Weight {
+			let mut weight = T::DbWeight::get().writes(1);
+
+			let (fee_weight, native_fees) = migrate_fee_balances::<T>();
+
+			weight = weight.saturating_add(fee_weight);
+			weight = weight.saturating_add(migrate_fee_policy::<T>());
+			weight = weight.saturating_add(migrate_tipping_account::<T>(native_fees));
+			weight = weight.saturating_add(migrate_tip_expiry::<T>());
+
+			TipsBalanceCursor::put(Vec::<u8>::new());
+
+			weight.saturating_add(T::DbWeight::get().writes(1))
+		}
+
+		fn migrate_fee_policy<T: Config>() -> Weight {
+			// Previous runtime constants: TransactionFee = 5, AdminFee = 10. Tip fee starts at
+			// zero, no server may charge it until governance raises the bound.
+			let fee_policy = FeePolicy::new(Permill::from_percent(5), Permill::from_percent(10));
+			let bounds = FeePolicyBounds::new(Permill::from_percent(10), Permill::from_percent(10));
+
+			DefaultFeePolicy::<T>::put(fee_policy);
+			FeeBounds::<T>::put(bounds);
+
+			T::DbWeight::get().writes(2)
+		}
+
+		/// Bounds the admin fee and reward balances and registers their currencies. There is an
+		/// entry per currency and server instance, few enough for the upgrade block. Returns the
+		/// native fees and rewards, the tipping account keeps them free.
+		fn migrate_fee_balances<T: Config>() -> (Weight, BalanceOf<T>) {
+			let mut weight = T::DbWeight::get().writes(1);
+			let native = Pallet::<T>::bounded_string(b"native");
+			let mut ft_identifiers = BTreeSet::<FtIdentifierOf<T>>::new();
+			let mut native_fees = BalanceOf::<T>::zero();
+
+			let metadata = AssetMetadata::new(AssetKind::Native, Zero::zero(), 18);
+
+			AssetRegistry::<T>::insert(&native, metadata);
+
+			let unbounded_fees = v2::WithdrawalBalance::<T>::iter()
+				.filter(|(ft_identifier, _)| {
+					weight = weight.saturating_add(T::DbWeight::get().reads(1));
+					FtIdentifierOf::<T>::try_from(ft_identifier.clone()).is_err()
+				})
+				.collect::<Vec<(Vec<u8>, BalanceOf<T>)>>();
+
+			for (ft_identifier, amount) in unbounded_fees.into_iter() {
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 2));
+
+				v2::WithdrawalBalance::<T>::remove(&ft_identifier);
+
+				match bounded_ft_identifier::<T>(&ft_identifier) {
+					Some(bounded) => WithdrawalBalance::<T>::mutate(bounded, |fee| {
+						*fee = fee.saturating_add(amount);
+					}),
+					None => log::warn!(
+						target: "runtime::tipping",
+						"Dropped {:?} admin fees in unknown currency {:?}",
+						amount,
+						ft_identifier,
+					),
+				}
+			}
+
+			let unbounded_rewards = v2::RewardBalance::<T>::iter()
+				.filter(|((_, _, ft_identifier), _)| {
+					weight = weight.saturating_add(T::DbWeight::get().reads(1));
+					FtIdentifierOf::<T>::try_from(ft_identifier.clone()).is_err()
+				})
+				.collect::<Vec<((AccountIdOf<T>, u64, Vec<u8>), BalanceOf<T>)>>();
+
+			for ((server_id, instance_id, ft_identifier), amount) in unbounded_rewards.into_iter() {
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 2));
+
+				v2::RewardBalance::<T>::remove((&server_id, instance_id, &ft_identifier));
+
+				match bounded_ft_identifier::<T>(&ft_identifier) {
+					Some(bounded) =>
+						RewardBalance::<T>::mutate((&server_id, instance_id, bounded), |reward| {
+							*reward = reward.saturating_add(amount);
+						}),
+					None => log::warn!(
+						target: "runtime::tipping",
+						"Dropped {:?} rewards of {:?} instance {} in unknown currency {:?}",
+						amount,
+						server_id,
+						instance_id,
+						ft_identifier,
+					),
+				}
+			}
+
+			for (ft_identifier, amount) in WithdrawalBalance::<T>::iter() {
+				weight = weight.saturating_add(T::DbWeight::get().reads(1));
+
+				if ft_identifier == native {
+					native_fees = native_fees.saturating_add(amount);
+				}
+
+				ft_identifiers.insert(ft_identifier);
+			}
+
+			for ((_, _, ft_identifier), amount) in RewardBalance::<T>::iter() {
+				weight = weight.saturating_add(T::DbWeight::get().reads(1));
+
+				if ft_identifier == native {
+					native_fees = native_fees.saturating_add(amount);
+				}
+
+				ft_identifiers.insert(ft_identifier);
+			}
+
+			for ft_identifier in ft_identifiers {
+				weight = weight.saturating_add(register_asset::<T>(&ft_identifier));
+			}
+
+			(weight, native_fees)
+		}
+
+		/// Reserves the outstanding native tips on the tipping account, admin fees and rewards
+		/// stay free. Whatever else the account holds was tipped, so the tips balances don't have
+		/// to be added up. The existential deposit comes out of what is left over, then out of
+		/// the native admin fees, nothing is minted for it.
+		fn migrate_tipping_account<T: Config>(native_fees: BalanceOf<T>) -> Weight {
+			let tipping_account_id = Pallet::<T>::tipping_account_id();
+			let native = Pallet::<T>::bounded_string(b"native");
+			let minimum_balance = CurrencyOf::<T>::minimum_balance();
+			let free_balance = CurrencyOf::<T>::free_balance(&tipping_account_id);
+
+			if native_fees < minimum_balance {
+				WithdrawalBalance::<T>::mutate(&native, |fee| {
+					*fee = fee.saturating_sub(minimum_balance - native_fees);
+				});
+			}
+
+			// A tipping account holding less than its fees keeps all of it free
+			let reserved = free_balance.saturating_sub(native_fees.max(minimum_balance));
+
+			if let Err(error) = Pallet::<T>::do_reserve_tips(&native, reserved) {
+				log::warn!(
+					target: "runtime::tipping",
+					"Failed to reserve {:?} native tips on the tipping account: {:?}",
+					reserved,
+					error,
+				);
+			}
+
+			T::DbWeight::get().reads_writes(3, 3)
+		}
+
+		fn migrate_tip_expiry<T: Config>() -> Weight {
+			// Nothing expires before this upgrade, so the sweeper starts from here
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+
+			TipExpiryCursor::<T>::put(current_block_number);
+
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		/// Bounds as many tips balances as the remaining weight allows, from where the previous
+		/// block stopped. Tips balances not bounded yet can't be claimed in the meantime.
+		pub fn migrate_tips_balances<T: Config>(remaining_weight: Weight) -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			let cursor = match TipsBalanceCursor::get() {
+				Some(cursor) => cursor,
+				None => return weight,
+			};
+
+			// Reads the entry and the asset registry, moves the entry and registers its asset
+			let entry_weight = T::DbWeight::get().reads_writes(3, 3);
+			let mut tips_balances = if cursor.is_empty() {
+				v2::TipsBalanceByReference::<T>::iter()
+			} else {
+				v2::TipsBalanceByReference::<T>::iter_from(cursor)
+			};
+			let mut last_key = None;
+
+			// Leaves room to store the cursor
+			weight = weight.saturating_add(T::DbWeight::get().writes(1));
+
+			while weight.saturating_add(entry_weight).ref_time() <= remaining_weight.ref_time() {
+				let (key, old) = match tips_balances.next() {
+					Some(tips_balance) => tips_balance,
+					None => {
+						TipsBalanceCursor::kill();
+						log::info!(target: "runtime::tipping", "Bounded every tips balance");
+						return weight
+					},
+				};
+
+				weight = weight.saturating_add(entry_weight);
+				last_key = Some(v2::TipsBalanceByReference::<T>::hashed_key_for(&key));
+
+				migrate_tips_balance::<T>(key, old);
+			}
+
+			if let Some(last_key) = last_key {
+				TipsBalanceCursor::put(last_key);
+			}
+
+			weight
+		}
+
+		/// Bounded strings encode like the old ones, only the tips over the bound have to move.
+		/// The tips of references over the bound stay claimable under
+		/// `UNBOUNDED_REFERENCE_TYPE`, those of an asset id over the bound move to the plain id.
+		fn migrate_tips_balance<T: Config>(key: OldTipsBalanceKeyOf<T>, old: OldTipsBalanceOf<T>) {
+			if let Ok(tips_balance) = TipsBalanceOf::<T>::try_from(old.clone()) {
+				register_asset::<T>(tips_balance.get_ft_identifier());
+				return
+			}
+
+			v2::TipsBalanceByReference::<T>::remove(&key);
+
+			let amount = *old.get_amount();
+			let ft_identifier = match bounded_ft_identifier::<T>(&key.3) {
+				Some(ft_identifier) => ft_identifier,
+				None => {
+					// Tips in an unknown currency never moved any funds
+					log::warn!(
+						target: "runtime::tipping",
+						"Dropped {:?} tips to {:?} in unknown currency",
+						amount,
+						key,
+					);
+					return
+				},
+			};
+
+			register_asset::<T>(&ft_identifier);
+
+			let reference_type = BoundedStringOf::<T>::try_from(key.1.clone());
+			let reference_id = BoundedStringOf::<T>::try_from(key.2.clone());
+			let (reference_type, reference_id) = match (reference_type, reference_id) {
+				(Ok(reference_type), Ok(reference_id)) => (reference_type, reference_id),
+				_ => (
+					Pallet::<T>::bounded_string(UNBOUNDED_REFERENCE_TYPE),
+					Pallet::<T>::bounded_string(&hex(&key.using_encoded(blake2_256))),
+				),
+			};
+			let tips_balance_info =
+				TipsBalanceInfo::new(&key.0, &reference_type, &reference_id, &ft_identifier);
+
+			log::info!(
+				target: "runtime::tipping",
+				"Moved {:?} tips from {:?} to {:?}",
+				amount,
+				key,
+				tips_balance_info.key(),
+			);
+
+			// References moving to the same key keep the account of the first
+			TipsBalanceByReference::<T>::mutate(tips_balance_info.key(), |value| {
+				let tips_balance = value.get_or_insert_with(|| {
+					let mut tips_balance = TipsBalance::new(&tips_balance_info, &Zero::zero());
+
+					if let Some(account_id) = old.get_account_id() {
+						tips_balance.set_account_id(account_id);
+					}
+
+					tips_balance
+				});
+
+				tips_balance.add_amount(amount);
+			});
+		}
+
+		/// An asset id over the bound, leading zeros and all, parses to a bounded one.
+		fn bounded_ft_identifier<T: Config>(ft_identifier: &[u8]) -> Option<FtIdentifierOf<T>> {
+			if let Ok(ft_identifier) = FtIdentifierOf::<T>::try_from(ft_identifier.to_vec()) {
+				return Some(ft_identifier)
+			}
+
+			let asset_id = str::from_utf8(ft_identifier).ok()?.parse::<u32>().ok()?;
+
+			FtIdentifierOf::<T>::try_from(asset_id.to_string().into_bytes()).ok()
+		}
+
+		/// Existing assets stay claimable, governance enables the ones still used for tipping.
+		fn register_asset<T: Config>(ft_identifier: &FtIdentifierOf<T>) -> Weight {
+			if AssetRegistry::<T>::contains_key(ft_identifier) {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let asset_id = str::from_utf8(ft_identifier)
+				.ok()
+				.and_then(|ft_identifier| ft_identifier.parse::<u32>().ok());
+
+			if let Some(asset_id) = asset_id {
+				let asset = AssetMetadata::new(AssetKind::Asset(asset_id), Zero::zero(), 0)
+					.set_enabled(false);
+
+				AssetRegistry::<T>::insert(ft_identifier, asset);
+			}
+
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		fn hex(bytes: &[u8]) -> Vec<u8> {
+			const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+			bytes
+				.iter()
+				.flat_map(|byte| [DIGITS[(byte >> 4) as usize], DIGITS[(byte & 0xf) as usize]])
+				.collect()
+		}
+	}
 }
@@ -1,23 +1,36 @@
 use crate as pallet_tipping;
+use crate::{
+	AssetKind, AssetMetadata, BoundedStringOf, FeePolicy, FeePolicyBounds, References,
+	ReferencesOf, TokenBridge,
+};
 
 use sp_core::{
 	sr25519::{self as sr25519, Signature},
 	Pair, H256,
 };
 use sp_io::TestExternalities;
+use sp_keystore::{testing::KeyStore, KeystoreExt};
 use sp_runtime::{
-	testing::Header,
+	testing::{Header, UintAuthorityId},
 	traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Verify},
+	Permill,
 };
 
 use frame_support::{
-	construct_runtime, parameter_types,
-	traits::{ConstU16, ConstU32, ConstU64, Everything, GenesisBuild},
+	construct_runtime,
+	dispatch::{DispatchError, DispatchResult},
+	parameter_types,
+	traits::{
+		fungibles, ConstU16, ConstU32, ConstU64, Currency, Everything, ExistenceRequirement,
+		GenesisBuild,
+	},
 	weights::Weight,
+	BoundedVec,
 };
 use frame_system as system;
 
 use pallet_balances::AccountData;
+use std::sync::Arc;
 
 type UncheckedExtrinsic = system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = system::mocking::MockBlock<Test>;
@@ -36,6 +49,7 @@ construct_runtime!(
 		Timestamp: pallet_timestamp,
 		Balances: pallet_balances,
 		Assets: pallet_assets,
+		Server: pallet_server,
 		Tipping: pallet_tipping,
 	}
 );
@@ -92,15 +106,68 @@ impl pallet_balances::Config for Test {
 	type DustRemoval = ();
 	type ExistentialDeposit = ExistentialDeposit;
 	type MaxLocks = ();
-	type MaxReserves = ();
+	type MaxReserves = ConstU32<50>;
 	type ReserveIdentifier = [u8; 8];
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
 }
 
 parameter_types! {
-	pub const AdminFee: u8 = 5;
-	pub const TransactionFee: u8 = 5;
+	pub const MinimumStakeAmount: Balance = 1;
+	pub const MinimumDelegationAmount: Balance = 1;
+	pub const EraLength: u64 = 50;
+	pub const ReportDeposit: Balance = 2;
+	pub const ReporterRewardShare: Permill = Permill::from_percent(50);
+	pub const MaxSlashFraction: Permill = Permill::from_percent(50);
+	pub const ScheduledBlockTime: u64 = 10;
+	pub const MaxScheduledPerBlock: u32 = 5;
+	pub const MaxApiUrlLength: u32 = 64;
+	pub const HeartbeatInterval: u64 = 5;
+	pub const InactivityPeriod: u64 = 20;
+}
+
+impl pallet_server::Config for Test {
+	type Currency = Balances;
+	type EraLength = EraLength;
+	type HeartbeatInterval = HeartbeatInterval;
+	type HeartbeatKey = UintAuthorityId;
+	type InactivityPeriod = InactivityPeriod;
+	type MaxApiUrlLength = MaxApiUrlLength;
+	type MaxDelegatorsPerServer = ConstU32<2>;
+	type MaxDescriptionLength = ConstU32<64>;
+	type MaxMetadataFieldLength = ConstU32<16>;
+	type MaxPayoutHistory = ConstU32<2>;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type MaxSlashFraction = MaxSlashFraction;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type MinimumDelegationAmount = MinimumDelegationAmount;
+	type MinimumStakeAmount = MinimumStakeAmount;
+	type ReportDeposit = ReportDeposit;
+	type ReporterRewardShare = ReporterRewardShare;
+	type RewardFunding = Tipping;
+	type RuntimeEvent = RuntimeEvent;
+	type ScheduledBlockTime = ScheduledBlockTime;
+	type SlashDestination = FeeDestination;
+	type SlashOrigin = system::EnsureRoot<AccountId>;
+	type UnsignedPriority = ConstU64<{ u64::MAX }>;
+	type WeightInfo = ();
+}
+
+impl<C> system::offchain::SendTransactionTypes<C> for Test
+where
+	RuntimeCall: From<C>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = RuntimeCall;
+}
+
+parameter_types! {
+	pub FeeDestination: AccountId = account_key("treasury");
+	pub const FeeSpendDeposit: Balance = 5;
+	pub const MemoDepositBase: Balance = 1;
+	pub const MemoDepositPerByte: Balance = 1;
+	pub static RewardPotShare: Permill = Permill::zero();
 }
 
 impl pallet_tipping::Config for Test {
@@ -109,9 +176,62 @@ impl pallet_tipping::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
 	type Assets = Assets;
+	type Server = Server;
+	type Bridge = MockBridge;
 	type WeightInfo = ();
-	type AdminFee = AdminFee;
-	type TransactionFee = TransactionFee;
+	type MaxTipsPerBatch = ConstU32<10>;
+	type MaxSubscriptionPeriod = ConstU64<100>;
+	type MaxSubscriptionsPerBlock = ConstU32<2>;
+	type EscrowPeriod = ConstU64<10>;
+	type MaxEscrowsPerBlock = ConstU32<2>;
+	type AssetRegistryOrigin = system::EnsureRoot<AccountId>;
+	type WithdrawOrigin = system::EnsureRoot<AccountId>;
+	type FeeDestination = FeeDestination;
+	type MaxFeeWithdrawals = ConstU32<10>;
+	type FeeSpendDeposit = FeeSpendDeposit;
+	type RewardPotShare = RewardPotShare;
+	type TipExpiry = ConstU64<20>;
+	type MaxTipSplits = ConstU32<10>;
+	type MaxStringLength = ConstU32<64>;
+	type MaxTipExpiriesPerBlock = ConstU32<10>;
+	type MaxTipContributionRemovals = ConstU32<2>;
+	type MaxMemoLength = ConstU32<32>;
+	type MemoDepositBase = MemoDepositBase;
+	type MemoDepositPerByte = MemoDepositPerByte;
+	type MaxTopTippers = ConstU32<2>;
+	type MaxRewardBeneficiaries = ConstU32<10>;
+	type MaxReferenceIds = ConstU32<10>;
+	type MaxRewardPayoutsPerBlock = ConstU32<10>;
+	type MaxRewardTransfers = ConstU32<10>;
+	type Signature = Signature;
+	type Signer = sr25519::Public;
+}
+
+/// Locks native tokens in the `bridge` account and burns assets, rejecting `fail.near`.
+pub struct MockBridge;
+impl TokenBridge<AccountId, Balance> for MockBridge {
+	fn bridge(
+		sender: &AccountId,
+		asset: AssetKind,
+		receiver_id: Vec<u8>,
+		amount: Balance,
+	) -> DispatchResult {
+		if receiver_id == b"fail.near" {
+			return Err(DispatchError::Other("BridgeFailed"))
+		}
+
+		match asset {
+			AssetKind::Native => <Balances as Currency<AccountId>>::transfer(
+				sender,
+				&account_key("bridge"),
+				amount,
+				ExistenceRequirement::KeepAlive,
+			),
+			AssetKind::Asset(asset_id) =>
+				<Assets as fungibles::Mutate<AccountId>>::burn_from(asset_id, sender, amount)
+					.map(|_| ()),
+		}
+	}
 }
 
 parameter_types! {
@@ -132,7 +252,7 @@ impl pallet_assets::Config for Test {
 	type Currency = Balances;
 	type Extra = ();
 	type ForceOrigin = system::EnsureRoot<AccountId>;
-	type Freezer = ();
+	type Freezer = Tipping;
 	type MetadataDepositBase = MetadataDepositBase;
 	type MetadataDepositPerByte = MetadataDepositPerByte;
 	type RuntimeEvent = RuntimeEvent;
@@ -146,6 +266,21 @@ pub fn account_key(s: &str) -> sr25519::Public {
 		.public()
 }
 
+pub fn bounded(s: &[u8]) -> BoundedStringOf<Test> {
+	s.to_vec().try_into().expect("static values fit the bound; qed")
+}
+
+pub fn references_of(
+	reference_type: &BoundedStringOf<Test>,
+	reference_ids: &[BoundedStringOf<Test>],
+) -> ReferencesOf<Test> {
+	References::new(reference_type, reference_ids).expect("static values fit the bound; qed")
+}
+
+pub fn bounded_memo(s: &[u8]) -> BoundedVec<u8, ConstU32<32>> {
+	s.to_vec().try_into().expect("static values fit the bound; qed")
+}
+
 pub struct ExternalityBuilder {
 	existential_deposit: u64,
 }
@@ -161,6 +296,7 @@ impl ExternalityBuilder {
 		let sender_1_public = account_key("sender_1");
 		let sender_2_public = account_key("sender_2");
 		let admin_public = account_key("admin");
+		let tipping_account_id = Tipping::tipping_account_id();
 
 		pallet_assets::GenesisConfig::<Test> {
 			assets: vec![(1, alice_public, true, 1), (2, alice_public, true, 1)],
@@ -183,6 +319,8 @@ impl ExternalityBuilder {
 				(2, admin_public, 50),
 				(2, sender_1_public, 20_000),
 				(2, sender_2_public, 20_000),
+				(1, tipping_account_id, 1),
+				(2, tipping_account_id, 1),
 			],
 		}
 		.assimilate_storage(&mut t)
@@ -190,7 +328,7 @@ impl ExternalityBuilder {
 
 		pallet_balances::GenesisConfig::<Test> {
 			balances: vec![
-				(alice_public, 10),
+				(alice_public, 11),
 				(bob_public, 20),
 				(john_public, 30),
 				(satoshi_public, 40),
@@ -202,8 +340,32 @@ impl ExternalityBuilder {
 		.assimilate_storage(&mut t)
 		.unwrap();
 
+		pallet_tipping::GenesisConfig::<Test> {
+			assets: vec![
+				(b"native".to_vec(), AssetMetadata::new(AssetKind::Native, 0, 18)),
+				(b"1".to_vec(), AssetMetadata::new(AssetKind::Asset(1), 0, 18)),
+				(b"2".to_vec(), AssetMetadata::new(AssetKind::Asset(2), 0, 18)),
+			],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
 		let mut ext = TestExternalities::new(t);
-		ext.execute_with(|| System::set_block_number(1));
+		ext.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
+		ext.execute_with(|| {
+			System::set_block_number(1);
+
+			// Alice runs the server every test tips through
+			let api_url = b"https://api.dev.myriad.social".to_vec().try_into().unwrap();
+			Server::register(RuntimeOrigin::signed(alice_public), api_url, None).unwrap();
+
+			// 5% transaction fee, 5% of it goes to the admin
+			let bounds = FeePolicyBounds::new(Permill::from_percent(10), Permill::from_percent(5))
+				.set_max_tip_fee(Permill::from_percent(10));
+			let fee_policy = FeePolicy::new(Permill::from_percent(5), Permill::from_percent(5));
+			Tipping::set_fee_policy_bounds(RuntimeOrigin::root(), bounds).unwrap();
+			Tipping::set_default_fee_policy(RuntimeOrigin::root(), fee_policy).unwrap();
+		});
 		ext
 	}
 
@@ -0,0 +1,44 @@
+use crate::{FtIdentifier, ReferenceId, ReferenceType, TipStats, TipsBalance};
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait TippingApi<AccountId, Balance>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// Every tips balance stored for a reference, one entry per `FtIdentifier`.
+		fn get_tips_balances(
+			server_id: AccountId,
+			reference_type: ReferenceType,
+			reference_id: ReferenceId,
+		) -> Vec<TipsBalance<Balance, AccountId, AccountId>>;
+
+		/// Total balance per `FtIdentifier` that the account can claim from the server with
+		/// `claim_tip`.
+		fn get_claimable_tips(
+			server_id: AccountId,
+			account_id: AccountId,
+		) -> Vec<(FtIdentifier, Balance)>;
+
+		/// Whether the buyer holds a receipt, that has not been refunded, for the content.
+		fn has_paid(buyer: AccountId, server_id: AccountId, reference_id: ReferenceId) -> bool;
+
+		/// Lifetime totals and tipper count of a reference, one entry per `FtIdentifier`.
+		fn get_tip_stats(
+			server_id: AccountId,
+			reference_type: ReferenceType,
+			reference_id: ReferenceId,
+		) -> Vec<(FtIdentifier, TipStats<Balance>)>;
+
+		/// The biggest tippers of a reference in the `FtIdentifier`, biggest first.
+		fn get_top_tippers(
+			server_id: AccountId,
+			reference_type: ReferenceType,
+			reference_id: ReferenceId,
+			ft_identifier: FtIdentifier,
+		) -> Vec<(AccountId, Balance)>;
+	}
+}
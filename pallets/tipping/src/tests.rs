@@ -1,5 +1,20 @@
-use crate::{mock::*, Error, Receipt, References, TipsBalance, TipsBalanceInfo};
-use frame_support::{assert_noop, assert_ok, dispatch::DispatchError};
+use crate::{
+	mock::*, AssetKind, AssetMetadata, ClaimProof, ClaimProofOf, Error, FeeDetail, FeePolicy,
+	FeePolicyBounds, FeeSpendProposal, Memo, Receipt, ReceiptStatus, ReferencesOf,
+	RewardBeneficiary, RewardDistribution, RewardDistributionOf, SplitRecipient, Subscription,
+	SubscriptionStatus, TipSplit, TipStats, TipsBalance, TipsBalanceByReference, TipsBalanceInfo,
+	UnpaidRewards,
+};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{DispatchError, Dispatchable},
+	traits::{Currency, NamedReservableCurrency, OnIdle, OnInitialize},
+	weights::Weight,
+};
+use pallet_server::ActionType;
+use sp_core::{sr25519, Pair, H256};
+use sp_runtime::Permill;
 
 #[test]
 fn pay_content_with_myria_works() {
@@ -12,9 +27,9 @@ fn pay_content_with_myria_works() {
 
 		let tips_balance_info = TipsBalanceInfo::new(
 			&server_id,
-			b"unlockable_content",
-			b"unlockable_content_id",
-			b"native",
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
 		);
 
 		assert_ok!(Tipping::pay_content(
@@ -24,14 +39,16 @@ fn pay_content_with_myria_works() {
 			tips_balance_info,
 			amount,
 			None,
+			None,
 		));
 
 		assert_eq!(Balances::free_balance(sender), 10_000); // ori 9 500
 		assert_eq!(Balances::free_balance(receiver), 9_530); // ori 10 030
 		assert_eq!(Balances::free_balance(tipping_account_id), 500);
+		assert_eq!(Balances::reserved_balance(tipping_account_id), 0);
 
-		assert_eq!(Tipping::withdrawal_balance(b"native".to_vec()), 25);
-		assert_eq!(Tipping::reward_balance((server_id, 0, b"native".to_vec())), 475);
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"native")), 25);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 475);
 	})
 }
 
@@ -44,8 +61,12 @@ fn pay_content_with_assets_works() {
 		let tipping_account_id = Tipping::tipping_account_id();
 		let amount = 10_000;
 
-		let tips_balance_info =
-			TipsBalanceInfo::new(&server_id, b"unlockable_content", b"unlockable_content_id", b"1");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"1"),
+		);
 
 		assert_ok!(Tipping::pay_content(
 			RuntimeOrigin::signed(sender),
@@ -54,14 +75,15 @@ fn pay_content_with_assets_works() {
 			tips_balance_info,
 			amount,
 			None,
+			None,
 		));
 
 		assert_eq!(Assets::balance(1, sender), 10_000u128); // ori 9_500
 		assert_eq!(Assets::balance(1, receiver), 9_530u128); // ori 10_030
-		assert_eq!(Assets::balance(1, tipping_account_id), 500u128);
+		assert_eq!(Assets::balance(1, tipping_account_id), 501u128);
 
-		assert_eq!(Tipping::withdrawal_balance(b"1".to_vec()), 25);
-		assert_eq!(Tipping::reward_balance((server_id, 0, b"1".to_vec())), 475);
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"1")), 25);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"1"))), 475);
 	})
 }
 
@@ -75,9 +97,9 @@ fn pay_content_to_escrow_works() {
 
 		let tips_balance_info = TipsBalanceInfo::new(
 			&server_id,
-			b"unlockable_content",
-			b"unlockable_content_id",
-			b"native",
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
 		);
 
 		assert_ok!(Tipping::pay_content(
@@ -86,16 +108,23 @@ fn pay_content_to_escrow_works() {
 			0,
 			tips_balance_info,
 			amount,
-			Some(b"user_id".to_vec()),
+			Some(bounded(b"user_id")),
+			None,
 		));
 
 		assert_eq!(Balances::free_balance(sender), 10_000); // ori 9_500
-		assert_eq!(Balances::free_balance(tipping_account_id), 10_000); // ori 10_500
+		assert_eq!(Balances::free_balance(tipping_account_id), 500);
+		assert_eq!(Balances::reserved_balance(tipping_account_id), 9_500);
 
-		assert_eq!(Tipping::withdrawal_balance(b"native".to_vec()), 25);
-		assert_eq!(Tipping::reward_balance((server_id, 0, b"native".to_vec())), 475);
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"native")), 25);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 475);
 
-		let account_info = TipsBalanceInfo::new(&server_id, b"user", b"user_id", b"native");
+		let account_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
 		let net = 9_500; // added &net
 
 		let tips_balance = TipsBalance::new(&account_info, &net); // &amount changed to &net
@@ -103,9 +132,9 @@ fn pay_content_to_escrow_works() {
 		assert_eq!(
 			Tipping::tips_balance_by_reference((
 				&server_id,
-				b"user".to_vec(),
-				b"user_id".to_vec(),
-				b"native".to_vec()
+				bounded(b"user"),
+				bounded(b"user_id"),
+				bounded(b"native")
 			)),
 			Some(tips_balance)
 		);
@@ -135,9 +164,9 @@ fn withdrawal_fee_works() {
 
 		let tips_balance_info = TipsBalanceInfo::new(
 			&server_id,
-			b"unlockable_content",
-			b"unlockable_content_id",
-			b"native",
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
 		);
 
 		assert_ok!(Tipping::pay_content(
@@ -147,10 +176,15 @@ fn withdrawal_fee_works() {
 			tips_balance_info,
 			amount,
 			None,
+			None,
 		));
 
-		let tips_balance_info =
-			TipsBalanceInfo::new(&server_id, b"unlockable_content", b"unlockable_content_id", b"1");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"1"),
+		);
 
 		assert_ok!(Tipping::pay_content(
 			RuntimeOrigin::signed(sender_2),
@@ -159,19 +193,44 @@ fn withdrawal_fee_works() {
 			tips_balance_info,
 			amount,
 			None,
+			None,
 		));
 
-		assert_eq!(Tipping::withdrawal_balance(b"native".to_vec()), 25);
-		assert_eq!(Tipping::withdrawal_balance(b"1".to_vec()), 25);
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"native")), 25);
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"1")), 25);
 
-		let receiver = account_key("satoshi");
+		let receiver = account_key("treasury");
+
+		assert_noop!(
+			Tipping::withdraw_fee(
+				RuntimeOrigin::signed(account_key("admin")),
+				vec![(bounded(b"native"), 25)].try_into().unwrap()
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Tipping::withdraw_fee(
+				RuntimeOrigin::root(),
+				vec![(bounded(b"native"), 26)].try_into().unwrap()
+			),
+			Error::<Test>::InsufficientBalance,
+		);
 
-		assert_ok!(Tipping::withdraw_fee(RuntimeOrigin::root(), receiver));
-		assert_eq!(Tipping::withdrawal_balance(b"native".to_vec()), 0);
-		assert_eq!(Tipping::withdrawal_balance(b"1".to_vec()), 0);
+		assert_ok!(Tipping::withdraw_fee(
+			RuntimeOrigin::root(),
+			vec![(bounded(b"native"), 10), (bounded(b"1"), 25)].try_into().unwrap()
+		));
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"native")), 15);
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"1")), 0);
+
+		assert_ok!(Tipping::withdraw_fee(
+			RuntimeOrigin::root(),
+			vec![(bounded(b"native"), 15)].try_into().unwrap()
+		));
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"native")), 0);
 
-		assert_eq!(Balances::free_balance(receiver), 65);
-		assert_eq!(Assets::balance(1, receiver), 65);
+		assert_eq!(Balances::free_balance(receiver), 25);
+		assert_eq!(Assets::balance(1, receiver), 25);
 	})
 }
 
@@ -198,9 +257,9 @@ fn withdrawal_reward_works() {
 
 		let tips_balance_info = TipsBalanceInfo::new(
 			&server_id,
-			b"unlockable_content",
-			b"unlockable_content_id",
-			b"native",
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
 		);
 
 		assert_ok!(Tipping::pay_content(
@@ -210,10 +269,15 @@ fn withdrawal_reward_works() {
 			tips_balance_info,
 			amount,
 			None,
+			None,
 		));
 
-		let tips_balance_info =
-			TipsBalanceInfo::new(&server_id, b"unlockable_content", b"unlockable_content_id", b"1");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"1"),
+		);
 
 		assert_ok!(Tipping::pay_content(
 			RuntimeOrigin::signed(sender_2),
@@ -222,15 +286,16 @@ fn withdrawal_reward_works() {
 			tips_balance_info,
 			amount,
 			None,
+			None,
 		));
 
-		assert_eq!(Tipping::reward_balance((server_id, 0, b"native".to_vec())), 475);
-		assert_eq!(Tipping::reward_balance((server_id, 0, b"1".to_vec())), 475);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 475);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"1"))), 475);
 
 		assert_ok!(Tipping::withdraw_reward(RuntimeOrigin::signed(server_id), 0));
 
-		assert_eq!(Tipping::reward_balance((server_id, 0, b"native".to_vec())), 0);
-		assert_eq!(Tipping::reward_balance((server_id, 0, b"1".to_vec())), 0);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 0);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"1"))), 0);
 
 		assert_eq!(Balances::free_balance(server_id), 485);
 		assert_eq!(Assets::balance(1, server_id), 485);
@@ -241,27 +306,36 @@ fn withdrawal_reward_works() {
 fn send_tip_myria_works() {
 	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
 		let server_id = account_key("alice");
-		let tips_balance_info =
-			TipsBalanceInfo::new(&server_id, b"people", b"people_id", b"native");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"native"),
+		);
 		let tips_balance = TipsBalance::new(&tips_balance_info, &1);
 
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			tips_balance_info,
-			1
+			1,
+			None,
+			None
 		));
 
 		assert_eq!(
 			Tipping::tips_balance_by_reference((
 				&server_id,
-				b"people".to_vec(),
-				b"people_id".to_vec(),
-				b"native".to_vec()
+				bounded(b"people"),
+				bounded(b"people_id"),
+				bounded(b"native")
 			)),
 			Some(tips_balance)
 		);
 
+		// The tip stays reserved on the tipper until the reference is claimed
 		assert_eq!(Balances::free_balance(account_key("bob")), 19);
+		assert_eq!(Balances::reserved_balance(account_key("bob")), 1);
 	})
 }
 
@@ -269,26 +343,36 @@ fn send_tip_myria_works() {
 fn send_tip_assets_works() {
 	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
 		let server_id = account_key("alice");
-		let tips_balance_info = TipsBalanceInfo::new(&server_id, b"people", b"people_id", b"1");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
 		let tips_balance = TipsBalance::new(&tips_balance_info, &1);
 
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			tips_balance_info,
-			1
+			1,
+			None,
+			None
 		));
 
 		assert_eq!(
 			Tipping::tips_balance_by_reference((
 				&server_id,
-				b"people".to_vec(),
-				b"people_id".to_vec(),
-				"1".as_bytes().to_vec()
+				bounded(b"people"),
+				bounded(b"people_id"),
+				bounded(b"1")
 			)),
 			Some(tips_balance)
 		);
 
-		assert_eq!(Assets::balance(1, account_key("bob")), 19);
+		// The tip stays frozen on the tipper until the reference is claimed
+		assert_eq!(Assets::balance(1, account_key("bob")), 20);
+		assert_eq!(Tipping::frozen_assets(1, account_key("bob")), 1);
 	})
 }
 
@@ -296,17 +380,45 @@ fn send_tip_assets_works() {
 fn claim_reference_works() {
 	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
 		let server_id = account_key("alice");
-		let tips_balance_info_0 =
-			TipsBalanceInfo::new(&server_id, b"people", b"people_id", b"native");
-		let tips_balance_info_1 = TipsBalanceInfo::new(&server_id, b"people", b"people_id", b"1");
-		let tips_balance_info_2 = TipsBalanceInfo::new(&server_id, b"people", b"people_id", b"2");
+		let tips_balance_info_0 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"native"),
+		);
+		let tips_balance_info_1 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+		let tips_balance_info_2 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"2"),
+		);
 
-		let main_tips_balance_info_0 =
-			TipsBalanceInfo::new(&server_id, b"user", b"user_id", b"native");
+		let main_tips_balance_info_0 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
 
-		let main_tips_balance_info_1 = TipsBalanceInfo::new(&server_id, b"user", b"user_id", b"1");
+		let main_tips_balance_info_1 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"1"),
+		);
 
-		let main_tips_balance_info_2 = TipsBalanceInfo::new(&server_id, b"user", b"user_id", b"2");
+		let main_tips_balance_info_2 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"2"),
+		);
 
 		let mut main_tips_balance_0 = TipsBalance::new(&main_tips_balance_info_0, &1);
 		let mut main_tips_balance_1 = TipsBalance::new(&main_tips_balance_info_1, &1);
@@ -314,32 +426,45 @@ fn claim_reference_works() {
 
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			tips_balance_info_0,
-			1
+			1,
+			None,
+			None
 		));
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			tips_balance_info_1,
-			1
+			1,
+			None,
+			None
 		));
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			tips_balance_info_2,
-			2
+			2,
+			None,
+			None
 		));
 
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			main_tips_balance_info_0,
-			1
+			1,
+			None,
+			None
 		));
 
 		assert_ok!(Tipping::claim_reference(
 			RuntimeOrigin::signed(account_key("alice")),
 			server_id,
-			References::new(b"people", &[b"people_id".to_vec()]),
-			References::new(b"user", &[b"user_id".to_vec()]),
-			vec![b"native".to_vec(), b"1".to_vec(), b"2".to_vec()],
+			0,
+			references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+			references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+			vec![bounded(b"native"), bounded(b"1"), bounded(b"2")],
 			account_key("john"),
 			1,
 		));
@@ -351,9 +476,9 @@ fn claim_reference_works() {
 		assert_eq!(
 			Tipping::tips_balance_by_reference((
 				server_id,
-				b"people".to_vec(),
-				b"people_id".to_vec(),
-				b"native".to_vec()
+				bounded(b"people"),
+				bounded(b"people_id"),
+				bounded(b"native")
 			)),
 			None,
 		);
@@ -361,9 +486,9 @@ fn claim_reference_works() {
 		assert_eq!(
 			Tipping::tips_balance_by_reference((
 				server_id,
-				b"people".to_vec(),
-				b"people_id".to_vec(),
-				b"1".to_vec()
+				bounded(b"people"),
+				bounded(b"people_id"),
+				bounded(b"1")
 			)),
 			None,
 		);
@@ -371,9 +496,9 @@ fn claim_reference_works() {
 		assert_eq!(
 			Tipping::tips_balance_by_reference((
 				server_id,
-				b"people".to_vec(),
-				b"people_id".to_vec(),
-				b"2".to_vec()
+				bounded(b"people"),
+				bounded(b"people_id"),
+				bounded(b"2")
 			)),
 			None,
 		);
@@ -381,9 +506,9 @@ fn claim_reference_works() {
 		assert_eq!(
 			Tipping::tips_balance_by_reference((
 				server_id,
-				b"user".to_vec(),
-				b"user_id".to_vec(),
-				b"native".to_vec()
+				bounded(b"user"),
+				bounded(b"user_id"),
+				bounded(b"native")
 			)),
 			Some(main_tips_balance_0.clone())
 		);
@@ -391,9 +516,9 @@ fn claim_reference_works() {
 		assert_eq!(
 			Tipping::tips_balance_by_reference((
 				server_id,
-				b"user".to_vec(),
-				b"user_id".to_vec(),
-				b"1".to_vec()
+				bounded(b"user"),
+				bounded(b"user_id"),
+				bounded(b"1")
 			)),
 			Some(main_tips_balance_1.clone())
 		);
@@ -401,9 +526,9 @@ fn claim_reference_works() {
 		assert_eq!(
 			Tipping::tips_balance_by_reference((
 				server_id,
-				b"user".to_vec(),
-				b"user_id".to_vec(),
-				b"2".to_vec()
+				bounded(b"user"),
+				bounded(b"user_id"),
+				bounded(b"2")
 			)),
 			Some(main_tips_balance_2.clone())
 		);
@@ -431,41 +556,72 @@ fn claim_tip_works() {
 		));
 
 		let server_id = account_key("alice");
-		let tips_balance_info_0 =
-			TipsBalanceInfo::new(&server_id, b"people", b"people_id", b"native");
-		let tips_balance_info_1 = TipsBalanceInfo::new(&server_id, b"people", b"people_id", b"1");
-		let tips_balance_info_2 = TipsBalanceInfo::new(&server_id, b"people", b"people_id", b"2");
+		let tips_balance_info_0 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"native"),
+		);
+		let tips_balance_info_1 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+		let tips_balance_info_2 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"2"),
+		);
 
-		let main_tips_balance_info_0 =
-			TipsBalanceInfo::new(&server_id, b"user", b"user_id", b"native");
+		let main_tips_balance_info_0 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
 
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			tips_balance_info_0,
-			1
+			1,
+			None,
+			None
 		));
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			tips_balance_info_1,
-			1
+			1,
+			None,
+			None
 		));
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			tips_balance_info_2,
-			2
+			2,
+			None,
+			None
 		));
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			main_tips_balance_info_0,
-			1
+			1,
+			None,
+			None
 		));
 
 		assert_ok!(Tipping::claim_reference(
 			RuntimeOrigin::signed(account_key("alice")),
 			server_id,
-			References::new(b"people", &[b"people_id".to_vec()]),
-			References::new(b"user", &[b"user_id".to_vec()]),
-			vec![b"native".to_vec(), b"1".to_vec(), b"2".to_vec()],
+			0,
+			references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+			references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+			vec![bounded(b"native"), bounded(b"1"), bounded(b"2")],
 			account_key("john"),
 			1,
 		));
@@ -473,9 +629,9 @@ fn claim_tip_works() {
 		assert_ok!(Tipping::claim_tip(
 			RuntimeOrigin::signed(account_key("john")),
 			server_id,
-			b"user".to_vec(),
-			b"user_id".to_vec(),
-			vec![b"native".to_vec(), b"1".to_vec(), b"2".to_vec()]
+			bounded(b"user"),
+			bounded(b"user_id"),
+			vec![bounded(b"native"), bounded(b"1"), bounded(b"2")]
 		));
 
 		assert_eq!(Balances::free_balance(account_key("john")), 31);
@@ -495,9 +651,9 @@ fn cant_pay_content_when_insufficient_balance() {
 
 		let tips_balance_info = TipsBalanceInfo::new(
 			&server_id,
-			b"unlockable_content",
-			b"unlockable_content_id",
-			b"native",
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
 		);
 
 		assert_noop!(
@@ -508,12 +664,17 @@ fn cant_pay_content_when_insufficient_balance() {
 				tips_balance_info,
 				amount,
 				None,
+				None,
 			),
 			Error::<Test>::InsufficientBalance
 		);
 
-		let tips_balance_info =
-			TipsBalanceInfo::new(&server_id, b"unlockable_content", b"unlockable_content_id", b"1");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"1"),
+		);
 
 		assert_noop!(
 			Tipping::pay_content(
@@ -523,6 +684,7 @@ fn cant_pay_content_when_insufficient_balance() {
 				tips_balance_info,
 				amount,
 				None,
+				None,
 			),
 			Error::<Test>::InsufficientBalance
 		);
@@ -538,9 +700,10 @@ fn cant_claim_reference() {
 			Tipping::claim_reference(
 				RuntimeOrigin::signed(account_key("alice")),
 				account_key("bob"),
-				References::new(b"people", &[b"people_id".to_vec()]),
-				References::new(b"user", &[b"user_id".to_vec()]),
-				vec![b"native".to_vec()],
+				0,
+				references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+				references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+				vec![bounded(b"native")],
 				account_key("john"),
 				1,
 			),
@@ -551,9 +714,10 @@ fn cant_claim_reference() {
 			Tipping::claim_reference(
 				RuntimeOrigin::signed(account_key("alice")),
 				server_id,
-				References::new(b"people", &[b"people_id".to_vec()]),
-				References::new(b"user", &[b"user_id".to_vec()]),
-				vec![b"native".to_vec()],
+				0,
+				references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+				references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+				vec![bounded(b"native")],
 				account_key("alice"),
 				1,
 			),
@@ -564,9 +728,10 @@ fn cant_claim_reference() {
 			Tipping::claim_reference(
 				RuntimeOrigin::signed(account_key("alice")),
 				server_id,
-				References::new(b"people", &[b"people_id".to_vec()]),
-				References::new(b"user", &[b"user_id".to_vec()]),
-				vec![b"native".to_vec()],
+				0,
+				references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+				references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+				vec![bounded(b"native")],
 				account_key("john"),
 				0,
 			),
@@ -577,9 +742,10 @@ fn cant_claim_reference() {
 			Tipping::claim_reference(
 				RuntimeOrigin::signed(account_key("alice")),
 				server_id,
-				References::new(b"people", &[b"people_id".to_vec()]),
-				References::new(b"user", &[b"user_id".to_vec(), b"user_idd".to_vec()]),
-				vec![b"native".to_vec()],
+				0,
+				references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+				references_of(&bounded(b"user"), &[bounded(b"user_id"), bounded(b"user_idd")]),
+				vec![bounded(b"native")],
 				account_key("john"),
 				1,
 			),
@@ -590,31 +756,40 @@ fn cant_claim_reference() {
 			Tipping::claim_reference(
 				RuntimeOrigin::signed(account_key("alice")),
 				server_id,
-				References::new(b"people", &[b"people_id".to_vec()]),
-				References::new(b"user", &[b"user_id".to_vec()]),
-				vec![b"native".to_vec()],
+				0,
+				references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+				references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+				vec![bounded(b"native")],
 				account_key("john"),
 				1,
 			),
 			Error::<Test>::NotExists,
 		);
 
-		let main_tips_balance_info =
-			TipsBalanceInfo::new(&server_id, b"user", b"user_id", b"native");
+		let main_tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
 
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			main_tips_balance_info.clone(),
 			0,
+			None,
+			None,
 		));
 
 		assert_noop!(
 			Tipping::claim_reference(
 				RuntimeOrigin::signed(account_key("alice")),
 				server_id,
-				References::new(b"people", &[b"people_id".to_vec()]),
-				References::new(b"user", &[b"user_id".to_vec()]),
-				vec![b"native".to_vec()],
+				0,
+				references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+				references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+				vec![bounded(b"native")],
 				account_key("john"),
 				1,
 			),
@@ -623,17 +798,21 @@ fn cant_claim_reference() {
 
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			main_tips_balance_info,
 			1,
+			None,
+			None,
 		));
 
 		assert_noop!(
 			Tipping::claim_reference(
 				RuntimeOrigin::signed(account_key("alice")),
 				server_id,
-				References::new(b"people", &[b"people_id".to_vec()]),
-				References::new(b"user", &[b"user_id".to_vec()]),
-				vec![b"native".to_vec()],
+				0,
+				references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+				references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+				vec![bounded(b"native")],
 				account_key("john"),
 				2,
 			),
@@ -642,6 +821,16 @@ fn cant_claim_reference() {
 	})
 }
 
+#[test]
+fn cant_claim_more_reference_ids_than_bound() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let reference_ids = (0..11u8).map(|id| bounded(&[id])).collect::<Vec<_>>();
+
+		assert!(ReferencesOf::<Test>::new(&bounded(b"user"), &reference_ids).is_none());
+		assert!(ReferencesOf::<Test>::new(&bounded(b"user"), &reference_ids[..10]).is_some());
+	})
+}
+
 #[test]
 fn call_event_should_work() {
 	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
@@ -653,9 +842,9 @@ fn call_event_should_work() {
 
 		let tips_balance_info = TipsBalanceInfo::new(
 			&server_id,
-			b"unlockable_content",
-			b"unlockable_content_id",
-			b"native",
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
 		);
 
 		assert_ok!(Tipping::pay_content(
@@ -665,25 +854,40 @@ fn call_event_should_work() {
 			tips_balance_info.clone(),
 			amount,
 			None,
+			None,
 		));
 
-		let receipt = Receipt::new(&sender, &Some(receiver), &tips_balance_info, &amount, &500, 0);
+		let receipt_id = Tipping::receipt_id(&sender, &tips_balance_info, &amount, 0);
+		let receipt = Receipt::new(
+			receipt_id,
+			&sender,
+			&Some(receiver),
+			&tips_balance_info,
+			&amount,
+			&500,
+			0,
+			ReceiptStatus::Paid,
+		);
 
 		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::PayUnlockableContent {
 			from: sender,
 			to: Some(receiver),
 			receipt,
+			memo: None,
 		}));
 
 		// Withdraw Fee Event
-		assert_ok!(Tipping::withdraw_fee(RuntimeOrigin::root(), receiver));
+		assert_ok!(Tipping::withdraw_fee(
+			RuntimeOrigin::root(),
+			vec![(bounded(b"native"), 25)].try_into().unwrap()
+		));
 
 		let sender = Tipping::tipping_account_id();
 
 		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::Withdrawal {
 			from: sender,
-			to: receiver,
-			success: vec![(b"native".to_vec(), 25)],
+			to: account_key("treasury"),
+			success: vec![(bounded(b"native"), 25)],
 			failed: Vec::new(),
 		}));
 
@@ -693,20 +897,27 @@ fn call_event_should_work() {
 		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::Withdrawal {
 			from: sender,
 			to: server_id,
-			success: vec![(b"native".to_vec(), 475)],
+			success: vec![(bounded(b"native"), 475)],
 			failed: Vec::new(),
 		}));
 
 		// SendTip Event
 		let server_id = account_key("alice");
-		let tips_balance_info =
-			TipsBalanceInfo::new(&server_id, b"people", b"people_id", b"native");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"native"),
+		);
 		let tips_balance = TipsBalance::new(&tips_balance_info, &1);
 
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			tips_balance_info,
-			1
+			1,
+			None,
+			None
 		));
 
 		let tipping_account_id = Tipping::tipping_account_id();
@@ -715,24 +926,34 @@ fn call_event_should_work() {
 			from: account_key("bob"),
 			to: tipping_account_id,
 			tips_balance,
+			fee_detail: FeeDetail::new(0, 0, 0),
+			memo: None,
 		}));
 
 		// ClaimReference Event
-		let main_tips_balance_info =
-			TipsBalanceInfo::new(&server_id, b"user", b"user_id", b"native");
+		let main_tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
 
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			main_tips_balance_info.clone(),
-			1
+			1,
+			None,
+			None
 		));
 
 		assert_ok!(Tipping::claim_reference(
 			RuntimeOrigin::signed(account_key("alice")),
 			server_id,
-			References::new(b"people", &[b"people_id".to_vec()]),
-			References::new(b"user", &[b"user_id".to_vec()]),
-			vec![b"native".to_vec()],
+			0,
+			references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+			references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+			vec![bounded(b"native")],
 			account_key("john"),
 			1,
 		));
@@ -746,28 +967,3366 @@ fn call_event_should_work() {
 		])));
 
 		// ClaimTip Event
-		let main_tips_balance_info =
-			TipsBalanceInfo::new(&server_id, b"user", b"user_id", b"native");
+		let main_tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
 
 		assert_ok!(Tipping::send_tip(
 			RuntimeOrigin::signed(account_key("bob")),
+			0,
 			main_tips_balance_info,
-			1
+			1,
+			None,
+			None
 		));
 
 		assert_ok!(Tipping::claim_tip(
 			RuntimeOrigin::signed(account_key("john")),
 			server_id,
-			b"user".to_vec(),
-			b"user_id".to_vec(),
-			vec![b"native".to_vec()],
+			bounded(b"user"),
+			bounded(b"user_id"),
+			vec![bounded(b"native")],
 		));
 
 		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::ClaimTip {
 			from: tipping_account_id,
 			to: account_key("john"),
-			success: vec![(b"native".to_vec(), 2)],
+			success: vec![(bounded(b"native"), 2)],
 			failed: Vec::new(),
 		}));
 	})
 }
+
+#[test]
+fn get_tips_balances_works() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let tips_balance_info_0 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"native"),
+		);
+		let tips_balance_info_1 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+		let other_tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"other_people_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			tips_balance_info_0.clone(),
+			1,
+			None,
+			None
+		));
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			tips_balance_info_1.clone(),
+			2,
+			None,
+			None
+		));
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			other_tips_balance_info,
+			3,
+			None,
+			None
+		));
+
+		let mut tips_balances =
+			Tipping::get_tips_balances(&server_id, &bounded(b"people"), &bounded(b"people_id"));
+
+		tips_balances.sort_by(|a, b| a.get_ft_identifier().cmp(b.get_ft_identifier()));
+
+		assert_eq!(
+			tips_balances,
+			vec![
+				TipsBalance::new(&tips_balance_info_1, &2),
+				TipsBalance::new(&tips_balance_info_0, &1),
+			]
+		);
+	})
+}
+
+#[test]
+fn get_claimable_tips_works() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let tips_balance_info_0 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"native"),
+		);
+		let tips_balance_info_1 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+		let main_tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			tips_balance_info_0,
+			3,
+			None,
+			None
+		));
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			tips_balance_info_1,
+			2,
+			None,
+			None
+		));
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			main_tips_balance_info,
+			1,
+			None,
+			None
+		));
+
+		assert_eq!(Tipping::get_claimable_tips(&server_id, &account_key("john")), Vec::new());
+
+		assert_ok!(Tipping::claim_reference(
+			RuntimeOrigin::signed(account_key("alice")),
+			server_id,
+			0,
+			references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+			references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+			vec![bounded(b"native"), bounded(b"1")],
+			account_key("john"),
+			1,
+		));
+
+		assert_eq!(
+			Tipping::get_claimable_tips(&server_id, &account_key("john")),
+			vec![(bounded(b"1"), 2), (bounded(b"native"), 3)]
+		);
+		assert_eq!(
+			Tipping::get_claimable_tips(&account_key("bob"), &account_key("john")),
+			Vec::new()
+		);
+	})
+}
+
+#[test]
+fn cant_tip_unregistered_server() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("bob");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"native"),
+		);
+
+		assert_noop!(
+			Tipping::send_tip(
+				RuntimeOrigin::signed(account_key("john")),
+				0,
+				tips_balance_info,
+				1,
+				None,
+				None
+			),
+			Error::<Test>::ServerNotRegister,
+		);
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
+		);
+
+		assert_noop!(
+			Tipping::pay_content(
+				RuntimeOrigin::signed(account_key("sender_1")),
+				Some(account_key("john")),
+				0,
+				tips_balance_info,
+				10_000,
+				None,
+				None,
+			),
+			Error::<Test>::ServerNotRegister,
+		);
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&account_key("alice"),
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
+		);
+
+		assert_noop!(
+			Tipping::pay_content(
+				RuntimeOrigin::signed(account_key("sender_1")),
+				Some(account_key("john")),
+				1,
+				tips_balance_info,
+				10_000,
+				None,
+				None,
+			),
+			Error::<Test>::ServerNotRegister,
+		);
+
+		// The instance has to belong to the tipped server
+		let tips_balance_info = TipsBalanceInfo::new(
+			&account_key("alice"),
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"native"),
+		);
+
+		assert_noop!(
+			Tipping::send_tip(
+				RuntimeOrigin::signed(account_key("john")),
+				1,
+				tips_balance_info.clone(),
+				1,
+				None,
+				None
+			),
+			Error::<Test>::ServerNotRegister,
+		);
+		assert_noop!(
+			Tipping::send_tips(
+				RuntimeOrigin::signed(account_key("john")),
+				vec![(1, tips_balance_info, 1)].try_into().unwrap()
+			),
+			Error::<Test>::ServerNotRegister,
+		);
+		assert_noop!(
+			Tipping::claim_reference(
+				RuntimeOrigin::signed(account_key("alice")),
+				account_key("alice"),
+				1,
+				references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+				references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+				vec![bounded(b"native")],
+				account_key("john"),
+				0,
+			),
+			Error::<Test>::ServerNotRegister,
+		);
+	})
+}
+
+#[test]
+fn cant_claim_reference_when_server_unregistering() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let main_tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			main_tips_balance_info.clone(),
+			2,
+			None,
+			None,
+		));
+
+		assert_ok!(Server::unregister(RuntimeOrigin::signed(server_id), 0));
+
+		assert_noop!(
+			Tipping::send_tip(
+				RuntimeOrigin::signed(account_key("bob")),
+				0,
+				main_tips_balance_info,
+				1,
+				None,
+				None
+			),
+			Error::<Test>::ServerNotRegister,
+		);
+
+		assert_noop!(
+			Tipping::claim_reference(
+				RuntimeOrigin::signed(server_id),
+				server_id,
+				0,
+				references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+				references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+				vec![bounded(b"native")],
+				account_key("john"),
+				1,
+			),
+			Error::<Test>::ServerNotRegister,
+		);
+	})
+}
+
+#[test]
+fn send_tips_works() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("bob");
+		let tipping_account_id = Tipping::tipping_account_id();
+
+		let native_info_1 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id_1"),
+			&bounded(b"native"),
+		);
+		let native_info_2 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id_2"),
+			&bounded(b"native"),
+		);
+		let asset_info_1 = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id_1"),
+			&bounded(b"1"),
+		);
+
+		let tips = vec![
+			(0, native_info_1.clone(), 1),
+			(0, native_info_2.clone(), 2),
+			(0, asset_info_1.clone(), 3),
+			(0, native_info_1.clone(), 1),
+		];
+
+		assert_ok!(Tipping::send_tips(RuntimeOrigin::signed(sender), tips.try_into().unwrap()));
+
+		assert_eq!(Balances::free_balance(sender), 16);
+		assert_eq!(Balances::reserved_balance(sender), 4);
+		assert_eq!(Balances::reserved_balance(tipping_account_id), 0);
+		assert_eq!(Assets::balance(1, sender), 20);
+		assert_eq!(Tipping::frozen_assets(1, sender), 3);
+		assert_eq!(Assets::balance(1, tipping_account_id), 1);
+
+		assert_eq!(
+			Tipping::tips_balance_by_reference(native_info_1.key()),
+			Some(TipsBalance::new(&native_info_1, &2))
+		);
+		assert_eq!(
+			Tipping::tips_balance_by_reference(native_info_2.key()),
+			Some(TipsBalance::new(&native_info_2, &2))
+		);
+		assert_eq!(
+			Tipping::tips_balance_by_reference(asset_info_1.key()),
+			Some(TipsBalance::new(&asset_info_1, &3))
+		);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::SendTips {
+			from: sender,
+			to: tipping_account_id,
+			tips_balances: vec![
+				TipsBalance::new(&native_info_1, &1),
+				TipsBalance::new(&native_info_2, &2),
+				TipsBalance::new(&asset_info_1, &3),
+				TipsBalance::new(&native_info_1, &1),
+			],
+			total: vec![(bounded(b"1"), 3), (bounded(b"native"), 4)],
+		}));
+	})
+}
+
+#[test]
+fn cant_send_tips() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("bob");
+
+		assert_noop!(
+			Tipping::send_tips(RuntimeOrigin::signed(sender), Default::default()),
+			Error::<Test>::EmptyTips,
+		);
+
+		let content_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"content_id"),
+			&bounded(b"native"),
+		);
+
+		assert_noop!(
+			Tipping::send_tips(
+				RuntimeOrigin::signed(sender),
+				vec![(0, content_info, 1)].try_into().unwrap()
+			),
+			Error::<Test>::Unauthorized,
+		);
+
+		// The asset tip is transferred before the native tip fails, the whole batch reverts
+		let asset_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+		let native_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"native"),
+		);
+		let call = RuntimeCall::Tipping(crate::Call::send_tips {
+			tips: vec![(0, asset_info.clone(), 5), (0, native_info.clone(), 100)]
+				.try_into()
+				.unwrap(),
+		});
+
+		assert!(call.dispatch(RuntimeOrigin::signed(sender)).is_err());
+
+		assert_eq!(Balances::free_balance(sender), 20);
+		assert_eq!(Assets::balance(1, sender), 20);
+		assert_eq!(Tipping::tips_balance_by_reference(asset_info.key()), None);
+		assert_eq!(Tipping::tips_balance_by_reference(native_info.key()), None);
+	})
+}
+
+#[test]
+fn subscribe_works() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let subscriber = account_key("bob");
+		let tipping_account_id = Tipping::tipping_account_id();
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::subscribe(
+			RuntimeOrigin::signed(subscriber),
+			0,
+			tips_balance_info.clone(),
+			2,
+			10
+		));
+
+		let subscription = Subscription::new(0, 0, &subscriber, &tips_balance_info, &2, 10, 11);
+
+		assert_eq!(Tipping::subscription_index(), 1);
+		assert_eq!(Tipping::subscription_by_id(0), Some(subscription.clone()));
+		assert_eq!(Tipping::subscription_tasks(11), vec![0]);
+		assert_eq!(
+			Tipping::tips_balance_by_reference(tips_balance_info.key()),
+			Some(TipsBalance::new(&tips_balance_info, &2))
+		);
+		assert_eq!(Balances::free_balance(subscriber), 18);
+		assert_eq!(Balances::reserved_balance(subscriber), 2);
+		assert_eq!(Balances::reserved_balance(tipping_account_id), 0);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::Subscribed { subscription }));
+	})
+}
+
+#[test]
+fn subscription_charged_on_initialize_works() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let subscriber = account_key("bob");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::subscribe(
+			RuntimeOrigin::signed(subscriber),
+			0,
+			tips_balance_info.clone(),
+			2,
+			10
+		));
+
+		System::set_block_number(11);
+		Tipping::on_initialize(11);
+
+		assert_eq!(Balances::free_balance(subscriber), 16);
+		assert_eq!(Tipping::subscription_tasks(11), Vec::<u64>::new());
+		assert_eq!(Tipping::subscription_tasks(21), vec![0]);
+		assert_eq!(Tipping::subscription_by_id(0).unwrap().get_next_payment_at(), Some(21));
+		assert_eq!(
+			Tipping::tips_balance_by_reference(tips_balance_info.key()),
+			Some(TipsBalance::new(&tips_balance_info, &4))
+		);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::SubscriptionCharged {
+			subscription_id: 0,
+			tips_balance: TipsBalance::new(&tips_balance_info, &2),
+			next_payment_at: 21,
+		}));
+	})
+}
+
+#[test]
+fn subscription_suspended_when_charge_fails() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let subscriber = account_key("bob");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::subscribe(
+			RuntimeOrigin::signed(subscriber),
+			0,
+			tips_balance_info.clone(),
+			9,
+			10
+		));
+
+		System::set_block_number(11);
+		Tipping::on_initialize(11);
+
+		assert_eq!(Balances::free_balance(subscriber), 2);
+
+		System::set_block_number(21);
+		Tipping::on_initialize(21);
+
+		let subscription = Tipping::subscription_by_id(0).unwrap();
+
+		assert_eq!(subscription.get_status(), SubscriptionStatus::Suspended);
+		assert_eq!(subscription.get_next_payment_at(), None);
+		assert_eq!(Tipping::subscription_tasks(31), Vec::<u64>::new());
+		assert_eq!(Balances::free_balance(subscriber), 2);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::SubscriptionSuspended {
+			subscription_id: 0,
+			when: 21,
+		}));
+
+		// Suspended subscription can be resumed
+		assert_ok!(Tipping::resume_subscription(RuntimeOrigin::signed(subscriber), 0));
+		assert_eq!(Tipping::subscription_tasks(31), vec![0]);
+	})
+}
+
+#[test]
+fn pause_resume_and_cancel_subscription_works() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let subscriber = account_key("bob");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::subscribe(
+			RuntimeOrigin::signed(subscriber),
+			0,
+			tips_balance_info,
+			2,
+			10
+		));
+
+		// Pause
+		assert_ok!(Tipping::pause_subscription(RuntimeOrigin::signed(subscriber), 0));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::SubscriptionPaused {
+			subscription_id: 0,
+		}));
+
+		assert_eq!(Tipping::subscription_tasks(11), Vec::<u64>::new());
+
+		System::set_block_number(11);
+		Tipping::on_initialize(11);
+
+		assert_eq!(Balances::free_balance(subscriber), 18);
+
+		// Resume
+		System::set_block_number(15);
+
+		assert_ok!(Tipping::resume_subscription(RuntimeOrigin::signed(subscriber), 0));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::SubscriptionResumed {
+			subscription_id: 0,
+			next_payment_at: 25,
+		}));
+
+		assert_eq!(Tipping::subscription_tasks(25), vec![0]);
+
+		// Cancel
+		assert_ok!(Tipping::cancel_subscription(RuntimeOrigin::signed(subscriber), 0));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::SubscriptionCancelled {
+			subscription_id: 0,
+		}));
+
+		assert_eq!(Tipping::subscription_by_id(0), None);
+		assert_eq!(Tipping::subscription_tasks(25), Vec::<u64>::new());
+	})
+}
+
+#[test]
+fn cant_subscribe() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let subscriber = account_key("bob");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
+
+		assert_noop!(
+			Tipping::subscribe(
+				RuntimeOrigin::signed(subscriber),
+				0,
+				tips_balance_info.clone(),
+				2,
+				0
+			),
+			Error::<Test>::InvalidPeriod,
+		);
+
+		assert_noop!(
+			Tipping::subscribe(
+				RuntimeOrigin::signed(subscriber),
+				0,
+				tips_balance_info.clone(),
+				2,
+				101
+			),
+			Error::<Test>::InvalidPeriod,
+		);
+
+		let unregistered_info = TipsBalanceInfo::new(
+			&account_key("bob"),
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
+
+		assert_noop!(
+			Tipping::subscribe(RuntimeOrigin::signed(subscriber), 0, unregistered_info, 2, 10),
+			Error::<Test>::ServerNotRegister,
+		);
+
+		// At most two subscriptions are charged per block
+		assert_ok!(Tipping::subscribe(
+			RuntimeOrigin::signed(subscriber),
+			0,
+			tips_balance_info.clone(),
+			1,
+			10
+		));
+		assert_ok!(Tipping::subscribe(
+			RuntimeOrigin::signed(subscriber),
+			0,
+			tips_balance_info.clone(),
+			1,
+			10
+		));
+		assert_noop!(
+			Tipping::subscribe(RuntimeOrigin::signed(subscriber), 0, tips_balance_info, 1, 10),
+			Error::<Test>::FailedToSchedule,
+		);
+
+		assert_noop!(
+			Tipping::pause_subscription(RuntimeOrigin::signed(account_key("john")), 0),
+			Error::<Test>::Unauthorized,
+		);
+		assert_noop!(
+			Tipping::resume_subscription(RuntimeOrigin::signed(subscriber), 0),
+			Error::<Test>::InvalidStatus,
+		);
+		assert_noop!(
+			Tipping::cancel_subscription(RuntimeOrigin::signed(subscriber), 2),
+			Error::<Test>::NotExists,
+		);
+	})
+}
+
+#[test]
+fn pay_content_escrow_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+		let receiver = account_key("john");
+		let tipping_account_id = Tipping::tipping_account_id();
+		let amount = 10_000;
+		let memo = Memo::new(bounded_memo(b"order #1"), None, false);
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::pay_content_escrow(
+			RuntimeOrigin::signed(sender),
+			receiver,
+			0,
+			tips_balance_info.clone(),
+			amount,
+			Some(memo.clone()),
+		));
+
+		let receipt_id = Tipping::receipt_id(&sender, &tips_balance_info, &amount, 0);
+		let receipt = Receipt::new(
+			receipt_id,
+			&sender,
+			&Some(receiver),
+			&tips_balance_info,
+			&amount,
+			&500,
+			0,
+			ReceiptStatus::Escrowed,
+		);
+
+		// The memo holds a deposit of its 11 encoded bytes plus the base
+		assert_eq!(Balances::free_balance(sender), 9_988);
+		assert_eq!(Balances::reserved_balance(sender), 10_012);
+		assert_eq!(Balances::reserved_balance_named(b"tip/memo", &sender), 12);
+		assert_eq!(Balances::free_balance(receiver), 30);
+		assert_eq!(Balances::reserved_balance(tipping_account_id), 0);
+
+		// Fees are credited on release
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"native")), 0);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 0);
+
+		assert_eq!(Tipping::receipts(receipt_id), Some(receipt.clone()));
+		assert_eq!(Tipping::escrow_tasks(11), vec![receipt_id]);
+		assert_eq!(Tipping::memos(receipt_id), Some(memo.clone()));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::PayUnlockableContent {
+			from: sender,
+			to: Some(receiver),
+			receipt,
+			memo: Some(memo),
+		}));
+	})
+}
+
+#[test]
+fn confirm_escrow_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+		let receiver = account_key("john");
+		let tipping_account_id = Tipping::tipping_account_id();
+		let amount = 10_000;
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::pay_content_escrow(
+			RuntimeOrigin::signed(sender),
+			receiver,
+			0,
+			tips_balance_info.clone(),
+			amount,
+			None,
+		));
+
+		let receipt_id = Tipping::receipt_id(&sender, &tips_balance_info, &amount, 0);
+
+		assert_noop!(
+			Tipping::confirm_escrow(RuntimeOrigin::signed(receiver), receipt_id),
+			Error::<Test>::Unauthorized,
+		);
+
+		assert_ok!(Tipping::confirm_escrow(RuntimeOrigin::signed(sender), receipt_id));
+
+		assert_eq!(Balances::free_balance(receiver), 9_530);
+		assert_eq!(Balances::reserved_balance(sender), 0);
+		assert_eq!(Balances::free_balance(tipping_account_id), 500);
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"native")), 25);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 475);
+
+		assert_eq!(Tipping::receipts(receipt_id).unwrap().get_status(), ReceiptStatus::Released);
+		assert_eq!(Tipping::escrows(receipt_id), None);
+		assert_eq!(Tipping::escrow_tasks(11), Vec::new());
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::EscrowReleased {
+			receipt_id,
+			to: receiver,
+			amount: 9_500,
+		}));
+
+		assert_noop!(
+			Tipping::refund_escrow(RuntimeOrigin::signed(sender), receipt_id),
+			Error::<Test>::InvalidStatus,
+		);
+	})
+}
+
+#[test]
+fn escrow_released_on_initialize_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+		let receiver = account_key("john");
+		let amount = 10_000;
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"1"),
+		);
+
+		assert_ok!(Tipping::pay_content_escrow(
+			RuntimeOrigin::signed(sender),
+			receiver,
+			0,
+			tips_balance_info.clone(),
+			amount,
+			None,
+		));
+
+		let receipt_id = Tipping::receipt_id(&sender, &tips_balance_info, &amount, 0);
+
+		System::set_block_number(11);
+		Tipping::on_initialize(11);
+
+		assert_eq!(Assets::balance(1, receiver), 9_530);
+		assert_eq!(Assets::balance(1, sender), 10_000);
+		assert_eq!(Tipping::frozen_assets(1, sender), 0);
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"1")), 25);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"1"))), 475);
+		assert_eq!(Tipping::receipts(receipt_id).unwrap().get_status(), ReceiptStatus::Released);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::EscrowReleased {
+			receipt_id,
+			to: receiver,
+			amount: 9_500,
+		}));
+	})
+}
+
+#[test]
+fn refund_escrow_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+		let receiver = account_key("john");
+		let tipping_account_id = Tipping::tipping_account_id();
+		let amount = 10_000;
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
+		);
+
+		// Refund by buyer
+		assert_ok!(Tipping::pay_content_escrow(
+			RuntimeOrigin::signed(sender),
+			receiver,
+			0,
+			tips_balance_info.clone(),
+			amount,
+			None,
+		));
+
+		let receipt_id = Tipping::receipt_id(&sender, &tips_balance_info, &amount, 0);
+
+		assert_noop!(
+			Tipping::refund_escrow(RuntimeOrigin::signed(receiver), receipt_id),
+			Error::<Test>::Unauthorized,
+		);
+
+		assert_ok!(Tipping::refund_escrow(RuntimeOrigin::signed(sender), receipt_id));
+
+		assert_eq!(Balances::free_balance(sender), 20_000);
+		assert_eq!(Balances::reserved_balance(sender), 0);
+		assert_eq!(Balances::free_balance(receiver), 30);
+		assert_eq!(Balances::free_balance(tipping_account_id), 0);
+		assert_eq!(Tipping::receipts(receipt_id).unwrap().get_status(), ReceiptStatus::Refunded);
+		assert_eq!(Tipping::escrows(receipt_id), None);
+		assert_eq!(Tipping::escrow_tasks(11), Vec::new());
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::EscrowRefunded {
+			receipt_id,
+			to: sender,
+			amount,
+		}));
+
+		// Refund by server owner
+		assert_ok!(Tipping::pay_content_escrow(
+			RuntimeOrigin::signed(sender),
+			receiver,
+			0,
+			tips_balance_info.clone(),
+			amount,
+			None,
+		));
+
+		let receipt_id = Tipping::receipt_id(&sender, &tips_balance_info, &amount, 1);
+
+		assert_ok!(Tipping::refund_escrow(RuntimeOrigin::signed(server_id), receipt_id));
+
+		assert_eq!(Balances::free_balance(sender), 20_000);
+		assert_eq!(Tipping::receipts(receipt_id).unwrap().get_status(), ReceiptStatus::Refunded);
+
+		// Nothing left to release
+		System::set_block_number(11);
+		Tipping::on_initialize(11);
+
+		assert_eq!(Balances::free_balance(receiver), 30);
+	})
+}
+
+#[test]
+fn has_paid_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+		let receiver = account_key("john");
+		let amount = 10_000;
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
+		);
+
+		assert!(!Tipping::has_paid(&sender, &server_id, &bounded(b"unlockable_content_id")));
+
+		assert_ok!(Tipping::pay_content(
+			RuntimeOrigin::signed(sender),
+			Some(receiver),
+			0,
+			tips_balance_info.clone(),
+			amount,
+			None,
+			None,
+		));
+
+		let receipt_id = Tipping::receipt_id(&sender, &tips_balance_info, &amount, 0);
+
+		assert_eq!(
+			Tipping::receipt_by_content((server_id, bounded(b"unlockable_content_id"), sender)),
+			Some(receipt_id)
+		);
+		assert!(Tipping::has_paid(&sender, &server_id, &bounded(b"unlockable_content_id")));
+		assert!(!Tipping::has_paid(&receiver, &server_id, &bounded(b"unlockable_content_id")));
+
+		// Escrowed content is accessible until refunded
+		let sender = account_key("sender_2");
+
+		assert_ok!(Tipping::pay_content_escrow(
+			RuntimeOrigin::signed(sender),
+			receiver,
+			0,
+			tips_balance_info.clone(),
+			amount,
+			None,
+		));
+
+		let receipt_id = Tipping::receipt_id(&sender, &tips_balance_info, &amount, 1);
+
+		assert!(Tipping::has_paid(&sender, &server_id, &bounded(b"unlockable_content_id")));
+
+		assert_ok!(Tipping::refund_escrow(RuntimeOrigin::signed(sender), receipt_id));
+
+		assert!(!Tipping::has_paid(&sender, &server_id, &bounded(b"unlockable_content_id")));
+		assert_eq!(
+			Tipping::receipt_by_content((server_id, bounded(b"unlockable_content_id"), sender)),
+			None
+		);
+	})
+}
+
+#[test]
+fn set_server_fee_policy_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+		let receiver = account_key("john");
+		let amount = 10_000;
+		let fee_policy = FeePolicy::new(Permill::from_percent(10), Permill::from_percent(20));
+
+		assert_ok!(Tipping::set_server_fee_policy(
+			RuntimeOrigin::signed(server_id),
+			0,
+			Some(fee_policy)
+		));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::ServerFeePolicyUpdated {
+			instance_id: 0,
+			fee_policy: Some(fee_policy),
+		}));
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::pay_content(
+			RuntimeOrigin::signed(sender),
+			Some(receiver),
+			0,
+			tips_balance_info,
+			amount,
+			None,
+			None,
+		));
+
+		assert_eq!(Balances::free_balance(receiver), 9_030);
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"native")), 200);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 800);
+
+		// Reset to the default fee policy
+		assert_ok!(Tipping::set_server_fee_policy(RuntimeOrigin::signed(server_id), 0, None));
+
+		assert_eq!(Tipping::fee_policy_by_server(0), None);
+		assert_eq!(Tipping::fee_policy(0), Tipping::default_fee_policy());
+	})
+}
+
+#[test]
+fn server_fee_policy_is_clamped_to_bounds() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let fee_policy = FeePolicy::new(Permill::from_percent(10), Permill::from_percent(5))
+			.set_tip_fee(Permill::from_percent(10));
+
+		assert_ok!(Tipping::set_server_fee_policy(
+			RuntimeOrigin::signed(server_id),
+			0,
+			Some(fee_policy)
+		));
+
+		// Governance tightens the bounds after the server set its policy
+		let bounds = FeePolicyBounds::new(Permill::from_percent(5), Permill::from_percent(5))
+			.set_max_tip_fee(Permill::from_percent(2));
+
+		assert_ok!(Tipping::set_fee_policy_bounds(RuntimeOrigin::root(), bounds));
+
+		assert_eq!(Tipping::fee_policy_by_server(0), Some(fee_policy));
+		assert_eq!(
+			Tipping::fee_policy(0),
+			FeePolicy::new(Permill::from_percent(5), Permill::from_percent(5))
+				.set_tip_fee(Permill::from_percent(2))
+		);
+	})
+}
+
+#[test]
+fn default_fee_policy_is_exact() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+		let receiver = account_key("john");
+		let fee_policy = FeePolicy::new(Permill::from_percent(3), Permill::from_percent(10));
+
+		assert_ok!(Tipping::set_default_fee_policy(RuntimeOrigin::root(), fee_policy));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::DefaultFeePolicyUpdated(
+			fee_policy,
+		)));
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::pay_content(
+			RuntimeOrigin::signed(sender),
+			Some(receiver),
+			0,
+			tips_balance_info,
+			10_000,
+			None,
+			None,
+		));
+
+		assert_eq!(Balances::free_balance(receiver), 9_730);
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"native")), 30);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 270);
+	})
+}
+
+#[test]
+fn cant_set_fee_policy() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let fee_policy = FeePolicy::new(Permill::from_percent(20), Permill::from_percent(5));
+		let bounds = FeePolicyBounds::new(Permill::from_percent(10), Permill::from_percent(10));
+
+		assert_noop!(
+			Tipping::set_default_fee_policy(RuntimeOrigin::signed(server_id), fee_policy),
+			DispatchError::BadOrigin,
+		);
+
+		assert_noop!(
+			Tipping::set_fee_policy_bounds(RuntimeOrigin::signed(server_id), bounds),
+			DispatchError::BadOrigin,
+		);
+
+		// The default policy only gives the admin 5%
+		assert_noop!(
+			Tipping::set_fee_policy_bounds(RuntimeOrigin::root(), bounds),
+			Error::<Test>::FeePolicyOutOfBounds,
+		);
+
+		assert_noop!(
+			Tipping::set_default_fee_policy(RuntimeOrigin::root(), fee_policy),
+			Error::<Test>::FeePolicyOutOfBounds,
+		);
+
+		let default_fee_policy =
+			FeePolicy::new(Permill::from_percent(5), Permill::from_percent(10));
+
+		assert_ok!(Tipping::set_default_fee_policy(RuntimeOrigin::root(), default_fee_policy));
+		assert_ok!(Tipping::set_fee_policy_bounds(RuntimeOrigin::root(), bounds));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::FeePolicyBoundsUpdated(
+			bounds,
+		)));
+
+		assert_noop!(
+			Tipping::set_server_fee_policy(
+				RuntimeOrigin::signed(account_key("bob")),
+				0,
+				Some(fee_policy)
+			),
+			Error::<Test>::ServerNotRegister,
+		);
+
+		assert_noop!(
+			Tipping::set_server_fee_policy(RuntimeOrigin::signed(server_id), 0, Some(fee_policy)),
+			Error::<Test>::FeePolicyOutOfBounds,
+		);
+
+		let fee_policy = FeePolicy::new(Permill::from_percent(10), Permill::from_percent(5));
+
+		assert_noop!(
+			Tipping::set_server_fee_policy(RuntimeOrigin::signed(server_id), 0, Some(fee_policy)),
+			Error::<Test>::FeePolicyOutOfBounds,
+		);
+	})
+}
+
+#[test]
+fn send_tip_with_fee_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+		let tipping_account_id = Tipping::tipping_account_id();
+		let fee_policy = FeePolicy::new(Permill::from_percent(5), Permill::from_percent(5))
+			.set_tip_fee(Permill::from_percent(10));
+
+		assert_ok!(Tipping::set_default_fee_policy(RuntimeOrigin::root(), fee_policy));
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(sender),
+			0,
+			tips_balance_info.clone(),
+			10_000,
+			None,
+			None
+		));
+
+		let tips_balance = TipsBalance::new(&tips_balance_info, &9_000);
+
+		assert_eq!(Assets::balance(1, sender), 19_000);
+		assert_eq!(Tipping::frozen_assets(1, sender), 9_000);
+		assert_eq!(Assets::balance(1, tipping_account_id), 1_001);
+		assert_eq!(
+			Tipping::tips_balance_by_reference(tips_balance_info.key()),
+			Some(tips_balance.clone())
+		);
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"1")), 50);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"1"))), 950);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::SendTip {
+			from: sender,
+			to: tipping_account_id,
+			tips_balance,
+			fee_detail: FeeDetail::new(50, 950, 1_000),
+			memo: None,
+		}));
+	})
+}
+
+#[test]
+fn cant_set_tip_fee_above_bounds() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let bounds = FeePolicyBounds::new(Permill::from_percent(10), Permill::from_percent(5))
+			.set_max_tip_fee(Permill::from_percent(2));
+		let fee_policy = FeePolicy::new(Permill::from_percent(5), Permill::from_percent(5))
+			.set_tip_fee(Permill::from_percent(3));
+
+		assert_ok!(Tipping::set_fee_policy_bounds(RuntimeOrigin::root(), bounds));
+
+		assert_noop!(
+			Tipping::set_server_fee_policy(RuntimeOrigin::signed(server_id), 0, Some(fee_policy)),
+			Error::<Test>::FeePolicyOutOfBounds,
+		);
+
+		let fee_policy = fee_policy.set_tip_fee(Permill::from_percent(2));
+
+		assert_ok!(Tipping::set_server_fee_policy(
+			RuntimeOrigin::signed(server_id),
+			0,
+			Some(fee_policy)
+		));
+	})
+}
+
+#[test]
+fn register_asset_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+		let ft_identifier = bounded(b"debio");
+		let asset = AssetMetadata::new(AssetKind::Asset(1), 100, 18);
+
+		assert_ok!(Tipping::register_asset(
+			RuntimeOrigin::root(),
+			ft_identifier.clone(),
+			AssetKind::Asset(1),
+			100,
+			18
+		));
+
+		assert_eq!(Tipping::asset_registry(&ft_identifier), Some(asset.clone()));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::AssetRegistered {
+			ft_identifier: ft_identifier.clone(),
+			asset,
+		}));
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"debio"),
+		);
+
+		assert_noop!(
+			Tipping::send_tip(
+				RuntimeOrigin::signed(sender),
+				0,
+				tips_balance_info.clone(),
+				99,
+				None,
+				None
+			),
+			Error::<Test>::BelowMinimumTip,
+		);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(sender),
+			0,
+			tips_balance_info,
+			100,
+			None,
+			None
+		));
+
+		assert_eq!(Assets::balance(1, sender), 19_900);
+	})
+}
+
+#[test]
+fn update_asset_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+		let receiver = account_key("john");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"1"),
+		);
+
+		assert_ok!(Tipping::pay_content(
+			RuntimeOrigin::signed(sender),
+			Some(receiver),
+			0,
+			tips_balance_info.clone(),
+			10_000,
+			None,
+			None,
+		));
+
+		assert_ok!(Tipping::update_asset(RuntimeOrigin::root(), bounded(b"1"), false, 0, 18));
+
+		let asset = AssetMetadata::new(AssetKind::Asset(1), 0, 18).set_enabled(false);
+
+		assert_eq!(Tipping::asset_registry(bounded(b"1")), Some(asset.clone()));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::AssetUpdated {
+			ft_identifier: bounded(b"1"),
+			asset,
+		}));
+
+		assert_noop!(
+			Tipping::pay_content(
+				RuntimeOrigin::signed(sender),
+				Some(receiver),
+				0,
+				tips_balance_info,
+				10_000,
+				None,
+				None,
+			),
+			Error::<Test>::AssetDisabled,
+		);
+
+		// Disabled assets are still paid out
+		assert_ok!(Tipping::withdraw_reward(RuntimeOrigin::signed(server_id), 0));
+
+		assert_eq!(Assets::balance(1, server_id), 485);
+	})
+}
+
+#[test]
+fn cant_register_asset() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+
+		assert_noop!(
+			Tipping::register_asset(
+				RuntimeOrigin::signed(sender),
+				bounded(b"3"),
+				AssetKind::Asset(1),
+				0,
+				18
+			),
+			DispatchError::BadOrigin,
+		);
+
+		assert_noop!(
+			Tipping::register_asset(
+				RuntimeOrigin::root(),
+				bounded(b"1"),
+				AssetKind::Asset(1),
+				0,
+				18
+			),
+			Error::<Test>::AssetAlreadyRegistered,
+		);
+
+		assert_noop!(
+			Tipping::register_asset(
+				RuntimeOrigin::root(),
+				bounded(b"3"),
+				AssetKind::Asset(3),
+				0,
+				18
+			),
+			Error::<Test>::NotExists,
+		);
+
+		assert_noop!(
+			Tipping::update_asset(RuntimeOrigin::root(), bounded(b"3"), true, 0, 18),
+			Error::<Test>::AssetNotRegistered,
+		);
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"3"),
+		);
+
+		assert_noop!(
+			Tipping::send_tip(
+				RuntimeOrigin::signed(sender),
+				0,
+				tips_balance_info,
+				1_000,
+				None,
+				None
+			),
+			Error::<Test>::AssetNotRegistered,
+		);
+	})
+}
+
+#[test]
+fn withdraw_keeps_tipping_account_alive() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+		let receiver = account_key("john");
+		let treasury = account_key("treasury");
+		let tipping_account_id = Tipping::tipping_account_id();
+
+		for ft_identifier in [bounded(b"native"), bounded(b"1")] {
+			let tips_balance_info = TipsBalanceInfo::new(
+				&server_id,
+				&bounded(b"unlockable_content"),
+				&bounded(b"unlockable_content_id"),
+				&ft_identifier,
+			);
+
+			assert_ok!(Tipping::pay_content(
+				RuntimeOrigin::signed(sender),
+				Some(receiver),
+				0,
+				tips_balance_info,
+				10_000,
+				None,
+				None,
+			));
+		}
+
+		assert_eq!(Balances::free_balance(tipping_account_id), 500);
+		assert_eq!(Balances::reserved_balance(tipping_account_id), 0);
+		assert_eq!(Assets::balance(1, tipping_account_id), 501);
+
+		// Every fee is paid out, only the seeded minimum balance stays
+		assert_ok!(Tipping::withdraw_reward(RuntimeOrigin::signed(server_id), 0));
+		assert_ok!(Tipping::withdraw_fee(
+			RuntimeOrigin::root(),
+			vec![(bounded(b"native"), 25), (bounded(b"1"), 25)].try_into().unwrap()
+		));
+
+		assert_eq!(Balances::free_balance(tipping_account_id), 0);
+		assert_eq!(Assets::balance(1, tipping_account_id), 1);
+		assert_eq!(Balances::free_balance(server_id), 486);
+		assert_eq!(Assets::balance(1, server_id), 485);
+		assert_eq!(Assets::balance(1, treasury), 25);
+	})
+}
+
+#[test]
+fn cant_register_unfunded_asset() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let owner = account_key("alice");
+
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), 3, owner, true, 1));
+
+		assert_noop!(
+			Tipping::register_asset(
+				RuntimeOrigin::root(),
+				bounded(b"3"),
+				AssetKind::Asset(3),
+				0,
+				18
+			),
+			Error::<Test>::AssetNotFunded,
+		);
+
+		let tipping_account_id = Tipping::tipping_account_id();
+
+		assert_ok!(Assets::burn(RuntimeOrigin::signed(owner), 1, tipping_account_id, 1));
+
+		assert_noop!(
+			Tipping::update_asset(RuntimeOrigin::root(), bounded(b"1"), true, 0, 18),
+			Error::<Test>::AssetNotFunded,
+		);
+	})
+}
+
+fn sign_claim_proof(signer: &str, proof: &ClaimProofOf<Test>) -> sr25519::Signature {
+	sr25519::Pair::from_string(&format!("//{}", signer), None)
+		.expect("static values are valid; qed")
+		.sign(&proof.signing_payload())
+}
+
+#[test]
+fn claim_reference_with_proof_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let john = account_key("john");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+		let references = references_of(&bounded(b"people"), &[bounded(b"people_id")]);
+		let account_references = references_of(&bounded(b"user"), &[bounded(b"user_id")]);
+
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(server_id),
+			0,
+			ActionType::UpdateSigningKey(Some(account_key("signer")))
+		));
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			tips_balance_info,
+			10,
+			None,
+			None
+		));
+
+		let proof = ClaimProof::new(0, john, references, account_references, None, 0, 10);
+		let signature = sign_claim_proof("signer", &proof);
+
+		assert_ok!(Tipping::claim_reference_with_proof(
+			RuntimeOrigin::signed(john),
+			proof.clone(),
+			signature.clone(),
+			vec![bounded(b"1")],
+		));
+
+		let account_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"1"),
+		);
+		let mut tips_balance = TipsBalance::new(&account_info, &10);
+
+		tips_balance.set_account_id(&john);
+
+		assert_eq!(
+			Tipping::tips_balance_by_reference(account_info.key()),
+			Some(tips_balance.clone())
+		);
+		assert_eq!(Tipping::claim_nonce(0, john), 1);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::ClaimReference(vec![
+			tips_balance,
+		])));
+
+		// The proof can't be replayed
+		assert_noop!(
+			Tipping::claim_reference_with_proof(
+				RuntimeOrigin::signed(john),
+				proof,
+				signature,
+				vec![bounded(b"1")],
+			),
+			Error::<Test>::InvalidNonce,
+		);
+	})
+}
+
+#[test]
+fn claim_reference_with_proof_pays_fee() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let john = account_key("john");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+		let references = references_of(&bounded(b"people"), &[bounded(b"people_id")]);
+		let account_references = references_of(&bounded(b"user"), &[bounded(b"user_id")]);
+
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(server_id),
+			0,
+			ActionType::UpdateSigningKey(Some(account_key("signer")))
+		));
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			tips_balance_info,
+			10,
+			None,
+			None
+		));
+
+		let tx_fee = Some((bounded(b"1"), 11));
+		let proof =
+			ClaimProof::new(0, john, references.clone(), account_references.clone(), tx_fee, 0, 10);
+
+		assert_noop!(
+			Tipping::claim_reference_with_proof(
+				RuntimeOrigin::signed(john),
+				proof.clone(),
+				sign_claim_proof("signer", &proof),
+				vec![bounded(b"1")],
+			),
+			Error::<Test>::InsufficientBalance,
+		);
+
+		let tx_fee = Some((bounded(b"1"), 3));
+		let proof = ClaimProof::new(0, john, references, account_references, tx_fee, 0, 10);
+
+		assert_ok!(Tipping::claim_reference_with_proof(
+			RuntimeOrigin::signed(john),
+			proof.clone(),
+			sign_claim_proof("signer", &proof),
+			vec![bounded(b"1")],
+		));
+
+		let account_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"1"),
+		);
+		let mut tips_balance = TipsBalance::new(&account_info, &7);
+
+		tips_balance.set_account_id(&john);
+
+		assert_eq!(Assets::balance(1, server_id), 13);
+		assert_eq!(
+			Tipping::tips_balance_by_reference(account_info.key()),
+			Some(tips_balance.clone())
+		);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::ClaimReference(vec![
+			tips_balance,
+		])));
+	})
+}
+
+#[test]
+fn cant_claim_reference_with_proof() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let john = account_key("john");
+		let references = references_of(&bounded(b"people"), &[bounded(b"people_id")]);
+		let account_references = references_of(&bounded(b"user"), &[bounded(b"user_id")]);
+		let ft_identifiers = vec![bounded(b"1")];
+
+		let proof =
+			ClaimProof::new(0, john, references.clone(), account_references.clone(), None, 0, 10);
+		let signature = sign_claim_proof("signer", &proof);
+
+		assert_noop!(
+			Tipping::claim_reference_with_proof(
+				RuntimeOrigin::signed(john),
+				proof.clone(),
+				signature.clone(),
+				ft_identifiers.clone(),
+			),
+			Error::<Test>::SigningKeyNotSet,
+		);
+
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(server_id),
+			0,
+			ActionType::UpdateSigningKey(Some(account_key("signer")))
+		));
+
+		assert_noop!(
+			Tipping::claim_reference_with_proof(
+				RuntimeOrigin::signed(account_key("bob")),
+				proof.clone(),
+				signature,
+				ft_identifiers.clone(),
+			),
+			Error::<Test>::Unauthorized,
+		);
+
+		assert_noop!(
+			Tipping::claim_reference_with_proof(
+				RuntimeOrigin::signed(john),
+				proof.clone(),
+				sign_claim_proof("bob", &proof),
+				ft_identifiers.clone(),
+			),
+			Error::<Test>::BadSignature,
+		);
+
+		// The signature must cover the prefixed payload
+		let signature = sr25519::Pair::from_string("//signer", None)
+			.expect("static values are valid; qed")
+			.sign(&proof.encode());
+
+		assert_noop!(
+			Tipping::claim_reference_with_proof(
+				RuntimeOrigin::signed(john),
+				proof.clone(),
+				signature,
+				ft_identifiers.clone(),
+			),
+			Error::<Test>::BadSignature,
+		);
+
+		let proof = ClaimProof::new(0, john, references, account_references, None, 0, 10);
+		let signature = sign_claim_proof("signer", &proof);
+
+		System::set_block_number(11);
+
+		assert_noop!(
+			Tipping::claim_reference_with_proof(
+				RuntimeOrigin::signed(john),
+				proof,
+				signature,
+				ft_identifiers,
+			),
+			Error::<Test>::ProofExpired,
+		);
+	})
+}
+
+#[test]
+fn reclaim_tip_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let tipper = account_key("bob");
+		let tipping_account_id = Tipping::tipping_account_id();
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(tipper),
+			0,
+			tips_balance_info.clone(),
+			5,
+			None,
+			None
+		));
+
+		assert_eq!(Tipping::tip_expiry_at(tips_balance_info.key()), Some(21));
+		assert_eq!(Tipping::tip_expiry_tasks(21), vec![tips_balance_info.key()]);
+		assert_eq!(Assets::balance(1, tipper), 20);
+		assert_eq!(Tipping::frozen_assets(1, tipper), 5);
+		assert_eq!(Assets::balance(1, tipping_account_id), 1);
+
+		System::set_block_number(21);
+
+		assert_ok!(Tipping::reclaim_tip(RuntimeOrigin::signed(tipper), tips_balance_info.key()));
+
+		assert_eq!(Tipping::tips_balance_by_reference(tips_balance_info.key()), None);
+		assert_eq!(Tipping::tip_expiry_at(tips_balance_info.key()), None);
+		assert_eq!(Assets::balance(1, tipper), 20);
+		assert_eq!(Tipping::frozen_assets(1, tipper), 0);
+		assert_eq!(Assets::balance(1, tipping_account_id), 1);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::TipReclaimed {
+			tipper,
+			tips_balance: TipsBalance::new(&tips_balance_info, &0),
+			amount: 5,
+		}));
+	})
+}
+
+#[test]
+fn reclaim_tip_expires_per_contribution() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let tipper = account_key("bob");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(tipper),
+			0,
+			tips_balance_info.clone(),
+			5,
+			None,
+			None
+		));
+
+		// A later tip by someone else doesn't hold the earlier one back
+		System::set_block_number(10);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("satoshi")),
+			0,
+			tips_balance_info.clone(),
+			3,
+			None,
+			None
+		));
+
+		assert_eq!(Tipping::tip_expiry_at(tips_balance_info.key()), Some(30));
+
+		System::set_block_number(21);
+
+		assert_ok!(Tipping::reclaim_tip(RuntimeOrigin::signed(tipper), tips_balance_info.key()));
+		assert_noop!(
+			Tipping::reclaim_tip(
+				RuntimeOrigin::signed(account_key("satoshi")),
+				tips_balance_info.key()
+			),
+			Error::<Test>::NotExpired,
+		);
+
+		assert_eq!(Tipping::frozen_assets(1, tipper), 0);
+		assert_eq!(
+			Tipping::tips_balance_by_reference(tips_balance_info.key())
+				.map(|tips_balance| *tips_balance.get_amount()),
+			Some(3)
+		);
+	})
+}
+
+#[test]
+fn expired_tips_reported_on_idle_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			tips_balance_info.clone(),
+			5,
+			None,
+			None
+		));
+
+		System::set_block_number(21);
+		Tipping::on_idle(21, Weight::MAX);
+
+		assert_eq!(Tipping::tip_expiry_cursor(), 22);
+		assert_eq!(Tipping::tip_expiry_tasks(21), Vec::new());
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::TipsExpired {
+			tips_balance: TipsBalance::new(&tips_balance_info, &5),
+		}));
+	})
+}
+
+#[test]
+fn claimed_tip_contributions_removed_over_blocks() {
+	let mut ext = <ExternalityBuilder>::default().existential_deposit(1).build();
+	let server_id = account_key("alice");
+	let john = account_key("john");
+	let tippers = ["bob", "satoshi", "admin"];
+	let tips_balance_info = TipsBalanceInfo::new(
+		&server_id,
+		&bounded(b"people"),
+		&bounded(b"people_id"),
+		&bounded(b"1"),
+	);
+	let key = tips_balance_info.key();
+	let claimed_key = (server_id, bounded(b"user"), bounded(b"user_id"), bounded(b"1"));
+	let contribution = |tipper| {
+		Tipping::tip_contributions((&server_id, &key.1, &key.2, &key.3, &account_key(tipper)))
+			.get_amount()
+	};
+
+	let alice_balance = ext.execute_with(|| Assets::balance(1, server_id));
+
+	ext.execute_with(|| {
+		for tipper in tippers {
+			assert_ok!(Tipping::send_tip(
+				RuntimeOrigin::signed(account_key(tipper)),
+				0,
+				tips_balance_info.clone(),
+				5,
+				None,
+				None
+			));
+		}
+
+		assert_ok!(Server::update_server(
+			RuntimeOrigin::signed(server_id),
+			0,
+			ActionType::UpdateSigningKey(Some(account_key("signer")))
+		));
+	});
+
+	// Removal limits only apply to what earlier blocks stored
+	ext.commit_all().unwrap();
+
+	ext.execute_with(|| {
+		let references = references_of(&bounded(b"people"), &[bounded(b"people_id")]);
+		let account_references = references_of(&bounded(b"user"), &[bounded(b"user_id")]);
+		let tx_fee = Some((bounded(b"1"), 1));
+		let proof = ClaimProof::new(0, john, references, account_references, tx_fee, 0, 10);
+
+		assert_ok!(Tipping::claim_reference_with_proof(
+			RuntimeOrigin::signed(john),
+			proof.clone(),
+			sign_claim_proof("signer", &proof),
+			vec![bounded(b"1")],
+		));
+
+		// Only two contributions go with the claim
+		assert_eq!(tippers.into_iter().filter(|tipper| contribution(*tipper) > 0).count(), 1);
+		assert!(Tipping::tip_contribution_cleanup(&key).is_some());
+		assert_eq!(Tipping::tip_expiry_at(&key), None);
+
+		// A new tip isn't mixed up with the old contributions
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("sender_1")),
+			0,
+			tips_balance_info.clone(),
+			5,
+			None,
+			None
+		));
+
+		assert_eq!(contribution("sender_1"), 0);
+		assert_eq!(Tipping::tip_expiry_at(&key), None);
+
+		// Nothing is paid out of the claimed tips before they are all settled
+		assert_eq!(Tipping::pending_settlements(&claimed_key), 1);
+		assert_eq!(Tipping::pending_claim_fee(&claimed_key), 1);
+		assert_eq!(Assets::balance(1, server_id), alice_balance);
+
+		assert_ok!(Tipping::claim_tip(
+			RuntimeOrigin::signed(john),
+			server_id,
+			bounded(b"user"),
+			bounded(b"user_id"),
+			vec![bounded(b"1")]
+		));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::ClaimTip {
+			from: Tipping::tipping_account_id(),
+			to: john,
+			success: Vec::new(),
+			failed: vec![(bounded(b"1"), 14)],
+		}));
+	});
+
+	ext.commit_all().unwrap();
+
+	ext.execute_with(|| {
+		Tipping::on_idle(1, Weight::MAX);
+
+		assert_eq!(Tipping::tip_contribution_cleanup(&key), None);
+		assert_eq!(tippers.into_iter().map(contribution).sum::<u128>(), 0);
+
+		// Every held tip is settled on the tipping account
+		for tipper in tippers {
+			assert_eq!(Tipping::frozen_assets(1, account_key(tipper)), 0);
+		}
+
+		assert_eq!(Assets::balance(1, Tipping::tipping_account_id()), 20);
+
+		// The claim fee is paid once the claimed tips are settled
+		assert_eq!(Tipping::pending_settlements(&claimed_key), 0);
+		assert_eq!(Tipping::pending_claim_fee(&claimed_key), 0);
+		assert_eq!(Assets::balance(1, server_id), alice_balance + 1);
+
+		assert_ok!(Tipping::claim_tip(
+			RuntimeOrigin::signed(john),
+			server_id,
+			bounded(b"user"),
+			bounded(b"user_id"),
+			vec![bounded(b"1")]
+		));
+
+		assert_eq!(Assets::balance(1, john), 44);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("sender_1")),
+			0,
+			tips_balance_info.clone(),
+			5,
+			None,
+			None
+		));
+
+		assert_eq!(contribution("sender_1"), 5);
+		assert_eq!(Tipping::tip_expiry_at(&key), Some(21));
+	});
+}
+
+#[test]
+fn claimed_tips_credit_what_was_settled() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let tipper = account_key("bob");
+		let john = account_key("john");
+		let tipping_account_id = Tipping::tipping_account_id();
+		let claimed_key = (server_id, bounded(b"user"), bounded(b"user_id"), bounded(b"native"));
+
+		Balances::make_free_balance_be(&tipping_account_id, 1);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(tipper),
+			0,
+			TipsBalanceInfo::new(
+				&server_id,
+				&bounded(b"people"),
+				&bounded(b"people_id"),
+				&bounded(b"native")
+			),
+			10,
+			None,
+			None
+		));
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("satoshi")),
+			0,
+			TipsBalanceInfo::new(
+				&server_id,
+				&bounded(b"user"),
+				&bounded(b"user_id"),
+				&bounded(b"native")
+			),
+			2,
+			None,
+			None
+		));
+
+		// Part of the held tip is slashed before the reference is claimed
+		let _ = Balances::slash_reserved_named(b"Tipping!", &tipper, 4);
+
+		assert_ok!(Tipping::claim_reference(
+			RuntimeOrigin::signed(server_id),
+			server_id,
+			0,
+			references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+			references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+			vec![bounded(b"native")],
+			john,
+			1,
+		));
+
+		assert_eq!(
+			Tipping::tips_balance_by_reference(&claimed_key).map(|tips| *tips.get_amount()),
+			Some(7)
+		);
+		assert_eq!(Balances::reserved_balance(tipper), 0);
+		assert_eq!(Balances::reserved_balance(tipping_account_id), 7);
+
+		let john_balance = Balances::free_balance(john);
+
+		assert_ok!(Tipping::claim_tip(
+			RuntimeOrigin::signed(john),
+			server_id,
+			bounded(b"user"),
+			bounded(b"user_id"),
+			vec![bounded(b"native")]
+		));
+
+		assert_eq!(Balances::free_balance(john), john_balance + 7);
+		assert_eq!(Balances::reserved_balance(tipping_account_id), 0);
+	})
+}
+
+#[test]
+fn cant_reclaim_tip() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let tipper = account_key("bob");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
+
+		assert_noop!(
+			Tipping::reclaim_tip(RuntimeOrigin::signed(tipper), tips_balance_info.key()),
+			Error::<Test>::NotExists,
+		);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(tipper),
+			0,
+			tips_balance_info.clone(),
+			5,
+			None,
+			None
+		));
+
+		assert_noop!(
+			Tipping::reclaim_tip(RuntimeOrigin::signed(tipper), tips_balance_info.key()),
+			Error::<Test>::NotExpired,
+		);
+
+		System::set_block_number(21);
+
+		assert_noop!(
+			Tipping::reclaim_tip(
+				RuntimeOrigin::signed(account_key("john")),
+				tips_balance_info.key()
+			),
+			Error::<Test>::NotExists,
+		);
+
+		assert_ok!(Tipping::claim_reference(
+			RuntimeOrigin::signed(server_id),
+			server_id,
+			0,
+			references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+			references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+			vec![bounded(b"native")],
+			account_key("john"),
+			1,
+		));
+
+		assert_eq!(Tipping::tip_expiry_at(tips_balance_info.key()), None);
+		assert_noop!(
+			Tipping::reclaim_tip(RuntimeOrigin::signed(tipper), tips_balance_info.key()),
+			Error::<Test>::Unauthorized,
+		);
+	})
+}
+
+#[test]
+fn send_tip_to_split_reference_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let owner = account_key("john");
+		let sender = account_key("sender_1");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
+		let charity_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"charity"),
+			&bounded(b"charity_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			tips_balance_info.clone(),
+			10,
+			None,
+			None
+		));
+		assert_ok!(Tipping::claim_reference(
+			RuntimeOrigin::signed(server_id),
+			server_id,
+			0,
+			references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+			references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+			vec![bounded(b"native")],
+			owner,
+			1,
+		));
+
+		let splits = vec![
+			TipSplit::new(
+				SplitRecipient::Reference(bounded(b"user"), bounded(b"user_id")),
+				Permill::from_percent(60),
+			),
+			TipSplit::new(
+				SplitRecipient::Account(account_key("satoshi")),
+				Permill::from_percent(30),
+			),
+			TipSplit::new(
+				SplitRecipient::Reference(bounded(b"charity"), bounded(b"charity_id")),
+				Permill::from_percent(10),
+			),
+		];
+
+		assert_ok!(Tipping::set_tip_split(
+			RuntimeOrigin::signed(owner),
+			server_id,
+			bounded(b"user"),
+			bounded(b"user_id"),
+			Some(splits.clone()),
+		));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::TipSplitUpdated {
+			server_id,
+			reference_type: bounded(b"user"),
+			reference_id: bounded(b"user_id"),
+			splits: Some(splits.try_into().unwrap()),
+		}));
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(sender),
+			0,
+			tips_balance_info.clone(),
+			100,
+			None,
+			None
+		));
+
+		let mut tips_balance = TipsBalance::new(&tips_balance_info, &69);
+
+		tips_balance.set_account_id(&owner);
+
+		assert_eq!(Tipping::tips_balance_by_reference(tips_balance_info.key()), Some(tips_balance));
+		assert_eq!(
+			Tipping::tips_balance_by_reference(charity_info.key()),
+			Some(TipsBalance::new(&charity_info, &10))
+		);
+		assert_eq!(Balances::free_balance(account_key("satoshi")), 70);
+		assert_eq!(Balances::free_balance(sender), 19_890);
+		assert_eq!(Balances::reserved_balance(sender), 10);
+
+		System::assert_has_event(RuntimeEvent::Tipping(crate::Event::TipSplitPaid {
+			tips_balance: TipsBalance::new(&tips_balance_info, &100),
+			shares: vec![
+				(SplitRecipient::Reference(bounded(b"user"), bounded(b"user_id")), 60),
+				(SplitRecipient::Account(account_key("satoshi")), 30),
+				(SplitRecipient::Reference(bounded(b"charity"), bounded(b"charity_id")), 10),
+			],
+		}));
+
+		// The first recipient takes the rounding dust
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(sender),
+			0,
+			tips_balance_info.clone(),
+			7,
+			None,
+			None
+		));
+
+		System::assert_has_event(RuntimeEvent::Tipping(crate::Event::TipSplitPaid {
+			tips_balance: TipsBalance::new(&tips_balance_info, &7),
+			shares: vec![
+				(SplitRecipient::Reference(bounded(b"user"), bounded(b"user_id")), 5),
+				(SplitRecipient::Account(account_key("satoshi")), 2),
+				(SplitRecipient::Reference(bounded(b"charity"), bounded(b"charity_id")), 0),
+			],
+		}));
+
+		assert_eq!(Balances::free_balance(account_key("satoshi")), 72);
+		assert_eq!(
+			Tipping::tips_balance_by_reference(charity_info.key()),
+			Some(TipsBalance::new(&charity_info, &10))
+		);
+	})
+}
+
+#[test]
+fn cant_set_tip_split() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let owner = account_key("john");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"user"),
+			&bounded(b"user_id"),
+			&bounded(b"native"),
+		);
+		let split = |share| TipSplit::new(SplitRecipient::Account(owner), share);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			tips_balance_info,
+			10,
+			None,
+			None
+		));
+
+		assert_noop!(
+			Tipping::set_tip_split(
+				RuntimeOrigin::signed(owner),
+				server_id,
+				bounded(b"user"),
+				bounded(b"user_id"),
+				Some(vec![split(Permill::one())]),
+			),
+			Error::<Test>::Unauthorized,
+		);
+
+		assert_ok!(Tipping::claim_reference(
+			RuntimeOrigin::signed(server_id),
+			server_id,
+			0,
+			references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+			references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+			vec![bounded(b"native")],
+			owner,
+			1,
+		));
+
+		assert_noop!(
+			Tipping::set_tip_split(
+				RuntimeOrigin::signed(account_key("bob")),
+				server_id,
+				bounded(b"user"),
+				bounded(b"user_id"),
+				Some(vec![split(Permill::one())]),
+			),
+			Error::<Test>::Unauthorized,
+		);
+
+		for splits in [
+			vec![],
+			vec![split(Permill::from_percent(60)), split(Permill::from_percent(30))],
+			vec![split(Permill::one()), split(Permill::zero())],
+			vec![split(Permill::one()), split(Permill::one())],
+		] {
+			assert_noop!(
+				Tipping::set_tip_split(
+					RuntimeOrigin::signed(owner),
+					server_id,
+					bounded(b"user"),
+					bounded(b"user_id"),
+					Some(splits),
+				),
+				Error::<Test>::InvalidSplit,
+			);
+		}
+
+		assert_noop!(
+			Tipping::set_tip_split(
+				RuntimeOrigin::signed(owner),
+				server_id,
+				bounded(b"user"),
+				bounded(b"user_id"),
+				Some(vec![split(Permill::from_percent(10)); 11]),
+			),
+			Error::<Test>::TooManySplits,
+		);
+	})
+}
+
+#[test]
+fn tip_stats_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+
+		for (tipper, amount) in [("bob", 5), ("john", 8), ("satoshi", 3), ("bob", 4)] {
+			assert_ok!(Tipping::send_tip(
+				RuntimeOrigin::signed(account_key(tipper)),
+				0,
+				tips_balance_info.clone(),
+				amount,
+				None,
+				None
+			));
+		}
+
+		assert_eq!(
+			Tipping::get_top_tippers(
+				&server_id,
+				&bounded(b"people"),
+				&bounded(b"people_id"),
+				&bounded(b"1")
+			),
+			vec![(account_key("bob"), 9), (account_key("john"), 8)]
+		);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("satoshi")),
+			0,
+			tips_balance_info.clone(),
+			7,
+			None,
+			None
+		));
+
+		let mut tip_stats = TipStats::default();
+
+		for (amount, new_tipper) in [(5, true), (8, true), (3, true), (4, false), (7, false)] {
+			tip_stats.add_tip(amount, new_tipper);
+		}
+
+		assert_eq!(tip_stats.get_total_tipped(), 27);
+		assert_eq!(tip_stats.get_tipper_count(), 3);
+		assert_eq!(
+			Tipping::get_top_tippers(
+				&server_id,
+				&bounded(b"people"),
+				&bounded(b"people_id"),
+				&bounded(b"1")
+			),
+			vec![(account_key("satoshi"), 10), (account_key("bob"), 9)]
+		);
+		assert_eq!(
+			Tipping::tipper_total((
+				&server_id,
+				bounded(b"people"),
+				bounded(b"people_id"),
+				bounded(b"1"),
+				account_key("john")
+			)),
+			8
+		);
+
+		// Pays the claim fee
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			TipsBalanceInfo::new(
+				&server_id,
+				&bounded(b"user"),
+				&bounded(b"user_id"),
+				&bounded(b"native")
+			),
+			1,
+			None,
+			None
+		));
+		assert_ok!(Tipping::claim_reference(
+			RuntimeOrigin::signed(account_key("alice")),
+			server_id,
+			0,
+			references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+			references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+			vec![bounded(b"1")],
+			account_key("john"),
+			1,
+		));
+
+		// Claiming moves the balance but keeps the history
+		assert_eq!(
+			Tipping::get_tip_stats(&server_id, &bounded(b"people"), &bounded(b"people_id")),
+			vec![(bounded(b"1"), tip_stats)]
+		);
+		assert_eq!(
+			Tipping::get_tip_stats(&server_id, &bounded(b"people"), &bounded(b"other_id")),
+			vec![]
+		);
+	})
+}
+
+fn pay_content_reward(server_id: sr25519::Public) {
+	let tips_balance_info = TipsBalanceInfo::new(
+		&server_id,
+		&bounded(b"unlockable_content"),
+		&bounded(b"unlockable_content_id"),
+		&bounded(b"native"),
+	);
+
+	assert_ok!(Tipping::pay_content(
+		RuntimeOrigin::signed(account_key("sender_1")),
+		Some(account_key("bob")),
+		0,
+		tips_balance_info,
+		10_000,
+		None,
+		None,
+	));
+}
+
+#[test]
+fn reward_distribution_works() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let beneficiaries = vec![
+			RewardBeneficiary::new(account_key("john"), Permill::from_percent(60)),
+			RewardBeneficiary::new(account_key("satoshi"), Permill::from_percent(40)),
+		];
+
+		pay_content_reward(server_id);
+
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 475);
+
+		assert_ok!(Tipping::set_reward_distribution(
+			RuntimeOrigin::signed(server_id),
+			0,
+			beneficiaries.clone(),
+			10,
+		));
+
+		let mut distribution: RewardDistributionOf<Test> =
+			RewardDistribution::new(server_id, beneficiaries.try_into().unwrap(), 10, 11);
+
+		assert_eq!(Tipping::reward_distribution(0), Some(distribution.clone()));
+		assert_eq!(Tipping::reward_payout_tasks(11), vec![0]);
+
+		System::set_block_number(11);
+		Tipping::on_initialize(11);
+
+		distribution.set_next_payout_at(Some(21));
+
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 0);
+		assert_eq!(Tipping::reward_distribution(0), Some(distribution));
+		assert_eq!(Tipping::reward_payout_tasks(21), vec![0]);
+		assert_eq!(Balances::free_balance(account_key("john")), 315);
+		assert_eq!(Balances::free_balance(account_key("satoshi")), 230);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::RewardDistributed {
+			instance_id: 0,
+			success: vec![
+				(account_key("john"), bounded(b"native"), 285),
+				(account_key("satoshi"), bounded(b"native"), 190),
+			],
+			failed: vec![],
+		}));
+	})
+}
+
+#[test]
+fn reward_payout_retries_failed_transfers() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let newcomer = account_key("newcomer");
+		let beneficiaries = vec![
+			RewardBeneficiary::new(account_key("john"), Permill::from_parts(997_000)),
+			RewardBeneficiary::new(newcomer, Permill::from_parts(3_000)),
+		];
+
+		pay_content_reward(server_id);
+
+		assert_ok!(Tipping::set_reward_distribution(
+			RuntimeOrigin::signed(server_id),
+			0,
+			beneficiaries,
+			10,
+		));
+
+		// The newcomer's share is below the existential deposit
+		System::set_block_number(11);
+		Tipping::on_initialize(11);
+
+		assert_eq!(Balances::free_balance(account_key("john")), 504);
+		assert_eq!(Tipping::unpaid_reward((0, newcomer, bounded(b"native"))), 1);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::RewardDistributed {
+			instance_id: 0,
+			success: vec![(account_key("john"), bounded(b"native"), 474)],
+			failed: vec![(newcomer, bounded(b"native"), 1)],
+		}));
+
+		assert_ok!(Balances::transfer(RuntimeOrigin::signed(account_key("bob")), newcomer, 5));
+
+		System::set_block_number(21);
+		Tipping::on_initialize(21);
+
+		assert_eq!(Balances::free_balance(newcomer), 6);
+		assert_eq!(Tipping::unpaid_reward((0, newcomer, bounded(b"native"))), 0);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::RewardDistributed {
+			instance_id: 0,
+			success: vec![(newcomer, bounded(b"native"), 1)],
+			failed: vec![],
+		}));
+	})
+}
+
+#[test]
+fn reward_payout_is_paged() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let beneficiaries = vec![
+			RewardBeneficiary::new(account_key("john"), Permill::from_percent(60)),
+			RewardBeneficiary::new(account_key("satoshi"), Permill::from_percent(40)),
+		];
+
+		pay_content_reward(server_id);
+
+		for i in 0..9 {
+			let account_id = account_key(&format!("unpaid_{}", i));
+
+			UnpaidRewards::<Test>::insert((0, account_id, bounded(b"native")), 3);
+		}
+
+		assert_ok!(Tipping::set_reward_distribution(
+			RuntimeOrigin::signed(server_id),
+			0,
+			beneficiaries,
+			10,
+		));
+
+		// The unpaid rewards leave no room to split the reward balance
+		System::set_block_number(11);
+		Tipping::on_initialize(11);
+
+		assert_eq!(UnpaidRewards::<Test>::iter_prefix(0).count(), 0);
+		assert_eq!(Balances::free_balance(account_key("unpaid_0")), 3);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 475);
+
+		System::set_block_number(21);
+		Tipping::on_initialize(21);
+
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 0);
+		assert_eq!(Balances::free_balance(account_key("john")), 315);
+		assert_eq!(Balances::free_balance(account_key("satoshi")), 230);
+	})
+}
+
+#[test]
+fn remove_reward_distribution_works() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let newcomer = account_key("newcomer");
+		let beneficiaries = vec![
+			RewardBeneficiary::new(account_key("john"), Permill::from_parts(997_000)),
+			RewardBeneficiary::new(newcomer, Permill::from_parts(3_000)),
+		];
+
+		pay_content_reward(server_id);
+
+		assert_ok!(Tipping::set_reward_distribution(
+			RuntimeOrigin::signed(server_id),
+			0,
+			beneficiaries,
+			10,
+		));
+
+		System::set_block_number(11);
+		Tipping::on_initialize(11);
+
+		assert_ok!(Tipping::remove_reward_distribution(RuntimeOrigin::signed(server_id), 0));
+
+		// The unpaid share goes back to the server
+		assert_eq!(Tipping::reward_distribution(0), None);
+		assert_eq!(Tipping::reward_payout_tasks(21), vec![]);
+		assert_eq!(Tipping::unpaid_reward((0, newcomer, bounded(b"native"))), 0);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 1);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::RewardDistributionRemoved {
+			instance_id: 0,
+		}));
+
+		assert_noop!(
+			Tipping::remove_reward_distribution(RuntimeOrigin::signed(server_id), 0),
+			Error::<Test>::NotExists,
+		);
+	})
+}
+
+#[test]
+fn remove_reward_distribution_is_paged() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let beneficiaries = vec![RewardBeneficiary::new(account_key("john"), Permill::one())];
+
+		assert_ok!(Tipping::set_reward_distribution(
+			RuntimeOrigin::signed(server_id),
+			0,
+			beneficiaries.clone(),
+			10,
+		));
+
+		for i in 0..12 {
+			let account_id = account_key(&format!("unpaid_{}", i));
+
+			UnpaidRewards::<Test>::insert((0, account_id, bounded(b"native")), 3);
+		}
+
+		assert_ok!(Tipping::remove_reward_distribution(RuntimeOrigin::signed(server_id), 0));
+
+		// Two unpaid rewards are left, the distribution is no longer paid out
+		let mut distribution: RewardDistributionOf<Test> =
+			RewardDistribution::new(server_id, beneficiaries.try_into().unwrap(), 10, 11);
+
+		distribution.set_next_payout_at(None);
+
+		assert_eq!(Tipping::reward_distribution(0), Some(distribution.clone()));
+		assert_eq!(Tipping::reward_payout_tasks(11), vec![]);
+		assert_eq!(UnpaidRewards::<Test>::iter_prefix(0).count(), 2);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 30);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::RewardDistributionUpdated {
+			instance_id: 0,
+			distribution,
+		}));
+
+		assert_ok!(Tipping::remove_reward_distribution(RuntimeOrigin::signed(server_id), 0));
+
+		assert_eq!(Tipping::reward_distribution(0), None);
+		assert_eq!(UnpaidRewards::<Test>::iter_prefix(0).count(), 0);
+		assert_eq!(Tipping::reward_balance((server_id, 0, bounded(b"native"))), 36);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::RewardDistributionRemoved {
+			instance_id: 0,
+		}));
+	})
+}
+
+#[test]
+fn cant_set_reward_distribution() {
+	<ExternalityBuilder>::default().existential_deposit(2).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let beneficiary =
+			|share| RewardBeneficiary::new(account_key("john"), Permill::from_percent(share));
+
+		assert_noop!(
+			Tipping::set_reward_distribution(
+				RuntimeOrigin::signed(account_key("bob")),
+				0,
+				vec![beneficiary(100)],
+				10,
+			),
+			Error::<Test>::ServerNotRegister,
+		);
+
+		assert_noop!(
+			Tipping::set_reward_distribution(
+				RuntimeOrigin::signed(server_id),
+				0,
+				vec![beneficiary(100)],
+				0,
+			),
+			Error::<Test>::InvalidPeriod,
+		);
+
+		for beneficiaries in
+			[vec![], vec![beneficiary(60), beneficiary(30)], vec![beneficiary(100), beneficiary(0)]]
+		{
+			assert_noop!(
+				Tipping::set_reward_distribution(
+					RuntimeOrigin::signed(server_id),
+					0,
+					beneficiaries,
+					10,
+				),
+				Error::<Test>::InvalidBeneficiaries,
+			);
+		}
+
+		assert_noop!(
+			Tipping::set_reward_distribution(
+				RuntimeOrigin::signed(server_id),
+				0,
+				vec![beneficiary(10); 11],
+				10,
+			),
+			Error::<Test>::TooManyBeneficiaries,
+		);
+	})
+}
+
+#[test]
+fn fee_spend_proposal_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let proposer = account_key("satoshi");
+		let beneficiary = account_key("john");
+		let treasury = account_key("treasury");
+
+		pay_content_reward(account_key("alice"));
+
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"native")), 25);
+
+		assert_ok!(Tipping::propose_fee_spend(
+			RuntimeOrigin::signed(proposer),
+			beneficiary,
+			bounded(b"native"),
+			20,
+		));
+		assert_ok!(Tipping::propose_fee_spend(
+			RuntimeOrigin::signed(proposer),
+			beneficiary,
+			bounded(b"native"),
+			10,
+		));
+
+		let proposal = FeeSpendProposal::new(proposer, beneficiary, bounded(b"native"), 20, 5);
+
+		assert_eq!(Tipping::fee_spend_proposal(0), Some(proposal.clone()));
+		assert_eq!(Tipping::fee_spend_proposal_count(), 2);
+		assert_eq!(Balances::reserved_balance(proposer), 10);
+
+		assert_ok!(Tipping::approve_fee_spend(RuntimeOrigin::root(), 0));
+
+		assert_eq!(Tipping::fee_spend_proposal(0), None);
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"native")), 5);
+		assert_eq!(Balances::free_balance(beneficiary), 50);
+		assert_eq!(Balances::reserved_balance(proposer), 5);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::FeeSpendApproved {
+			proposal_id: 0,
+			proposal,
+		}));
+
+		// Only 5 of the fees are left
+		assert_noop!(
+			Tipping::approve_fee_spend(RuntimeOrigin::root(), 1),
+			Error::<Test>::InsufficientBalance,
+		);
+
+		assert_ok!(Tipping::reject_fee_spend(RuntimeOrigin::root(), 1));
+
+		assert_eq!(Tipping::fee_spend_proposal(1), None);
+		assert_eq!(Balances::reserved_balance(proposer), 0);
+		assert_eq!(Balances::free_balance(proposer), 35);
+		assert_eq!(Balances::free_balance(treasury), 5);
+	})
+}
+
+#[test]
+fn cant_propose_or_approve_fee_spend() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let beneficiary = account_key("john");
+
+		assert_noop!(
+			Tipping::propose_fee_spend(
+				RuntimeOrigin::signed(account_key("bob")),
+				beneficiary,
+				bounded(b"unknown"),
+				10,
+			),
+			Error::<Test>::AssetNotRegistered,
+		);
+
+		assert_noop!(
+			Tipping::propose_fee_spend(
+				RuntimeOrigin::signed(account_key("newcomer")),
+				beneficiary,
+				bounded(b"native"),
+				10,
+			),
+			Error::<Test>::InsufficientBalance,
+		);
+
+		assert_ok!(Tipping::propose_fee_spend(
+			RuntimeOrigin::signed(account_key("bob")),
+			beneficiary,
+			bounded(b"native"),
+			10,
+		));
+
+		assert_noop!(
+			Tipping::approve_fee_spend(RuntimeOrigin::signed(account_key("admin")), 0),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Tipping::reject_fee_spend(RuntimeOrigin::signed(account_key("admin")), 0),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Tipping::approve_fee_spend(RuntimeOrigin::root(), 1),
+			Error::<Test>::NotExists,
+		);
+	})
+}
+
+#[test]
+fn send_tip_and_pay_content_with_memo_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let sender = account_key("sender_1");
+		let receiver = account_key("john");
+		let attachment = H256::repeat_byte(1);
+		let memo = Memo::new(bounded_memo(b"thanks for the post"), Some(attachment), false);
+		let encrypted_memo = Memo::new(bounded_memo(&[7u8; 32]), None, true);
+
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"native"),
+		);
+		let memo_id = Tipping::receipt_id(&sender, &tips_balance_info, &1_000, 0);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(sender),
+			0,
+			tips_balance_info.clone(),
+			1_000,
+			Some(memo.clone()),
+			None,
+		));
+
+		assert_eq!(Tipping::memos(memo_id), Some(memo.clone()));
+		assert_eq!(Tipping::memo_deposit(memo_id), Some((sender, 55)));
+		assert_eq!(Tipping::receipt_nonce(), 1);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::SendTip {
+			from: sender,
+			to: Tipping::tipping_account_id(),
+			tips_balance: TipsBalance::new(&tips_balance_info, &1_000),
+			fee_detail: FeeDetail::new(0, 0, 0),
+			memo: Some((memo_id, memo)),
+		}));
+
+		let content_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::pay_content(
+			RuntimeOrigin::signed(sender),
+			Some(receiver),
+			0,
+			content_info,
+			1_000,
+			None,
+			Some(encrypted_memo.clone()),
+		));
+
+		let receipt_id =
+			Tipping::receipt_by_content((server_id, bounded(b"unlockable_content_id"), sender))
+				.unwrap();
+		let memo = Tipping::memos(receipt_id).unwrap();
+
+		assert_eq!(memo, encrypted_memo);
+		assert!(memo.is_encrypted());
+		assert_eq!(memo.get_attachment(), &None);
+		assert_eq!(Tipping::memo_deposit(receipt_id), Some((sender, 36)));
+		assert_eq!(Balances::reserved_balance_named(b"tip/memo", &sender), 91);
+
+		// A tip without a memo stores nothing
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(sender),
+			0,
+			tips_balance_info.clone(),
+			1_000,
+			None,
+			None,
+		));
+
+		assert_eq!(
+			Tipping::memos(Tipping::receipt_id(&sender, &tips_balance_info, &1_000, 2)),
+			None
+		);
+		assert_eq!(Tipping::receipt_nonce(), 2);
+	})
+}
+
+#[test]
+fn remove_memo_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let sender = account_key("sender_1");
+		let memo = Memo::new(bounded_memo(b"thanks for the post"), None, false);
+		let tips_balance_info = TipsBalanceInfo::new(
+			&account_key("alice"),
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"native"),
+		);
+		let memo_id = Tipping::receipt_id(&sender, &tips_balance_info, &1_000, 0);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(sender),
+			0,
+			tips_balance_info,
+			1_000,
+			Some(memo),
+			None,
+		));
+
+		assert_eq!(Balances::reserved_balance_named(b"tip/memo", &sender), 23);
+
+		assert_noop!(
+			Tipping::remove_memo(RuntimeOrigin::signed(account_key("john")), memo_id),
+			Error::<Test>::Unauthorized,
+		);
+
+		assert_ok!(Tipping::remove_memo(RuntimeOrigin::signed(sender), memo_id));
+
+		assert_eq!(Tipping::memos(memo_id), None);
+		assert_eq!(Tipping::memo_deposit(memo_id), None);
+		assert_eq!(Balances::reserved_balance_named(b"tip/memo", &sender), 0);
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::MemoRemoved {
+			memo_id,
+			deposit: 23,
+		}));
+
+		assert_noop!(
+			Tipping::remove_memo(RuntimeOrigin::signed(sender), memo_id),
+			Error::<Test>::NotExists,
+		);
+	})
+}
+
+#[test]
+fn anonymous_tip_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let tipper = account_key("bob");
+		let salt = H256::repeat_byte(7);
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+		let key = tips_balance_info.key();
+		let commitment = Tipping::tip_commitment(&tipper, &key, &salt);
+		let tips_balance = TipsBalance::new(&tips_balance_info, &5);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(tipper),
+			0,
+			tips_balance_info.clone(),
+			5,
+			None,
+			Some(commitment),
+		));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::SendAnonymousTip {
+			commitment,
+			to: Tipping::tipping_account_id(),
+			tips_balance: tips_balance.clone(),
+			fee_detail: FeeDetail::new(0, 0, 0),
+			memo: None,
+		}));
+
+		let tip_stats = Tipping::tip_stats_by_reference(tips_balance_info.key());
+
+		assert_eq!(tip_stats.get_total_tipped(), 5);
+		assert_eq!(tip_stats.get_tipper_count(), 0);
+		assert_eq!(Tipping::top_tippers(tips_balance_info.key()).into_inner(), vec![]);
+		assert_eq!(Tipping::anonymous_tip(&key, commitment), Some((tips_balance.clone(), 21)));
+
+		// Nothing ties the tipper to the reference
+		assert_eq!(
+			Tipping::tip_contributions((&server_id, &key.1, &key.2, &key.3, &tipper)).get_amount(),
+			0
+		);
+		assert_eq!(Tipping::tip_expiry_at(&key), None);
+
+		// Nobody can add to a tip under an existing commitment, not even its tipper
+		assert_noop!(
+			Tipping::send_tip(
+				RuntimeOrigin::signed(account_key("john")),
+				0,
+				tips_balance_info.clone(),
+				3,
+				None,
+				Some(commitment),
+			),
+			Error::<Test>::CommitmentExists,
+		);
+
+		assert_noop!(
+			Tipping::reveal_tip(RuntimeOrigin::signed(tipper), key.clone(), H256::repeat_byte(8)),
+			Error::<Test>::NotExists,
+		);
+		assert_noop!(
+			Tipping::reveal_tip(RuntimeOrigin::signed(account_key("john")), key.clone(), salt),
+			Error::<Test>::NotExists,
+		);
+
+		assert_ok!(Tipping::reveal_tip(RuntimeOrigin::signed(tipper), key.clone(), salt));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::TipRevealed {
+			commitment,
+			from: tipper,
+			tips_balance,
+		}));
+
+		let tip_stats = Tipping::tip_stats_by_reference(&key);
+
+		assert_eq!(tip_stats.get_total_tipped(), 5);
+		assert_eq!(tip_stats.get_tipper_count(), 1);
+		assert_eq!(Tipping::top_tippers(&key).into_inner(), vec![(tipper, 5)]);
+		assert_eq!(Tipping::anonymous_tip(&key, commitment), None);
+
+		assert_noop!(
+			Tipping::reveal_tip(RuntimeOrigin::signed(tipper), key, salt),
+			Error::<Test>::NotExists,
+		);
+	})
+}
+
+#[test]
+fn reclaim_anonymous_tip_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let tipper = account_key("bob");
+		let salt = H256::repeat_byte(7);
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_id,
+			&bounded(b"people"),
+			&bounded(b"people_id"),
+			&bounded(b"1"),
+		);
+		let key = tips_balance_info.key();
+		let commitment = Tipping::tip_commitment(&tipper, &key, &salt);
+		let tipper_balance = Assets::balance(1, tipper);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(tipper),
+			0,
+			tips_balance_info.clone(),
+			5,
+			None,
+			Some(commitment),
+		));
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("john")),
+			0,
+			tips_balance_info.clone(),
+			3,
+			None,
+			None,
+		));
+
+		assert_eq!(Assets::balance(1, tipper), tipper_balance - 5);
+
+		assert_noop!(
+			Tipping::reclaim_anonymous_tip(RuntimeOrigin::signed(tipper), key.clone(), salt),
+			Error::<Test>::NotExpired,
+		);
+
+		System::set_block_number(21);
+
+		assert_noop!(
+			Tipping::reclaim_anonymous_tip(
+				RuntimeOrigin::signed(account_key("john")),
+				key.clone(),
+				salt
+			),
+			Error::<Test>::NotExists,
+		);
+
+		assert_ok!(Tipping::reclaim_anonymous_tip(
+			RuntimeOrigin::signed(tipper),
+			key.clone(),
+			salt
+		));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::AnonymousTipReclaimed {
+			commitment,
+			tipper,
+			tips_balance: TipsBalance::new(&tips_balance_info, &3),
+			amount: 5,
+		}));
+
+		assert_eq!(Assets::balance(1, tipper), tipper_balance);
+		assert_eq!(Tipping::anonymous_tip(&key, commitment), None);
+		assert_eq!(
+			Tipping::tips_balance_by_reference(&key).map(|tips_balance| *tips_balance.get_amount()),
+			Some(3)
+		);
+
+		assert_noop!(
+			Tipping::reclaim_anonymous_tip(RuntimeOrigin::signed(tipper), key.clone(), salt),
+			Error::<Test>::NotExists,
+		);
+
+		// Once the reference is claimed its anonymous tips belong to the claimer
+		let salt = H256::repeat_byte(8);
+		let commitment = Tipping::tip_commitment(&tipper, &key, &salt);
+
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(tipper),
+			0,
+			tips_balance_info.clone(),
+			5,
+			None,
+			Some(commitment),
+		));
+
+		let mut tips_balance = Tipping::tips_balance_by_reference(&key).unwrap();
+
+		tips_balance.set_account_id(&account_key("satoshi"));
+		TipsBalanceByReference::<Test>::insert(&key, tips_balance);
+
+		System::set_block_number(41);
+
+		assert_noop!(
+			Tipping::reclaim_anonymous_tip(RuntimeOrigin::signed(tipper), key, salt),
+			Error::<Test>::Unauthorized,
+		);
+	})
+}
+
+#[test]
+fn claim_tip_and_bridge_works() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		let server_id = account_key("alice");
+		let tipping_account_id = Tipping::tipping_account_id();
+
+		assert_ok!(Assets::mint(
+			RuntimeOrigin::signed(account_key("alice")),
+			1,
+			tipping_account_id,
+			1
+		));
+
+		for ft_identifier in [b"native".as_slice(), b"1".as_slice()] {
+			assert_ok!(Tipping::send_tip(
+				RuntimeOrigin::signed(account_key("bob")),
+				0,
+				TipsBalanceInfo::new(
+					&server_id,
+					&bounded(b"people"),
+					&bounded(b"people_id"),
+					&bounded(ft_identifier),
+				),
+				5,
+				None,
+				None,
+			));
+		}
+
+		// Pays the claim fee
+		assert_ok!(Tipping::send_tip(
+			RuntimeOrigin::signed(account_key("bob")),
+			0,
+			TipsBalanceInfo::new(
+				&server_id,
+				&bounded(b"user"),
+				&bounded(b"user_id"),
+				&bounded(b"native"),
+			),
+			1,
+			None,
+			None,
+		));
+
+		assert_ok!(Tipping::claim_reference(
+			RuntimeOrigin::signed(server_id),
+			server_id,
+			0,
+			references_of(&bounded(b"people"), &[bounded(b"people_id")]),
+			references_of(&bounded(b"user"), &[bounded(b"user_id")]),
+			vec![bounded(b"native"), bounded(b"1")],
+			account_key("john"),
+			1,
+		));
+
+		let asset_key = (server_id, bounded(b"user"), bounded(b"user_id"), bounded(b"1"));
+
+		assert_noop!(
+			Tipping::claim_tip_and_bridge(
+				RuntimeOrigin::signed(account_key("bob")),
+				server_id,
+				bounded(b"user"),
+				bounded(b"user_id"),
+				bounded(b"1"),
+				bounded(b"bob.near"),
+			),
+			Error::<Test>::NotExists,
+		);
+		assert_noop!(
+			Tipping::claim_tip_and_bridge(
+				RuntimeOrigin::signed(account_key("john")),
+				server_id,
+				bounded(b"user"),
+				bounded(b"user_id"),
+				bounded(b"1"),
+				bounded(b"fail.near"),
+			),
+			DispatchError::Other("BridgeFailed"),
+		);
+
+		assert_ok!(Tipping::claim_tip_and_bridge(
+			RuntimeOrigin::signed(account_key("john")),
+			server_id,
+			bounded(b"user"),
+			bounded(b"user_id"),
+			bounded(b"1"),
+			bounded(b"john.near"),
+		));
+
+		System::assert_last_event(RuntimeEvent::Tipping(crate::Event::ClaimTipAndBridge {
+			from: tipping_account_id,
+			to: account_key("john"),
+			receiver_id: bounded(b"john.near"),
+			ft_identifier: bounded(b"1"),
+			amount: 5,
+		}));
+
+		// Burned on the way out
+		assert_eq!(Assets::balance(1, account_key("john")), 30);
+		assert_eq!(Assets::balance(1, tipping_account_id), 1);
+		assert_eq!(Tipping::tips_balance_by_reference(&asset_key).unwrap().get_amount(), &0);
+
+		assert_noop!(
+			Tipping::claim_tip_and_bridge(
+				RuntimeOrigin::signed(account_key("john")),
+				server_id,
+				bounded(b"user"),
+				bounded(b"user_id"),
+				bounded(b"1"),
+				bounded(b"john.near"),
+			),
+			Error::<Test>::NotExists,
+		);
+
+		// Native tips net of the claim fee are locked
+		assert_ok!(Tipping::claim_tip_and_bridge(
+			RuntimeOrigin::signed(account_key("john")),
+			server_id,
+			bounded(b"user"),
+			bounded(b"user_id"),
+			bounded(b"native"),
+			bounded(b"john.near"),
+		));
+
+		assert_eq!(Balances::free_balance(account_key("bridge")), 5);
+	})
+}
+
+#[test]
+fn admin_fees_fund_server_rewards() {
+	<ExternalityBuilder>::default().existential_deposit(1).build().execute_with(|| {
+		RewardPotShare::set(Permill::from_percent(20));
+
+		let server_owner = account_key("alice");
+		let tips_balance_info = TipsBalanceInfo::new(
+			&server_owner,
+			&bounded(b"unlockable_content"),
+			&bounded(b"unlockable_content_id"),
+			&bounded(b"native"),
+		);
+
+		assert_ok!(Tipping::pay_content(
+			RuntimeOrigin::signed(account_key("sender_1")),
+			Some(account_key("john")),
+			0,
+			tips_balance_info,
+			10_000,
+			None,
+			None,
+		));
+
+		// A fifth of the 25 admin fee goes to the pot
+		assert_eq!(Tipping::withdrawal_balance(bounded(b"native")), 20);
+		assert_eq!(Tipping::reward_pot_balance(), 5);
+
+		System::set_block_number(50);
+
+		Server::on_initialize(50);
+
+		assert_eq!(Tipping::reward_pot_balance(), 0);
+		assert_eq!(Balances::free_balance(Server::reward_pot_account_id()), 5);
+
+		// Alice is the only staker
+		assert_ok!(Server::claim_reward(RuntimeOrigin::signed(server_owner), 0));
+
+		assert_eq!(Balances::free_balance(server_owner), 15);
+	})
+}
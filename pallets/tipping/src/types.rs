@@ -1,45 +1,95 @@
 use crate::*;
 
-use frame_support::{pallet_prelude::*, sp_runtime::traits::Saturating, traits::Currency};
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::{traits::Saturating, Permill},
+	traits::Currency,
+};
 use scale_info::TypeInfo;
 use sp_std::vec::Vec;
 
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
 pub type FtIdentifier = Vec<u8>;
 pub type ReferenceId = Vec<u8>;
 pub type ReferenceType = Vec<u8>;
 
-pub type TipsBalanceKey<ServerId> = (ServerId, ReferenceType, ReferenceId, FtIdentifier);
+pub type TipsBalanceKey<ServerId, Bytes = Vec<u8>> = (ServerId, Bytes, Bytes, Bytes);
 pub type TipsBalanceTuppleOf<T> = (TipsBalanceKeyOf<T>, BalanceOf<T>);
 
-pub type AccountBalancesOf<T> = Vec<(FtIdentifier, AccountIdOf<T>, BalanceOf<T>)>;
+pub type AccountBalancesOf<T> = Vec<(FtIdentifierOf<T>, AccountIdOf<T>, BalanceOf<T>)>;
 pub type AccountBalancesTuppleOf<T> = (AccountBalancesOf<T>, Option<AccountBalancesOf<T>>);
 
+pub type SubscriptionId = u64;
+pub type FeeSpendProposalId = u32;
+
 pub type AssetId = u32;
 pub type AssetBalance = u128;
 
 pub type HashOf<T> = <T as frame_system::Config>::Hash;
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+pub type BlockNumberOf<T> = <T as frame_system::Config>::BlockNumber;
 pub type ServerIdOf<T> = AccountIdOf<T>;
 pub type CurrencyOf<T> = <T as self::Config>::Currency;
 pub type BalanceOf<T> = <CurrencyOf<T> as Currency<AccountIdOf<T>>>::Balance;
-pub type TipsBalanceOf<T> = TipsBalance<BalanceOf<T>, AccountIdOf<T>, ServerIdOf<T>>;
-pub type TipsBalanceInfoOf<T> = TipsBalanceInfo<ServerIdOf<T>>;
-pub type TipsBalanceKeyOf<T> = TipsBalanceKey<ServerIdOf<T>>;
-pub type ReceiptOf<T> = Receipt<AccountIdOf<T>, BalanceOf<T>>;
-
-#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo)]
-pub struct TipsBalance<Balance, AccountId, ServerId> {
-	tips_balance_info: TipsBalanceInfo<ServerId>,
+pub type BoundedStringOf<T> = BoundedVec<u8, <T as self::Config>::MaxStringLength>;
+pub type FtIdentifierOf<T> = BoundedStringOf<T>;
+pub type ReferenceIdOf<T> = BoundedStringOf<T>;
+pub type ReferenceTypeOf<T> = BoundedStringOf<T>;
+pub type TipsBalanceOf<T> =
+	TipsBalance<BalanceOf<T>, AccountIdOf<T>, ServerIdOf<T>, BoundedStringOf<T>>;
+pub type TipsBalanceInfoOf<T> = TipsBalanceInfo<ServerIdOf<T>, BoundedStringOf<T>>;
+pub type TipsBalanceKeyOf<T> = TipsBalanceKey<ServerIdOf<T>, BoundedStringOf<T>>;
+pub type ReceiptOf<T> = Receipt<HashOf<T>, AccountIdOf<T>, BalanceOf<T>, BoundedStringOf<T>>;
+pub type EscrowOf<T> = Escrow<BalanceOf<T>, BlockNumberOf<T>>;
+pub type SubscriptionOf<T> =
+	Subscription<AccountIdOf<T>, BalanceOf<T>, BlockNumberOf<T>, BoundedStringOf<T>>;
+pub type AssetMetadataOf<T> = AssetMetadata<BalanceOf<T>>;
+pub type ReferenceIdsOf<T> = BoundedVec<BoundedStringOf<T>, <T as self::Config>::MaxReferenceIds>;
+pub type ReferencesOf<T> = References<BoundedStringOf<T>, ReferenceIdsOf<T>>;
+pub type ClaimProofOf<T> = ClaimProof<
+	AccountIdOf<T>,
+	BlockNumberOf<T>,
+	BalanceOf<T>,
+	BoundedStringOf<T>,
+	ReferenceIdsOf<T>,
+>;
+pub type TipSplitOf<T> = TipSplit<AccountIdOf<T>, BoundedStringOf<T>>;
+pub type TipSplitsOf<T> = BoundedVec<TipSplitOf<T>, <T as self::Config>::MaxTipSplits>;
+pub type SplitRecipientOf<T> = SplitRecipient<AccountIdOf<T>, BoundedStringOf<T>>;
+pub type TipStatsOf<T> = TipStats<BalanceOf<T>>;
+pub type TipContributionOf<T> = TipContribution<BalanceOf<T>, BlockNumberOf<T>>;
+pub type TopTippersOf<T> =
+	BoundedVec<(AccountIdOf<T>, BalanceOf<T>), <T as self::Config>::MaxTopTippers>;
+pub type RewardBeneficiaryOf<T> = RewardBeneficiary<AccountIdOf<T>>;
+pub type RewardBeneficiariesOf<T> =
+	BoundedVec<RewardBeneficiaryOf<T>, <T as self::Config>::MaxRewardBeneficiaries>;
+pub type RewardDistributionOf<T> =
+	RewardDistribution<ServerIdOf<T>, RewardBeneficiariesOf<T>, BlockNumberOf<T>>;
+pub type RewardPayoutOf<T> = (AccountIdOf<T>, FtIdentifierOf<T>, BalanceOf<T>);
+pub type FeeSpendProposalOf<T> = FeeSpendProposal<AccountIdOf<T>, BalanceOf<T>, BoundedStringOf<T>>;
+pub type MemoOf<T> = Memo<HashOf<T>, BoundedVec<u8, <T as self::Config>::MaxMemoLength>>;
+pub type TipsOf<T> =
+	BoundedVec<(u64, TipsBalanceInfoOf<T>, BalanceOf<T>), <T as self::Config>::MaxTipsPerBatch>;
+pub type FeeWithdrawalsOf<T> =
+	BoundedVec<(FtIdentifierOf<T>, BalanceOf<T>), <T as self::Config>::MaxFeeWithdrawals>;
+
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct TipsBalance<Balance, AccountId, ServerId, Bytes = Vec<u8>> {
+	tips_balance_info: TipsBalanceInfo<ServerId, Bytes>,
 	account_id: Option<AccountId>,
 	amount: Balance,
 }
-impl<Balance, AccountId, ServerId> TipsBalance<Balance, AccountId, ServerId>
+impl<Balance, AccountId, ServerId, Bytes> TipsBalance<Balance, AccountId, ServerId, Bytes>
 where
 	Balance: Clone + Saturating,
 	AccountId: Clone,
 	ServerId: Clone,
+	Bytes: Clone,
 {
-	pub fn new(tips_balance_info: &TipsBalanceInfo<ServerId>, amount: &Balance) -> Self {
+	pub fn new(tips_balance_info: &TipsBalanceInfo<ServerId, Bytes>, amount: &Balance) -> Self {
 		Self {
 			tips_balance_info: tips_balance_info.clone(),
 			account_id: None,
@@ -47,11 +97,11 @@ where
 		}
 	}
 
-	pub fn key(&self) -> TipsBalanceKey<ServerId> {
+	pub fn key(&self) -> TipsBalanceKey<ServerId, Bytes> {
 		self.tips_balance_info.key()
 	}
 
-	pub fn get_tips_balance_info(&self) -> &TipsBalanceInfo<ServerId> {
+	pub fn get_tips_balance_info(&self) -> &TipsBalanceInfo<ServerId, Bytes> {
 		&self.tips_balance_info
 	}
 
@@ -63,15 +113,15 @@ where
 		self.tips_balance_info.get_server_id()
 	}
 
-	pub fn get_reference_id(&self) -> &Vec<u8> {
+	pub fn get_reference_id(&self) -> &Bytes {
 		self.tips_balance_info.get_reference_id()
 	}
 
-	pub fn get_reference_type(&self) -> &Vec<u8> {
+	pub fn get_reference_type(&self) -> &Bytes {
 		self.tips_balance_info.get_reference_type()
 	}
 
-	pub fn get_ft_identifier(&self) -> &Vec<u8> {
+	pub fn get_ft_identifier(&self) -> &Bytes {
 		self.tips_balance_info.get_ft_identifier()
 	}
 
@@ -79,7 +129,7 @@ where
 		&self.account_id
 	}
 
-	pub fn set_tips_balance_info(&mut self, tips_balance_info: &TipsBalanceInfo<ServerId>) {
+	pub fn set_tips_balance_info(&mut self, tips_balance_info: &TipsBalanceInfo<ServerId, Bytes>) {
 		self.tips_balance_info = tips_balance_info.clone();
 	}
 
@@ -87,6 +137,17 @@ where
 		self.amount = amount;
 	}
 
+	pub fn map_amount<B>(
+		self,
+		f: impl FnOnce(Balance) -> B,
+	) -> TipsBalance<B, AccountId, ServerId, Bytes> {
+		TipsBalance {
+			tips_balance_info: self.tips_balance_info,
+			account_id: self.account_id,
+			amount: f(self.amount),
+		}
+	}
+
 	pub fn add_amount(&mut self, amount: Balance) {
 		self.amount = self.amount.clone().saturating_add(amount);
 	}
@@ -96,29 +157,57 @@ where
 	}
 }
 
-#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo)]
-pub struct TipsBalanceInfo<ServerId> {
+impl<Balance, AccountId, ServerId, S: Get<u32>> TryFrom<TipsBalance<Balance, AccountId, ServerId>>
+	for TipsBalance<Balance, AccountId, ServerId, BoundedVec<u8, S>>
+{
+	type Error = ();
+
+	fn try_from(tips_balance: TipsBalance<Balance, AccountId, ServerId>) -> Result<Self, ()> {
+		Ok(Self {
+			tips_balance_info: tips_balance.tips_balance_info.try_into()?,
+			account_id: tips_balance.account_id,
+			amount: tips_balance.amount,
+		})
+	}
+}
+
+impl<Balance, AccountId, ServerId, S: Get<u32>>
+	From<TipsBalance<Balance, AccountId, ServerId, BoundedVec<u8, S>>>
+	for TipsBalance<Balance, AccountId, ServerId>
+{
+	fn from(tips_balance: TipsBalance<Balance, AccountId, ServerId, BoundedVec<u8, S>>) -> Self {
+		Self {
+			tips_balance_info: tips_balance.tips_balance_info.into(),
+			account_id: tips_balance.account_id,
+			amount: tips_balance.amount,
+		}
+	}
+}
+
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct TipsBalanceInfo<ServerId, Bytes = Vec<u8>> {
 	server_id: ServerId,
-	reference_type: Vec<u8>,
-	reference_id: Vec<u8>,
-	ft_identifier: Vec<u8>,
+	reference_type: Bytes,
+	reference_id: Bytes,
+	ft_identifier: Bytes,
 }
-impl<ServerId: Clone> TipsBalanceInfo<ServerId> {
+impl<ServerId: Clone, Bytes: Clone> TipsBalanceInfo<ServerId, Bytes> {
 	pub fn new(
 		server_id: &ServerId,
-		reference_type: &[u8],
-		reference_id: &[u8],
-		ft_identifier: &[u8],
+		reference_type: &Bytes,
+		reference_id: &Bytes,
+		ft_identifier: &Bytes,
 	) -> Self {
 		Self {
 			server_id: server_id.clone(),
-			reference_type: reference_type.to_vec(),
-			reference_id: reference_id.to_vec(),
-			ft_identifier: ft_identifier.to_vec(),
+			reference_type: reference_type.clone(),
+			reference_id: reference_id.clone(),
+			ft_identifier: ft_identifier.clone(),
 		}
 	}
 
-	pub fn key(&self) -> TipsBalanceKey<ServerId> {
+	pub fn key(&self) -> TipsBalanceKey<ServerId, Bytes> {
 		(
 			self.server_id.clone(),
 			self.reference_type.clone(),
@@ -127,11 +216,11 @@ impl<ServerId: Clone> TipsBalanceInfo<ServerId> {
 		)
 	}
 
-	pub fn get_reference_id(&self) -> &Vec<u8> {
+	pub fn get_reference_id(&self) -> &Bytes {
 		&self.reference_id
 	}
 
-	pub fn get_reference_type(&self) -> &Vec<u8> {
+	pub fn get_reference_type(&self) -> &Bytes {
 		&self.reference_type
 	}
 
@@ -139,7 +228,7 @@ impl<ServerId: Clone> TipsBalanceInfo<ServerId> {
 		&self.server_id
 	}
 
-	pub fn get_ft_identifier(&self) -> &Vec<u8> {
+	pub fn get_ft_identifier(&self) -> &Bytes {
 		&self.ft_identifier
 	}
 
@@ -148,68 +237,271 @@ impl<ServerId: Clone> TipsBalanceInfo<ServerId> {
 		self
 	}
 
-	pub fn set_reference_id(&mut self, reference_id: &[u8]) {
-		self.reference_id = reference_id.to_vec();
+	pub fn set_reference_id(&mut self, reference_id: &Bytes) {
+		self.reference_id = reference_id.clone();
+	}
+
+	pub fn set_reference_type(&mut self, reference_type: &Bytes) {
+		self.reference_type = reference_type.clone();
+	}
+}
+
+impl<ServerId, S: Get<u32>> TryFrom<TipsBalanceInfo<ServerId>>
+	for TipsBalanceInfo<ServerId, BoundedVec<u8, S>>
+{
+	type Error = ();
+
+	fn try_from(info: TipsBalanceInfo<ServerId>) -> Result<Self, ()> {
+		Ok(Self {
+			server_id: info.server_id,
+			reference_type: info.reference_type.try_into().map_err(|_| ())?,
+			reference_id: info.reference_id.try_into().map_err(|_| ())?,
+			ft_identifier: info.ft_identifier.try_into().map_err(|_| ())?,
+		})
 	}
+}
 
-	pub fn set_reference_type(&mut self, reference_type: &[u8]) {
-		self.reference_type = reference_type.to_vec();
+impl<ServerId, S: Get<u32>> From<TipsBalanceInfo<ServerId, BoundedVec<u8, S>>>
+	for TipsBalanceInfo<ServerId>
+{
+	fn from(info: TipsBalanceInfo<ServerId, BoundedVec<u8, S>>) -> Self {
+		Self {
+			server_id: info.server_id,
+			reference_type: info.reference_type.into_inner(),
+			reference_id: info.reference_id.into_inner(),
+			ft_identifier: info.ft_identifier.into_inner(),
+		}
 	}
 }
 
 #[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo)]
-pub struct References {
-	reference_type: ReferenceType,
-	reference_ids: Vec<ReferenceId>,
+pub struct References<Bytes = Vec<u8>, ReferenceIds = Vec<Bytes>> {
+	reference_type: Bytes,
+	reference_ids: ReferenceIds,
 }
-impl References {
-	pub fn new(reference_type: &[u8], reference_ids: &[Vec<u8>]) -> Self {
-		Self { reference_type: reference_type.to_vec(), reference_ids: reference_ids.to_vec() }
+impl<Bytes: Clone, ReferenceIds> References<Bytes, ReferenceIds>
+where
+	ReferenceIds: TryFrom<Vec<Bytes>>,
+{
+	/// `None` when there are more reference ids than `ReferenceIds` holds.
+	pub fn new(reference_type: &Bytes, reference_ids: &[Bytes]) -> Option<Self> {
+		let reference_ids = reference_ids.to_vec().try_into().ok()?;
+
+		Some(Self { reference_type: reference_type.clone(), reference_ids })
 	}
 
-	pub fn get_reference_type(&self) -> &Vec<u8> {
+	pub fn get_reference_type(&self) -> &Bytes {
 		&self.reference_type
 	}
 
-	pub fn get_reference_ids(&self) -> &Vec<Vec<u8>> {
+	pub fn get_reference_ids(&self) -> &ReferenceIds {
 		&self.reference_ids
 	}
 }
 
-#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo)]
-pub struct Receipt<AccountId, Balance> {
+/// Prepended to an encoded `ClaimProof` before signing, so the signature can't be replayed
+/// as another message signed by the same key.
+pub const CLAIM_PROOF_PREFIX: &[u8] = b"tipping:claim_proof:";
+
+/// Signed by the server signing key, lets the account claim the references itself. The fee is
+/// paid to the server from the account reference's balance in the fee currency.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct ClaimProof<AccountId, BlockNumber, Balance, Bytes = Vec<u8>, ReferenceIds = Vec<Bytes>> {
+	instance_id: u64,
+	account_id: AccountId,
+	references: References<Bytes, ReferenceIds>,
+	account_references: References<Bytes, ReferenceIds>,
+	tx_fee: Option<(Bytes, Balance)>,
+	nonce: u64,
+	expires_at: BlockNumber,
+}
+impl<AccountId, BlockNumber: Copy, Balance, Bytes, ReferenceIds>
+	ClaimProof<AccountId, BlockNumber, Balance, Bytes, ReferenceIds>
+{
+	pub fn new(
+		instance_id: u64,
+		account_id: AccountId,
+		references: References<Bytes, ReferenceIds>,
+		account_references: References<Bytes, ReferenceIds>,
+		tx_fee: Option<(Bytes, Balance)>,
+		nonce: u64,
+		expires_at: BlockNumber,
+	) -> Self {
+		Self { instance_id, account_id, references, account_references, tx_fee, nonce, expires_at }
+	}
+
+	/// The bytes the server signs.
+	pub fn signing_payload(&self) -> Vec<u8>
+	where
+		Self: Encode,
+	{
+		[CLAIM_PROOF_PREFIX, &self.encode()].concat()
+	}
+
+	pub fn get_instance_id(&self) -> u64 {
+		self.instance_id
+	}
+
+	pub fn get_account_id(&self) -> &AccountId {
+		&self.account_id
+	}
+
+	pub fn get_references(&self) -> &References<Bytes, ReferenceIds> {
+		&self.references
+	}
+
+	pub fn get_account_references(&self) -> &References<Bytes, ReferenceIds> {
+		&self.account_references
+	}
+
+	pub fn get_tx_fee(&self) -> &Option<(Bytes, Balance)> {
+		&self.tx_fee
+	}
+
+	pub fn get_nonce(&self) -> u64 {
+		self.nonce
+	}
+
+	pub fn get_expires_at(&self) -> BlockNumber {
+		self.expires_at
+	}
+}
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum ReceiptStatus {
+	Paid,
+	Escrowed,
+	Released,
+	Refunded,
+}
+impl Default for ReceiptStatus {
+	fn default() -> Self {
+		Self::Paid
+	}
+}
+
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Receipt<Hash, AccountId, Balance, Bytes = Vec<u8>> {
+	id: Hash,
 	from: AccountId,
 	to: Option<AccountId>,
-	info: TipsBalanceInfo<AccountId>,
+	info: TipsBalanceInfo<AccountId, Bytes>,
 	amount: Balance,
 	fee: Balance,
 	created_at: u128,
+	status: ReceiptStatus,
 }
-impl<AccountId, Balance> Receipt<AccountId, Balance>
+impl<Hash, AccountId, Balance, Bytes> Receipt<Hash, AccountId, Balance, Bytes>
 where
+	Hash: Copy,
 	Balance: Clone + Saturating + Copy,
 	AccountId: Clone,
+	Bytes: Clone,
 {
+	#[allow(clippy::too_many_arguments)]
 	pub fn new(
+		id: Hash,
 		from: &AccountId,
 		to: &Option<AccountId>,
-		info: &TipsBalanceInfo<AccountId>,
+		info: &TipsBalanceInfo<AccountId, Bytes>,
 		amount: &Balance,
 		fee: &Balance,
 		created_at: u128,
+		status: ReceiptStatus,
 	) -> Self {
 		Self {
+			id,
 			from: from.clone(),
 			to: to.clone(),
 			info: info.clone(),
 			amount: *amount,
 			fee: *fee,
 			created_at,
+			status,
 		}
 	}
+
+	pub fn get_id(&self) -> Hash {
+		self.id
+	}
+
+	pub fn get_from(&self) -> &AccountId {
+		&self.from
+	}
+
+	pub fn get_to(&self) -> &Option<AccountId> {
+		&self.to
+	}
+
+	pub fn get_info(&self) -> &TipsBalanceInfo<AccountId, Bytes> {
+		&self.info
+	}
+
+	pub fn get_amount(&self) -> &Balance {
+		&self.amount
+	}
+
+	pub fn get_fee(&self) -> &Balance {
+		&self.fee
+	}
+
+	pub fn get_status(&self) -> ReceiptStatus {
+		self.status
+	}
+
+	pub fn set_status(&mut self, status: ReceiptStatus) {
+		self.status = status;
+	}
 }
 
-#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+impl<Hash, AccountId, Balance, S: Get<u32>> TryFrom<Receipt<Hash, AccountId, Balance>>
+	for Receipt<Hash, AccountId, Balance, BoundedVec<u8, S>>
+{
+	type Error = ();
+
+	fn try_from(receipt: Receipt<Hash, AccountId, Balance>) -> Result<Self, ()> {
+		Ok(Self {
+			id: receipt.id,
+			from: receipt.from,
+			to: receipt.to,
+			info: receipt.info.try_into()?,
+			amount: receipt.amount,
+			fee: receipt.fee,
+			created_at: receipt.created_at,
+			status: receipt.status,
+		})
+	}
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Escrow<Balance, BlockNumber> {
+	instance_id: u64,
+	fee_detail: FeeDetail<Balance>,
+	release_at: BlockNumber,
+}
+impl<Balance, BlockNumber> Escrow<Balance, BlockNumber>
+where
+	Balance: Copy,
+	BlockNumber: Copy,
+{
+	pub fn new(instance_id: u64, fee_detail: &FeeDetail<Balance>, release_at: BlockNumber) -> Self {
+		Self { instance_id, fee_detail: fee_detail.clone(), release_at }
+	}
+
+	pub fn get_instance_id(&self) -> u64 {
+		self.instance_id
+	}
+
+	pub fn get_fee_detail(&self) -> &FeeDetail<Balance> {
+		&self.fee_detail
+	}
+
+	pub fn get_release_at(&self) -> BlockNumber {
+		self.release_at
+	}
+}
+
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
 pub struct FeeDetail<Balance> {
 	admin_fee: Balance,
 	server_fee: Balance,
@@ -235,3 +527,480 @@ where
 		self.total_fee
 	}
 }
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum SubscriptionStatus {
+	Active,
+	Paused,
+	Suspended,
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Subscription<AccountId, Balance, BlockNumber, Bytes = Vec<u8>> {
+	id: SubscriptionId,
+	instance_id: u64,
+	subscriber: AccountId,
+	tips_balance_info: TipsBalanceInfo<AccountId, Bytes>,
+	amount: Balance,
+	period: BlockNumber,
+	next_payment_at: Option<BlockNumber>,
+	status: SubscriptionStatus,
+}
+impl<AccountId, Balance, BlockNumber, Bytes> Subscription<AccountId, Balance, BlockNumber, Bytes>
+where
+	AccountId: Clone + PartialEq,
+	Balance: Copy,
+	BlockNumber: Copy,
+	Bytes: Clone,
+{
+	pub fn new(
+		id: SubscriptionId,
+		instance_id: u64,
+		subscriber: &AccountId,
+		tips_balance_info: &TipsBalanceInfo<AccountId, Bytes>,
+		amount: &Balance,
+		period: BlockNumber,
+		next_payment_at: BlockNumber,
+	) -> Self {
+		Self {
+			id,
+			instance_id,
+			subscriber: subscriber.clone(),
+			tips_balance_info: tips_balance_info.clone(),
+			amount: *amount,
+			period,
+			next_payment_at: Some(next_payment_at),
+			status: SubscriptionStatus::Active,
+		}
+	}
+
+	pub fn is_authorized(self, subscriber: &AccountId) -> Option<Self> {
+		if &self.subscriber == subscriber {
+			Some(self)
+		} else {
+			None
+		}
+	}
+
+	pub fn get_id(&self) -> SubscriptionId {
+		self.id
+	}
+
+	pub fn get_instance_id(&self) -> u64 {
+		self.instance_id
+	}
+
+	pub fn get_subscriber(&self) -> &AccountId {
+		&self.subscriber
+	}
+
+	pub fn get_tips_balance_info(&self) -> &TipsBalanceInfo<AccountId, Bytes> {
+		&self.tips_balance_info
+	}
+
+	pub fn get_amount(&self) -> &Balance {
+		&self.amount
+	}
+
+	pub fn get_period(&self) -> BlockNumber {
+		self.period
+	}
+
+	pub fn get_next_payment_at(&self) -> Option<BlockNumber> {
+		self.next_payment_at
+	}
+
+	pub fn get_status(&self) -> SubscriptionStatus {
+		self.status
+	}
+
+	pub fn set_next_payment_at(&mut self, next_payment_at: Option<BlockNumber>) {
+		self.next_payment_at = next_payment_at;
+	}
+
+	pub fn set_status(&mut self, status: SubscriptionStatus) {
+		self.status = status;
+	}
+}
+
+impl<AccountId, Balance, BlockNumber, S: Get<u32>>
+	TryFrom<Subscription<AccountId, Balance, BlockNumber>>
+	for Subscription<AccountId, Balance, BlockNumber, BoundedVec<u8, S>>
+{
+	type Error = ();
+
+	fn try_from(subscription: Subscription<AccountId, Balance, BlockNumber>) -> Result<Self, ()> {
+		Ok(Self {
+			id: subscription.id,
+			instance_id: subscription.instance_id,
+			subscriber: subscription.subscriber,
+			tips_balance_info: subscription.tips_balance_info.try_into()?,
+			amount: subscription.amount,
+			period: subscription.period,
+			next_payment_at: subscription.next_payment_at,
+			status: subscription.status,
+		})
+	}
+}
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct FeePolicy {
+	transaction_fee: Permill,
+	tip_fee: Permill,
+	admin_fee: Permill,
+}
+impl Default for FeePolicy {
+	fn default() -> Self {
+		Self::new(Permill::from_percent(5), Permill::from_percent(10))
+	}
+}
+impl FeePolicy {
+	/// `transaction_fee` is taken from the paid amount, `admin_fee` is the admin share of it.
+	pub fn new(transaction_fee: Permill, admin_fee: Permill) -> Self {
+		Self { transaction_fee, tip_fee: Permill::zero(), admin_fee }
+	}
+
+	pub fn transaction_fee(&self) -> Permill {
+		self.transaction_fee
+	}
+
+	pub fn tip_fee(&self) -> Permill {
+		self.tip_fee
+	}
+
+	/// `tip_fee` is taken from sent tips and shares `admin_fee` with content payments.
+	pub fn set_tip_fee(mut self, tip_fee: Permill) -> Self {
+		self.tip_fee = tip_fee;
+		self
+	}
+
+	pub fn admin_fee(&self) -> Permill {
+		self.admin_fee
+	}
+}
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct FeePolicyBounds {
+	max_transaction_fee: Permill,
+	max_tip_fee: Permill,
+	min_admin_fee: Permill,
+}
+impl Default for FeePolicyBounds {
+	fn default() -> Self {
+		Self::new(Permill::from_percent(10), Permill::from_percent(10))
+	}
+}
+impl FeePolicyBounds {
+	pub fn new(max_transaction_fee: Permill, min_admin_fee: Permill) -> Self {
+		Self { max_transaction_fee, max_tip_fee: Permill::zero(), min_admin_fee }
+	}
+
+	pub fn max_transaction_fee(&self) -> Permill {
+		self.max_transaction_fee
+	}
+
+	pub fn max_tip_fee(&self) -> Permill {
+		self.max_tip_fee
+	}
+
+	pub fn set_max_tip_fee(mut self, max_tip_fee: Permill) -> Self {
+		self.max_tip_fee = max_tip_fee;
+		self
+	}
+
+	pub fn min_admin_fee(&self) -> Permill {
+		self.min_admin_fee
+	}
+
+	pub fn contains(&self, fee_policy: &FeePolicy) -> bool {
+		fee_policy.transaction_fee() <= self.max_transaction_fee &&
+			fee_policy.tip_fee() <= self.max_tip_fee &&
+			fee_policy.admin_fee() >= self.min_admin_fee
+	}
+
+	/// The closest policy within the bounds.
+	pub fn clamp(&self, fee_policy: FeePolicy) -> FeePolicy {
+		FeePolicy {
+			transaction_fee: fee_policy.transaction_fee().min(self.max_transaction_fee),
+			tip_fee: fee_policy.tip_fee().min(self.max_tip_fee),
+			admin_fee: fee_policy.admin_fee().max(self.min_admin_fee),
+		}
+	}
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum AssetKind {
+	Native,
+	Asset(AssetId),
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct AssetMetadata<Balance> {
+	kind: AssetKind,
+	enabled: bool,
+	min_tip: Balance,
+	decimals: u8,
+}
+impl<Balance: Copy> AssetMetadata<Balance> {
+	pub fn new(kind: AssetKind, min_tip: Balance, decimals: u8) -> Self {
+		Self { kind, enabled: true, min_tip, decimals }
+	}
+
+	pub fn get_kind(&self) -> AssetKind {
+		self.kind
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		self.enabled
+	}
+
+	pub fn get_min_tip(&self) -> &Balance {
+		&self.min_tip
+	}
+
+	pub fn get_decimals(&self) -> u8 {
+		self.decimals
+	}
+
+	pub fn set_enabled(mut self, enabled: bool) -> Self {
+		self.enabled = enabled;
+		self
+	}
+
+	pub fn set_min_tip(mut self, min_tip: Balance) -> Self {
+		self.min_tip = min_tip;
+		self
+	}
+
+	pub fn set_decimals(mut self, decimals: u8) -> Self {
+		self.decimals = decimals;
+		self
+	}
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum SplitRecipient<AccountId, Bytes = Vec<u8>> {
+	Account(AccountId),
+	Reference(Bytes, Bytes),
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct TipSplit<AccountId, Bytes = Vec<u8>> {
+	recipient: SplitRecipient<AccountId, Bytes>,
+	share: Permill,
+}
+impl<AccountId, Bytes> TipSplit<AccountId, Bytes> {
+	pub fn new(recipient: SplitRecipient<AccountId, Bytes>, share: Permill) -> Self {
+		Self { recipient, share }
+	}
+
+	pub fn get_recipient(&self) -> &SplitRecipient<AccountId, Bytes> {
+		&self.recipient
+	}
+
+	pub fn get_share(&self) -> Permill {
+		self.share
+	}
+}
+
+impl<AccountId, S: Get<u32>> TryFrom<TipSplit<AccountId>>
+	for TipSplit<AccountId, BoundedVec<u8, S>>
+{
+	type Error = ();
+
+	fn try_from(split: TipSplit<AccountId>) -> Result<Self, ()> {
+		let recipient = match split.recipient {
+			SplitRecipient::Account(account_id) => SplitRecipient::Account(account_id),
+			SplitRecipient::Reference(reference_type, reference_id) => SplitRecipient::Reference(
+				reference_type.try_into().map_err(|_| ())?,
+				reference_id.try_into().map_err(|_| ())?,
+			),
+		};
+
+		Ok(Self { recipient, share: split.share })
+	}
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct TipStats<Balance> {
+	total_tipped: Balance,
+	tipper_count: u32,
+}
+impl<Balance> TipStats<Balance>
+where
+	Balance: Saturating + Copy,
+{
+	pub fn get_total_tipped(&self) -> Balance {
+		self.total_tipped
+	}
+
+	pub fn get_tipper_count(&self) -> u32 {
+		self.tipper_count
+	}
+
+	pub fn map_total_tipped<B>(self, f: impl FnOnce(Balance) -> B) -> TipStats<B> {
+		TipStats { total_tipped: f(self.total_tipped), tipper_count: self.tipper_count }
+	}
+
+	pub fn add_tip(&mut self, amount: Balance, new_tipper: bool) {
+		self.total_tipped = self.total_tipped.saturating_add(amount);
+
+		if new_tipper {
+			self.tipper_count = self.tipper_count.saturating_add(1);
+		}
+	}
+}
+
+/// What a tipper holds for an unclaimed reference, reclaimable from `expires_at` on.
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct TipContribution<Balance, BlockNumber> {
+	amount: Balance,
+	expires_at: BlockNumber,
+}
+impl<Balance, BlockNumber> TipContribution<Balance, BlockNumber>
+where
+	Balance: Saturating + Copy,
+	BlockNumber: Copy,
+{
+	pub fn get_amount(&self) -> Balance {
+		self.amount
+	}
+
+	pub fn get_expires_at(&self) -> BlockNumber {
+		self.expires_at
+	}
+
+	/// Adds a tip, the whole contribution expires with the latest one.
+	pub fn add_tip(&mut self, amount: Balance, expires_at: BlockNumber) {
+		self.amount = self.amount.saturating_add(amount);
+		self.expires_at = expires_at;
+	}
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct RewardBeneficiary<AccountId> {
+	account_id: AccountId,
+	share: Permill,
+}
+impl<AccountId> RewardBeneficiary<AccountId> {
+	pub fn new(account_id: AccountId, share: Permill) -> Self {
+		Self { account_id, share }
+	}
+
+	pub fn get_account_id(&self) -> &AccountId {
+		&self.account_id
+	}
+
+	pub fn get_share(&self) -> Permill {
+		self.share
+	}
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct RewardDistribution<ServerId, Beneficiaries, BlockNumber> {
+	server_id: ServerId,
+	beneficiaries: Beneficiaries,
+	period: BlockNumber,
+	next_payout_at: Option<BlockNumber>,
+}
+impl<ServerId, Beneficiaries, BlockNumber: Copy>
+	RewardDistribution<ServerId, Beneficiaries, BlockNumber>
+{
+	pub fn new(
+		server_id: ServerId,
+		beneficiaries: Beneficiaries,
+		period: BlockNumber,
+		next_payout_at: BlockNumber,
+	) -> Self {
+		Self { server_id, beneficiaries, period, next_payout_at: Some(next_payout_at) }
+	}
+
+	pub fn get_server_id(&self) -> &ServerId {
+		&self.server_id
+	}
+
+	pub fn get_beneficiaries(&self) -> &Beneficiaries {
+		&self.beneficiaries
+	}
+
+	pub fn get_period(&self) -> BlockNumber {
+		self.period
+	}
+
+	pub fn get_next_payout_at(&self) -> Option<BlockNumber> {
+		self.next_payout_at
+	}
+
+	pub fn set_next_payout_at(&mut self, next_payout_at: Option<BlockNumber>) {
+		self.next_payout_at = next_payout_at;
+	}
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct FeeSpendProposal<AccountId, Balance, Bytes = Vec<u8>> {
+	proposer: AccountId,
+	beneficiary: AccountId,
+	ft_identifier: Bytes,
+	amount: Balance,
+	deposit: Balance,
+}
+impl<AccountId, Balance, Bytes> FeeSpendProposal<AccountId, Balance, Bytes> {
+	pub fn new(
+		proposer: AccountId,
+		beneficiary: AccountId,
+		ft_identifier: Bytes,
+		amount: Balance,
+		deposit: Balance,
+	) -> Self {
+		Self { proposer, beneficiary, ft_identifier, amount, deposit }
+	}
+
+	pub fn get_proposer(&self) -> &AccountId {
+		&self.proposer
+	}
+
+	pub fn get_beneficiary(&self) -> &AccountId {
+		&self.beneficiary
+	}
+
+	pub fn get_ft_identifier(&self) -> &Bytes {
+		&self.ft_identifier
+	}
+
+	pub fn get_amount(&self) -> &Balance {
+		&self.amount
+	}
+
+	pub fn get_deposit(&self) -> &Balance {
+		&self.deposit
+	}
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Memo<Hash, Bytes = Vec<u8>> {
+	content: Bytes,
+	attachment: Option<Hash>,
+	encrypted: bool,
+}
+impl<Hash, Bytes> Memo<Hash, Bytes> {
+	pub fn new(content: Bytes, attachment: Option<Hash>, encrypted: bool) -> Self {
+		Self { content, attachment, encrypted }
+	}
+
+	pub fn get_content(&self) -> &Bytes {
+		&self.content
+	}
+
+	pub fn get_attachment(&self) -> &Option<Hash> {
+		&self.attachment
+	}
+
+	/// Whether the sender says the content was encrypted to the recipient's public key before
+	/// submission. The chain doesn't check it, clients decide whether to trust it.
+	pub fn is_encrypted(&self) -> bool {
+		self.encrypted
+	}
+}
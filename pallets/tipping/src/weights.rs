@@ -28,30 +28,145 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for pallet_tipping.
 pub trait WeightInfo {
 	fn pay_content() -> Weight;
-	fn withdraw_fee() -> Weight;
+	fn pay_content_escrow() -> Weight;
+	fn confirm_escrow() -> Weight;
+	fn refund_escrow() -> Weight;
+	fn on_initialize_escrow() -> Weight;
+	fn withdraw_fee(s: u32, ) -> Weight;
+	fn propose_fee_spend() -> Weight;
+	fn approve_fee_spend() -> Weight;
+	fn reject_fee_spend() -> Weight;
 	fn withdraw_reward() -> Weight;
 	fn send_tip() -> Weight;
+	fn reveal_tip() -> Weight;
+	fn send_tips(s: u32, ) -> Weight;
 	fn claim_reference() -> Weight;
+	fn claim_reference_with_proof() -> Weight;
+	fn reclaim_tip() -> Weight;
+	fn on_idle_tip_expiry() -> Weight;
+	fn remove_tip_contributions(c: u32, ) -> Weight;
 	fn claim_tip() -> Weight;
+	fn claim_tip_and_bridge() -> Weight;
+	fn set_default_fee_policy() -> Weight;
+	fn set_fee_policy_bounds() -> Weight;
+	fn set_server_fee_policy() -> Weight;
+	fn set_tip_split(s: u32, ) -> Weight;
+	fn split_tip(s: u32, ) -> Weight;
+	fn register_asset() -> Weight;
+	fn update_asset() -> Weight;
+	fn subscribe() -> Weight;
+	fn cancel_subscription() -> Weight;
+	fn pause_subscription() -> Weight;
+	fn resume_subscription() -> Weight;
+	fn on_initialize_subscription() -> Weight;
+	fn set_reward_distribution(b: u32, ) -> Weight;
+	fn remove_reward_distribution(u: u32, ) -> Weight;
+	fn on_initialize_reward_payout(p: u32, ) -> Weight;
+	fn remove_memo() -> Weight;
+	fn reclaim_anonymous_tip() -> Weight;
 }
 
 /// Weights for pallet_tipping using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: System Account (r:2 w:2)
+	// Storage: Balances Reserves (r:1 w:1)
 	// Storage: Tipping WithdrawalBalance (r:1 w:1)
 	// Storage: Timestamp Now (r:1 w:0)
 	// Storage: Tipping ReceiptIds (r:1 w:1)
 	// Storage: Tipping Receipts (r:0 w:1)
+	// Storage: Tipping ReceiptByContent (r:0 w:1)
+	// Storage: Tipping Memos (r:0 w:1)
+	// Storage: Tipping MemoDeposit (r:0 w:1)
 	fn pay_content() -> Weight {
-		Weight::from_ref_time(165_900_000_u64)
-			.saturating_add(T::DbWeight::get().reads(5_u64))
-			.saturating_add(T::DbWeight::get().writes(5_u64))
+		Weight::from_ref_time(178_400_000_u64)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping EscrowTasks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping ReceiptNonce (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Tipping Receipts (r:0 w:1)
+	// Storage: Tipping Escrows (r:0 w:1)
+	// Storage: Tipping ReceiptByContent (r:0 w:1)
+	// Storage: Tipping Memos (r:0 w:1)
+	// Storage: Tipping MemoDeposit (r:0 w:1)
+	fn pay_content_escrow() -> Weight {
+		Weight::from_ref_time(131_800_000_u64)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+	}
+	// Storage: Tipping Receipts (r:1 w:1)
+	// Storage: Tipping Escrows (r:1 w:1)
+	// Storage: System Account (r:3 w:3)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping WithdrawalBalance (r:1 w:1)
+	// Storage: Tipping RewardBalance (r:1 w:1)
+	// Storage: Tipping EscrowTasks (r:1 w:1)
+	fn confirm_escrow() -> Weight {
+		Weight::from_ref_time(139_600_000_u64)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+	}
+	// Storage: Tipping Receipts (r:1 w:1)
+	// Storage: Tipping Escrows (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping EscrowTasks (r:1 w:1)
+	// Storage: Tipping ReceiptByContent (r:1 w:1)
+	fn refund_escrow() -> Weight {
+		Weight::from_ref_time(82_700_000_u64)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	// Storage: Tipping Receipts (r:1 w:1)
+	// Storage: Tipping Escrows (r:1 w:1)
+	// Storage: System Account (r:3 w:3)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping WithdrawalBalance (r:1 w:1)
+	// Storage: Tipping RewardBalance (r:1 w:1)
+	fn on_initialize_escrow() -> Weight {
+		Weight::from_ref_time(127_500_000_u64)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
 	}
 	// Storage: Tipping WithdrawalBalance (r:1 w:1)
 	// Storage: System Account (r:2 w:2)
-	fn withdraw_fee() -> Weight {
-		Weight::from_ref_time(165_800_000_u64)
+	/// The range of component `s` is `[1, 10]`.
+	fn withdraw_fee(s: u32, ) -> Weight {
+		Weight::from_ref_time(32_400_000_u64)
+			// Standard Error: 19_000
+			.saturating_add(Weight::from_ref_time(66_700_000_u64).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(s as u64)))
+	}
+	// Storage: Tipping AssetRegistry (r:1 w:0)
+	// Storage: Tipping FeeSpendProposalCount (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Tipping FeeSpendProposals (r:0 w:1)
+	fn propose_fee_spend() -> Weight {
+		Weight::from_ref_time(38_900_000_u64)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	// Storage: Tipping FeeSpendProposals (r:1 w:1)
+	// Storage: Tipping WithdrawalBalance (r:1 w:1)
+	// Storage: Tipping AssetRegistry (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	fn approve_fee_spend() -> Weight {
+		Weight::from_ref_time(71_300_000_u64)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	// Storage: Tipping FeeSpendProposals (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn reject_fee_spend() -> Weight {
+		Weight::from_ref_time(44_600_000_u64)
 			.saturating_add(T::DbWeight::get().reads(3_u64))
 			.saturating_add(T::DbWeight::get().writes(3_u64))
 	}
@@ -63,44 +178,393 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(3_u64))
 	}
 	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
 	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Tipping TipperTotals (r:1 w:1)
+	// Storage: Tipping TipStatsByReference (r:1 w:1)
+	// Storage: Tipping TopTippers (r:1 w:1)
+	// Storage: Tipping ReceiptNonce (r:1 w:1)
+	// Storage: Tipping AnonymousTips (r:1 w:1)
+	// Storage: Tipping Memos (r:0 w:1)
+	// Storage: Tipping MemoDeposit (r:0 w:1)
 	fn send_tip() -> Weight {
-		Weight::from_ref_time(122_100_000_u64)
+		Weight::from_ref_time(139_800_000_u64)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+	}
+	// Storage: Tipping AnonymousTips (r:1 w:1)
+	// Storage: Tipping TipperTotals (r:1 w:1)
+	// Storage: Tipping TopTippers (r:1 w:1)
+	// Storage: Tipping TipStatsByReference (r:1 w:1)
+	fn reveal_tip() -> Weight {
+		Weight::from_ref_time(38_600_000_u64)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Tipping TipperTotals (r:1 w:1)
+	// Storage: Tipping TipStatsByReference (r:1 w:1)
+	// Storage: Tipping TopTippers (r:1 w:1)
+	/// The range of component `s` is `[1, 50]`.
+	fn send_tips(s: u32, ) -> Weight {
+		Weight::from_ref_time(98_400_000_u64)
+			// Standard Error: 21_000
+			.saturating_add(Weight::from_ref_time(49_800_000_u64).saturating_mul(s as u64))
 			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(s as u64)))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(s as u64)))
 	}
+	// Storage: Server ServerById (r:1 w:0)
 	// Storage: Tipping TipsBalanceByReference (r:2 w:2)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping PendingSettlements (r:1 w:0)
 	fn claim_reference() -> Weight {
-		Weight::from_ref_time(165_100_000_u64)
-			.saturating_add(T::DbWeight::get().reads(3_u64))
-			.saturating_add(T::DbWeight::get().writes(3_u64))
+		Weight::from_ref_time(185_900_000_u64)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping ClaimNonce (r:1 w:1)
+	// Storage: Server SigningKeyByServer (r:1 w:0)
+	// Storage: Tipping TipsBalanceByReference (r:2 w:2)
+	// Storage: Tipping AssetRegistry (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping PendingSettlements (r:1 w:0)
+	fn claim_reference_with_proof() -> Weight {
+		Weight::from_ref_time(186_600_000_u64)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Tipping TipContributions (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping TipExpiryAt (r:0 w:1)
+	fn reclaim_tip() -> Weight {
+		Weight::from_ref_time(69_800_000_u64)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	// Storage: Tipping TipExpiryAt (r:1 w:0)
+	// Storage: Tipping TipsBalanceByReference (r:1 w:0)
+	fn on_idle_tip_expiry() -> Weight {
+		Weight::from_ref_time(21_400_000_u64)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+	}
+	// Storage: Tipping TipExpiryAt (r:0 w:1)
+	// Storage: Tipping TipContributions (r:1 w:1)
+	// Storage: Tipping AssetRegistry (r:1 w:0)
+	// Storage: Balances Reserves (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Tipping TipContributionCleanup (r:1 w:1)
+	// Storage: Tipping PendingSettlements (r:1 w:1)
+	// Storage: Tipping PendingClaimFees (r:1 w:1)
+	/// The range of component `c` is `[1, 100]`.
+	fn remove_tip_contributions(c: u32, ) -> Weight {
+		Weight::from_ref_time(27_300_000_u64)
+			// Standard Error: 9_000
+			.saturating_add(Weight::from_ref_time(38_900_000_u64).saturating_mul(c as u64))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(c as u64)))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(c as u64)))
 	}
 	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping PendingSettlements (r:1 w:0)
 	fn claim_tip() -> Weight {
-		Weight::from_ref_time(107_400_000_u64)
+		Weight::from_ref_time(121_200_000_u64)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Tipping AssetRegistry (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: OctopusAppchain IsActivated (r:1 w:0)
+	// Storage: OctopusUpwardMessages Messages (r:1 w:1)
+	// Storage: Tipping PendingSettlements (r:1 w:0)
+	fn claim_tip_and_bridge() -> Weight {
+		Weight::from_ref_time(160_400_000_u64)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	// Storage: Tipping FeeBounds (r:1 w:0)
+	// Storage: Tipping DefaultFeePolicy (r:0 w:1)
+	fn set_default_fee_policy() -> Weight {
+		Weight::from_ref_time(25_100_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: Tipping DefaultFeePolicy (r:1 w:0)
+	// Storage: Tipping FeeBounds (r:0 w:1)
+	fn set_fee_policy_bounds() -> Weight {
+		Weight::from_ref_time(24_700_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping FeeBounds (r:1 w:0)
+	// Storage: Tipping FeePolicyByServer (r:0 w:1)
+	fn set_server_fee_policy() -> Weight {
+		Weight::from_ref_time(34_600_000_u64)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: Tipping TipsBalanceByReference (r:1 w:0)
+	// Storage: Tipping TipSplits (r:0 w:1)
+	/// The range of component `s` is `[1, 10]`.
+	fn set_tip_split(s: u32, ) -> Weight {
+		Weight::from_ref_time(30_700_000_u64)
+			// Standard Error: 3_000
+			.saturating_add(Weight::from_ref_time(1_200_000_u64).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: Tipping TipSplits (r:1 w:0)
+	// Storage: Tipping AssetRegistry (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `s` is `[1, 10]`.
+	fn split_tip(s: u32, ) -> Weight {
+		Weight::from_ref_time(12_800_000_u64)
+			// Standard Error: 17_000
+			.saturating_add(Weight::from_ref_time(38_900_000_u64).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(s as u64)))
+	}
+	// Storage: Tipping AssetRegistry (r:1 w:1)
+	fn register_asset() -> Weight {
+		Weight::from_ref_time(24_300_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: Tipping AssetRegistry (r:1 w:1)
+	fn update_asset() -> Weight {
+		Weight::from_ref_time(23_100_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping SubscriptionIndex (r:1 w:1)
+	// Storage: Tipping SubscriptionTasks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Tipping SubscriptionById (r:0 w:1)
+	// Storage: Tipping TipperTotals (r:1 w:1)
+	// Storage: Tipping TipStatsByReference (r:1 w:1)
+	// Storage: Tipping TopTippers (r:1 w:1)
+	fn subscribe() -> Weight {
+		Weight::from_ref_time(141_900_000_u64)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+	}
+	// Storage: Tipping SubscriptionById (r:1 w:1)
+	// Storage: Tipping SubscriptionTasks (r:1 w:1)
+	fn cancel_subscription() -> Weight {
+		Weight::from_ref_time(41_200_000_u64)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	// Storage: Tipping SubscriptionById (r:1 w:1)
+	// Storage: Tipping SubscriptionTasks (r:1 w:1)
+	fn pause_subscription() -> Weight {
+		Weight::from_ref_time(42_600_000_u64)
 			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(2_u64))
 	}
+	// Storage: Tipping SubscriptionById (r:1 w:1)
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping SubscriptionTasks (r:1 w:1)
+	fn resume_subscription() -> Weight {
+		Weight::from_ref_time(47_100_000_u64)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	// Storage: Tipping SubscriptionById (r:1 w:1)
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping SubscriptionTasks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Tipping TipperTotals (r:1 w:1)
+	// Storage: Tipping TipStatsByReference (r:1 w:1)
+	// Storage: Tipping TopTippers (r:1 w:1)
+	fn on_initialize_subscription() -> Weight {
+		Weight::from_ref_time(109_200_000_u64)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping RewardDistributionByInstance (r:1 w:1)
+	// Storage: Tipping RewardPayoutTasks (r:2 w:2)
+	/// The range of component `b` is `[1, 10]`.
+	fn set_reward_distribution(b: u32, ) -> Weight {
+		Weight::from_ref_time(41_200_000_u64)
+			// Standard Error: 4_000
+			.saturating_add(Weight::from_ref_time(1_100_000_u64).saturating_mul(b as u64))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping RewardDistributionByInstance (r:1 w:1)
+	// Storage: Tipping RewardPayoutTasks (r:1 w:1)
+	// Storage: Tipping UnpaidRewards (r:2 w:1)
+	// Storage: Tipping RewardBalance (r:1 w:1)
+	/// The range of component `u` is `[1, 100]`.
+	fn remove_reward_distribution(u: u32, ) -> Weight {
+		Weight::from_ref_time(41_300_000_u64)
+			// Standard Error: 4_000
+			.saturating_add(Weight::from_ref_time(9_800_000_u64).saturating_mul(u as u64))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(u as u64)))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(u as u64)))
+	}
+	// Storage: Tipping RewardDistributionByInstance (r:1 w:1)
+	// Storage: Tipping UnpaidRewards (r:2 w:1)
+	// Storage: Tipping RewardBalance (r:2 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Tipping RewardPayoutTasks (r:1 w:1)
+	/// The range of component `p` is `[1, 100]`.
+	fn on_initialize_reward_payout(p: u32, ) -> Weight {
+		Weight::from_ref_time(49_100_000_u64)
+			// Standard Error: 12_000
+			.saturating_add(Weight::from_ref_time(41_200_000_u64).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(p as u64)))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(p as u64)))
+	}
+	// Storage: Tipping MemoDeposit (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Tipping Memos (r:0 w:1)
+	fn remove_memo() -> Weight {
+		Weight::from_ref_time(38_400_000_u64)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	// Storage: Tipping AnonymousTips (r:1 w:1)
+	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Tipping TipExpiryAt (r:0 w:1)
+	fn reclaim_anonymous_tip() -> Weight {
+		Weight::from_ref_time(92_600_000_u64)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
 	// Storage: System Account (r:2 w:2)
+	// Storage: Balances Reserves (r:1 w:1)
 	// Storage: Tipping WithdrawalBalance (r:1 w:1)
 	// Storage: Timestamp Now (r:1 w:0)
 	// Storage: Tipping ReceiptIds (r:1 w:1)
 	// Storage: Tipping Receipts (r:0 w:1)
+	// Storage: Tipping ReceiptByContent (r:0 w:1)
+	// Storage: Tipping Memos (r:0 w:1)
+	// Storage: Tipping MemoDeposit (r:0 w:1)
 	fn pay_content() -> Weight {
-		Weight::from_ref_time(165_900_000_u64)
-			.saturating_add(RocksDbWeight::get().reads(5_u64))
-			.saturating_add(RocksDbWeight::get().writes(5_u64))
+		Weight::from_ref_time(178_400_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping EscrowTasks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping ReceiptNonce (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Tipping Receipts (r:0 w:1)
+	// Storage: Tipping Escrows (r:0 w:1)
+	// Storage: Tipping ReceiptByContent (r:0 w:1)
+	// Storage: Tipping Memos (r:0 w:1)
+	// Storage: Tipping MemoDeposit (r:0 w:1)
+	fn pay_content_escrow() -> Weight {
+		Weight::from_ref_time(131_800_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+	}
+	// Storage: Tipping Receipts (r:1 w:1)
+	// Storage: Tipping Escrows (r:1 w:1)
+	// Storage: System Account (r:3 w:3)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping WithdrawalBalance (r:1 w:1)
+	// Storage: Tipping RewardBalance (r:1 w:1)
+	// Storage: Tipping EscrowTasks (r:1 w:1)
+	fn confirm_escrow() -> Weight {
+		Weight::from_ref_time(139_600_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+	}
+	// Storage: Tipping Receipts (r:1 w:1)
+	// Storage: Tipping Escrows (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping EscrowTasks (r:1 w:1)
+	// Storage: Tipping ReceiptByContent (r:1 w:1)
+	fn refund_escrow() -> Weight {
+		Weight::from_ref_time(82_700_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	// Storage: Tipping Receipts (r:1 w:1)
+	// Storage: Tipping Escrows (r:1 w:1)
+	// Storage: System Account (r:3 w:3)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping WithdrawalBalance (r:1 w:1)
+	// Storage: Tipping RewardBalance (r:1 w:1)
+	fn on_initialize_escrow() -> Weight {
+		Weight::from_ref_time(127_500_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
 	}
 	// Storage: Tipping WithdrawalBalance (r:1 w:1)
 	// Storage: System Account (r:2 w:2)
-	fn withdraw_fee() -> Weight {
-		Weight::from_ref_time(165_800_000_u64)
+	/// The range of component `s` is `[1, 10]`.
+	fn withdraw_fee(s: u32, ) -> Weight {
+		Weight::from_ref_time(32_400_000_u64)
+			// Standard Error: 19_000
+			.saturating_add(Weight::from_ref_time(66_700_000_u64).saturating_mul(s as u64))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(s as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(s as u64)))
+	}
+	// Storage: Tipping AssetRegistry (r:1 w:0)
+	// Storage: Tipping FeeSpendProposalCount (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Tipping FeeSpendProposals (r:0 w:1)
+	fn propose_fee_spend() -> Weight {
+		Weight::from_ref_time(38_900_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	// Storage: Tipping FeeSpendProposals (r:1 w:1)
+	// Storage: Tipping WithdrawalBalance (r:1 w:1)
+	// Storage: Tipping AssetRegistry (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	fn approve_fee_spend() -> Weight {
+		Weight::from_ref_time(71_300_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	// Storage: Tipping FeeSpendProposals (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn reject_fee_spend() -> Weight {
+		Weight::from_ref_time(44_600_000_u64)
 			.saturating_add(RocksDbWeight::get().reads(3_u64))
 			.saturating_add(RocksDbWeight::get().writes(3_u64))
 	}
@@ -112,24 +576,289 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(3_u64))
 	}
 	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
 	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Tipping TipperTotals (r:1 w:1)
+	// Storage: Tipping TipStatsByReference (r:1 w:1)
+	// Storage: Tipping TopTippers (r:1 w:1)
+	// Storage: Tipping ReceiptNonce (r:1 w:1)
+	// Storage: Tipping AnonymousTips (r:1 w:1)
+	// Storage: Tipping Memos (r:0 w:1)
+	// Storage: Tipping MemoDeposit (r:0 w:1)
 	fn send_tip() -> Weight {
-		Weight::from_ref_time(122_100_000_u64)
+		Weight::from_ref_time(139_800_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+	}
+	// Storage: Tipping AnonymousTips (r:1 w:1)
+	// Storage: Tipping TipperTotals (r:1 w:1)
+	// Storage: Tipping TopTippers (r:1 w:1)
+	// Storage: Tipping TipStatsByReference (r:1 w:1)
+	fn reveal_tip() -> Weight {
+		Weight::from_ref_time(38_600_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Tipping TipperTotals (r:1 w:1)
+	// Storage: Tipping TipStatsByReference (r:1 w:1)
+	// Storage: Tipping TopTippers (r:1 w:1)
+	/// The range of component `s` is `[1, 50]`.
+	fn send_tips(s: u32, ) -> Weight {
+		Weight::from_ref_time(98_400_000_u64)
+			// Standard Error: 21_000
+			.saturating_add(Weight::from_ref_time(49_800_000_u64).saturating_mul(s as u64))
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((6_u64).saturating_mul(s as u64)))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(s as u64)))
 	}
+	// Storage: Server ServerById (r:1 w:0)
 	// Storage: Tipping TipsBalanceByReference (r:2 w:2)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping PendingSettlements (r:1 w:0)
 	fn claim_reference() -> Weight {
-		Weight::from_ref_time(165_100_000_u64)
-			.saturating_add(RocksDbWeight::get().reads(3_u64))
-			.saturating_add(RocksDbWeight::get().writes(3_u64))
+		Weight::from_ref_time(185_900_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping ClaimNonce (r:1 w:1)
+	// Storage: Server SigningKeyByServer (r:1 w:0)
+	// Storage: Tipping TipsBalanceByReference (r:2 w:2)
+	// Storage: Tipping AssetRegistry (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping PendingSettlements (r:1 w:0)
+	fn claim_reference_with_proof() -> Weight {
+		Weight::from_ref_time(186_600_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Tipping TipContributions (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping TipExpiryAt (r:0 w:1)
+	fn reclaim_tip() -> Weight {
+		Weight::from_ref_time(69_800_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	// Storage: Tipping TipExpiryAt (r:1 w:0)
+	// Storage: Tipping TipsBalanceByReference (r:1 w:0)
+	fn on_idle_tip_expiry() -> Weight {
+		Weight::from_ref_time(21_400_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+	}
+	// Storage: Tipping TipExpiryAt (r:0 w:1)
+	// Storage: Tipping TipContributions (r:1 w:1)
+	// Storage: Tipping AssetRegistry (r:1 w:0)
+	// Storage: Balances Reserves (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Tipping TipContributionCleanup (r:1 w:1)
+	// Storage: Tipping PendingSettlements (r:1 w:1)
+	// Storage: Tipping PendingClaimFees (r:1 w:1)
+	/// The range of component `c` is `[1, 100]`.
+	fn remove_tip_contributions(c: u32, ) -> Weight {
+		Weight::from_ref_time(27_300_000_u64)
+			// Standard Error: 9_000
+			.saturating_add(Weight::from_ref_time(38_900_000_u64).saturating_mul(c as u64))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(c as u64)))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+			.saturating_add(RocksDbWeight::get().writes((3_u64).saturating_mul(c as u64)))
 	}
 	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping PendingSettlements (r:1 w:0)
 	fn claim_tip() -> Weight {
-		Weight::from_ref_time(107_400_000_u64)
+		Weight::from_ref_time(121_200_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Tipping AssetRegistry (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: OctopusAppchain IsActivated (r:1 w:0)
+	// Storage: OctopusUpwardMessages Messages (r:1 w:1)
+	// Storage: Tipping PendingSettlements (r:1 w:0)
+	fn claim_tip_and_bridge() -> Weight {
+		Weight::from_ref_time(160_400_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	// Storage: Tipping FeeBounds (r:1 w:0)
+	// Storage: Tipping DefaultFeePolicy (r:0 w:1)
+	fn set_default_fee_policy() -> Weight {
+		Weight::from_ref_time(25_100_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: Tipping DefaultFeePolicy (r:1 w:0)
+	// Storage: Tipping FeeBounds (r:0 w:1)
+	fn set_fee_policy_bounds() -> Weight {
+		Weight::from_ref_time(24_700_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping FeeBounds (r:1 w:0)
+	// Storage: Tipping FeePolicyByServer (r:0 w:1)
+	fn set_server_fee_policy() -> Weight {
+		Weight::from_ref_time(34_600_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: Tipping TipsBalanceByReference (r:1 w:0)
+	// Storage: Tipping TipSplits (r:0 w:1)
+	/// The range of component `s` is `[1, 10]`.
+	fn set_tip_split(s: u32, ) -> Weight {
+		Weight::from_ref_time(30_700_000_u64)
+			// Standard Error: 3_000
+			.saturating_add(Weight::from_ref_time(1_200_000_u64).saturating_mul(s as u64))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: Tipping TipSplits (r:1 w:0)
+	// Storage: Tipping AssetRegistry (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	/// The range of component `s` is `[1, 10]`.
+	fn split_tip(s: u32, ) -> Weight {
+		Weight::from_ref_time(12_800_000_u64)
+			// Standard Error: 17_000
+			.saturating_add(Weight::from_ref_time(38_900_000_u64).saturating_mul(s as u64))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(s as u64)))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(s as u64)))
+	}
+	// Storage: Tipping AssetRegistry (r:1 w:1)
+	fn register_asset() -> Weight {
+		Weight::from_ref_time(24_300_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: Tipping AssetRegistry (r:1 w:1)
+	fn update_asset() -> Weight {
+		Weight::from_ref_time(23_100_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping SubscriptionIndex (r:1 w:1)
+	// Storage: Tipping SubscriptionTasks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Tipping SubscriptionById (r:0 w:1)
+	// Storage: Tipping TipperTotals (r:1 w:1)
+	// Storage: Tipping TipStatsByReference (r:1 w:1)
+	// Storage: Tipping TopTippers (r:1 w:1)
+	fn subscribe() -> Weight {
+		Weight::from_ref_time(141_900_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+	}
+	// Storage: Tipping SubscriptionById (r:1 w:1)
+	// Storage: Tipping SubscriptionTasks (r:1 w:1)
+	fn cancel_subscription() -> Weight {
+		Weight::from_ref_time(41_200_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	// Storage: Tipping SubscriptionById (r:1 w:1)
+	// Storage: Tipping SubscriptionTasks (r:1 w:1)
+	fn pause_subscription() -> Weight {
+		Weight::from_ref_time(42_600_000_u64)
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(2_u64))
 	}
+	// Storage: Tipping SubscriptionById (r:1 w:1)
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping SubscriptionTasks (r:1 w:1)
+	fn resume_subscription() -> Weight {
+		Weight::from_ref_time(47_100_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	// Storage: Tipping SubscriptionById (r:1 w:1)
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping SubscriptionTasks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Tipping TipperTotals (r:1 w:1)
+	// Storage: Tipping TipStatsByReference (r:1 w:1)
+	// Storage: Tipping TopTippers (r:1 w:1)
+	fn on_initialize_subscription() -> Weight {
+		Weight::from_ref_time(109_200_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping RewardDistributionByInstance (r:1 w:1)
+	// Storage: Tipping RewardPayoutTasks (r:2 w:2)
+	/// The range of component `b` is `[1, 10]`.
+	fn set_reward_distribution(b: u32, ) -> Weight {
+		Weight::from_ref_time(41_200_000_u64)
+			// Standard Error: 4_000
+			.saturating_add(Weight::from_ref_time(1_100_000_u64).saturating_mul(b as u64))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	// Storage: Server ServerById (r:1 w:0)
+	// Storage: Tipping RewardDistributionByInstance (r:1 w:1)
+	// Storage: Tipping RewardPayoutTasks (r:1 w:1)
+	// Storage: Tipping UnpaidRewards (r:2 w:1)
+	// Storage: Tipping RewardBalance (r:1 w:1)
+	/// The range of component `u` is `[1, 100]`.
+	fn remove_reward_distribution(u: u32, ) -> Weight {
+		Weight::from_ref_time(41_300_000_u64)
+			// Standard Error: 4_000
+			.saturating_add(Weight::from_ref_time(9_800_000_u64).saturating_mul(u as u64))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(u as u64)))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(u as u64)))
+	}
+	// Storage: Tipping RewardDistributionByInstance (r:1 w:1)
+	// Storage: Tipping UnpaidRewards (r:2 w:1)
+	// Storage: Tipping RewardBalance (r:2 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Tipping RewardPayoutTasks (r:1 w:1)
+	/// The range of component `p` is `[1, 100]`.
+	fn on_initialize_reward_payout(p: u32, ) -> Weight {
+		Weight::from_ref_time(49_100_000_u64)
+			// Standard Error: 12_000
+			.saturating_add(Weight::from_ref_time(41_200_000_u64).saturating_mul(p as u64))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(p as u64)))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(p as u64)))
+	}
+	// Storage: Tipping MemoDeposit (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Tipping Memos (r:0 w:1)
+	fn remove_memo() -> Weight {
+		Weight::from_ref_time(38_400_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	// Storage: Tipping AnonymousTips (r:1 w:1)
+	// Storage: Tipping TipsBalanceByReference (r:1 w:1)
+	// Storage: Balances Reserves (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Tipping TipExpiryAt (r:0 w:1)
+	fn reclaim_anonymous_tip() -> Weight {
+		Weight::from_ref_time(92_600_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
 }
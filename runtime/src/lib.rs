@@ -18,12 +18,13 @@ use sp_mmr_primitives as mmr;
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{
-		AccountIdLookup, BlakeTwo256, Block as BlockT, ConvertInto, Extrinsic, Hash as HashT,
-		IdentifyAccount, Keccak256, NumberFor, OpaqueKeys, SaturatedConversion, StaticLookup,
-		Verify,
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, ConvertInto, Extrinsic,
+		Hash as HashT, IdentifyAccount, Keccak256, NumberFor, OpaqueKeys, SaturatedConversion,
+		StaticLookup, Verify,
 	},
 	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, FixedPointNumber, MultiAddress, MultiSignature, Perbill, Perquintill,
+	ApplyExtrinsicResult, FixedPointNumber, MultiAddress, MultiSignature, Perbill, Permill,
+	Perquintill,
 };
 use sp_staking::SessionIndex;
 use sp_std::{cmp::Ordering, prelude::*};
@@ -37,7 +38,7 @@ use frame_support::{
 	parameter_types,
 	traits::{
 		AsEnsureOriginWithArg, ConstU128, ConstU16, ConstU32, ConstU64, ConstU8, EitherOfDiverse,
-		Everything, KeyOwnerProofSystem, PrivilegeCmp,
+		Everything, KeyOwnerProofSystem, NeverEnsureOrigin, PrivilegeCmp,
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -67,6 +68,7 @@ use pallet_grandpa::{
 use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
 use pallet_octopus_appchain::sr25519::AuthorityId as OctopusId;
 use pallet_octopus_lpos::{EraIndex, ExposureOf, FilterHistoricalOffences};
+use pallet_server::crypto::sr25519::HeartbeatKey as ServerHeartbeatKey;
 use pallet_session::{historical as pallet_session_historical, FindAccountFromAuthorIndex};
 use pallet_session_historical::NoteHistoricalRoot;
 pub use pallet_timestamp::Call as TimestampCall;
@@ -204,10 +206,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 2036,
+	spec_version: 2037,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 3,
+	transaction_version: 4,
 	state_version: 1,
 };
 
@@ -495,7 +497,7 @@ impl pallet_assets::Config<pallet_assets::Instance1> for Runtime {
 	type Currency = Balances;
 	type Extra = ();
 	type ForceOrigin = EnsureRoot<AccountId>;
-	type Freezer = ();
+	type Freezer = Tipping;
 	type MetadataDepositBase = MetadataDepositBase;
 	type MetadataDepositPerByte = MetadataDepositPerByte;
 	type RuntimeEvent = RuntimeEvent;
@@ -821,6 +823,38 @@ impl pallet_democracy::Config for Runtime {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: Balance = 100 * currency::DOLLARS;
+	pub const TreasuryBurn: Permill = Permill::zero();
+}
+
+impl pallet_treasury::Config for Runtime {
+	type ApproveOrigin = EitherOfDiverse<
+		EnsureRoot<AccountId>,
+		EnsureProportionAtLeast<AccountId, CouncilCollective, 3, 5>,
+	>;
+	type Burn = TreasuryBurn;
+	type BurnDestination = ();
+	type Currency = Balances;
+	type MaxApprovals = ConstU32<100>;
+	type OnSlash = Treasury;
+	type PalletId = TreasuryPalletId;
+	type ProposalBond = ProposalBond;
+	type ProposalBondMaximum = ();
+	type ProposalBondMinimum = ProposalBondMinimum;
+	type RejectOrigin = EitherOfDiverse<
+		EnsureRoot<AccountId>,
+		EnsureProportionAtLeast<AccountId, CouncilCollective, 1, 2>,
+	>;
+	type RuntimeEvent = RuntimeEvent;
+	type SpendFunds = ();
+	type SpendOrigin = NeverEnsureOrigin<Balance>;
+	type SpendPeriod = ConstU32<{ 6 * DAYS }>;
+	type WeightInfo = ();
+}
+
 impl pallet_sudo::Config for Runtime {
 	type RuntimeCall = RuntimeCall;
 	type RuntimeEvent = RuntimeEvent;
@@ -829,30 +863,126 @@ impl pallet_sudo::Config for Runtime {
 // Local pallets
 parameter_types! {
 	pub const MinimumStakeAmount: Balance = 50_000 * currency::DOLLARS;
+	pub const MinimumDelegationAmount: Balance = 100 * currency::DOLLARS;
+	pub const ServerMetadataDepositBase: Balance = currency::DOLLARS;
+	pub const ServerMetadataDepositPerByte: Balance = currency::CENTS;
+	pub const ServerReportDeposit: Balance = 100 * currency::DOLLARS;
+	pub const ServerReporterRewardShare: Permill = Permill::from_percent(10);
+	pub const ServerMaxSlashFraction: Permill = Permill::from_percent(50);
+	pub const ServerUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
 }
 
 impl pallet_server::Config for Runtime {
 	type Currency = Balances;
+	type EraLength = ConstU32<{ DAYS }>;
+	type HeartbeatInterval = ConstU32<{ 10 * MINUTES }>;
+	type HeartbeatKey = ServerHeartbeatKey;
+	type InactivityPeriod = ConstU32<{ HOURS }>;
+	type MaxApiUrlLength = ConstU32<256>;
+	type MaxDelegatorsPerServer = ConstU32<100>;
+	type MaxDescriptionLength = ConstU32<1024>;
+	type MaxMetadataFieldLength = ConstU32<64>;
+	type MaxPayoutHistory = ConstU32<84>;
 	type MaxScheduledPerBlock = ConstU32<5>;
+	type MaxSlashFraction = ServerMaxSlashFraction;
+	type MetadataDepositBase = ServerMetadataDepositBase;
+	type MetadataDepositPerByte = ServerMetadataDepositPerByte;
+	type MinimumDelegationAmount = MinimumDelegationAmount;
 	type MinimumStakeAmount = MinimumStakeAmount;
+	type ReportDeposit = ServerReportDeposit;
+	type ReporterRewardShare = ServerReporterRewardShare;
+	type RewardFunding = Tipping;
 	type RuntimeEvent = RuntimeEvent;
 	type ScheduledBlockTime = ConstU32<{ DAYS }>;
+	type SlashDestination = TippingFeeDestination;
+	type SlashOrigin = EitherOfDiverse<
+		EnsureRoot<AccountId>,
+		EnsureProportionAtLeast<AccountId, TechnicalCollective, 2, 3>,
+	>;
+	type UnsignedPriority = ServerUnsignedPriority;
 	type WeightInfo = ();
 }
 
 impl pallet_access_token::Config for Runtime {
+	type MaxAccessTokensPerOwner = ConstU32<100>;
+	type MaxTimelineIdLength = ConstU32<64>;
+	type MaxTimelinesPerScope = ConstU32<32>;
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	// Admin fees and slashed stake go to the treasury, which the council spends
+	pub TippingFeeDestination: AccountId = TreasuryPalletId::get().into_account_truncating();
+	pub const TippingFeeSpendDeposit: Balance = 10 * currency::DOLLARS;
+	// A fifth of native admin fees rewards server stakers
+	pub const TippingRewardPotShare: Permill = Permill::from_percent(20);
+	// Memos live in state until their sender removes them
+	pub const TippingMemoDepositBase: Balance = currency::CENTS;
+	pub const TippingMemoDepositPerByte: Balance = currency::BYTE_FEE;
+}
+
+/// Sends claimed tips to NEAR, locking MYRIA and burning NEP-141 assets like the bridge calls do.
+pub struct TippingBridge;
+impl pallet_tipping::TokenBridge<AccountId, Balance> for TippingBridge {
+	fn bridge(
+		sender: &AccountId,
+		asset: pallet_tipping::AssetKind,
+		receiver_id: Vec<u8>,
+		amount: Balance,
+	) -> frame_support::dispatch::DispatchResult {
+		let origin = RuntimeOrigin::signed(sender.clone());
+
+		match asset {
+			pallet_tipping::AssetKind::Native => OctopusBridge::lock(origin, receiver_id, amount),
+			pallet_tipping::AssetKind::Asset(asset_id) =>
+				OctopusBridge::burn_nep141(origin, asset_id, receiver_id, amount),
+		}
+		.map(|_| ())
+		.map_err(|e| e.error)
+	}
+}
+
 impl pallet_tipping::Config for Runtime {
 	type Assets = OctopusAssets;
 	type Currency = Balances;
 	type RuntimeCall = RuntimeCall;
 	type RuntimeEvent = RuntimeEvent;
+	type Server = Server;
+	type Bridge = TippingBridge;
 	type TimeProvider = Timestamp;
-	type AdminFee = ConstU8<10>;
-	type TransactionFee = ConstU8<5>;
+	type MaxTipsPerBatch = ConstU32<50>;
+	type MaxSubscriptionPeriod = ConstU32<{ 365 * DAYS }>;
+	type MaxSubscriptionsPerBlock = ConstU32<50>;
+	type EscrowPeriod = ConstU32<{ 7 * DAYS }>;
+	type MaxEscrowsPerBlock = ConstU32<50>;
+	type AssetRegistryOrigin = EitherOfDiverse<
+		EnsureRoot<AccountId>,
+		EnsureProportionAtLeast<AccountId, CouncilCollective, 1, 2>,
+	>;
+	type WithdrawOrigin = EitherOfDiverse<
+		EnsureRoot<AccountId>,
+		EnsureProportionAtLeast<AccountId, CouncilCollective, 1, 2>,
+	>;
+	type FeeDestination = TippingFeeDestination;
+	type MaxFeeWithdrawals = ConstU32<10>;
+	type FeeSpendDeposit = TippingFeeSpendDeposit;
+	type RewardPotShare = TippingRewardPotShare;
+	type TipExpiry = ConstU32<{ 180 * DAYS }>;
+	type MaxTipSplits = ConstU32<10>;
+	type MaxStringLength = ConstU32<256>;
+	type MaxTipExpiriesPerBlock = ConstU32<100>;
+	type MaxTipContributionRemovals = ConstU32<100>;
+	type MaxMemoLength = ConstU32<256>;
+	type MemoDepositBase = TippingMemoDepositBase;
+	type MemoDepositPerByte = TippingMemoDepositPerByte;
+	type MaxTopTippers = ConstU32<10>;
+	type MaxRewardBeneficiaries = ConstU32<10>;
+	type MaxReferenceIds = ConstU32<20>;
+	type MaxRewardPayoutsPerBlock = ConstU32<50>;
+	type MaxRewardTransfers = ConstU32<100>;
+	type Signature = Signature;
+	type Signer = <Signature as Verify>::Signer;
 	type WeightInfo = ();
 }
 
@@ -903,6 +1033,7 @@ construct_runtime!(
 		Council: pallet_collective::<Instance1>,
 		TechnicalCommittee: pallet_collective::<Instance2>,
 		Democracy: pallet_democracy,
+		Treasury: pallet_treasury,
 		Sudo: pallet_sudo,
 
 		// Local pallets
@@ -1138,6 +1269,82 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_tipping::runtime_api::TippingApi<Block, AccountId, Balance> for Runtime {
+		fn get_tips_balances(
+			server_id: AccountId,
+			reference_type: Vec<u8>,
+			reference_id: Vec<u8>,
+		) -> Vec<pallet_tipping::TipsBalance<Balance, AccountId, AccountId>> {
+			let reference_type = pallet_tipping::ReferenceTypeOf::<Runtime>::try_from(reference_type);
+			let reference_id = pallet_tipping::ReferenceIdOf::<Runtime>::try_from(reference_id);
+
+			// Nothing is stored under strings longer than the bound
+			match (reference_type, reference_id) {
+				(Ok(reference_type), Ok(reference_id)) =>
+					Tipping::get_tips_balances(&server_id, &reference_type, &reference_id)
+						.into_iter()
+						.map(Into::into)
+						.collect(),
+				_ => Vec::new(),
+			}
+		}
+
+		fn get_claimable_tips(
+			server_id: AccountId,
+			account_id: AccountId,
+		) -> Vec<(Vec<u8>, Balance)> {
+			Tipping::get_claimable_tips(&server_id, &account_id)
+				.into_iter()
+				.map(|(ft_identifier, amount)| (ft_identifier.into_inner(), amount))
+				.collect()
+		}
+
+		fn has_paid(buyer: AccountId, server_id: AccountId, reference_id: Vec<u8>) -> bool {
+			pallet_tipping::ReferenceIdOf::<Runtime>::try_from(reference_id)
+				.map_or(false, |reference_id| Tipping::has_paid(&buyer, &server_id, &reference_id))
+		}
+
+		fn get_tip_stats(
+			server_id: AccountId,
+			reference_type: Vec<u8>,
+			reference_id: Vec<u8>,
+		) -> Vec<(Vec<u8>, pallet_tipping::TipStats<Balance>)> {
+			let reference_type = pallet_tipping::ReferenceTypeOf::<Runtime>::try_from(reference_type);
+			let reference_id = pallet_tipping::ReferenceIdOf::<Runtime>::try_from(reference_id);
+
+			match (reference_type, reference_id) {
+				(Ok(reference_type), Ok(reference_id)) =>
+					Tipping::get_tip_stats(&server_id, &reference_type, &reference_id)
+						.into_iter()
+						.map(|(ft_identifier, tip_stats)| (ft_identifier.into_inner(), tip_stats))
+						.collect(),
+				_ => Vec::new(),
+			}
+		}
+
+		fn get_top_tippers(
+			server_id: AccountId,
+			reference_type: Vec<u8>,
+			reference_id: Vec<u8>,
+			ft_identifier: Vec<u8>,
+		) -> Vec<(AccountId, Balance)> {
+			let reference_type = pallet_tipping::ReferenceTypeOf::<Runtime>::try_from(reference_type);
+			let reference_id = pallet_tipping::ReferenceIdOf::<Runtime>::try_from(reference_id);
+			let ft_identifier = pallet_tipping::FtIdentifierOf::<Runtime>::try_from(ft_identifier);
+
+			match (reference_type, reference_id, ft_identifier) {
+				(Ok(reference_type), Ok(reference_id), Ok(ft_identifier)) =>
+					Tipping::get_top_tippers(
+						&server_id,
+						&reference_type,
+						&reference_id,
+						&ft_identifier,
+					),
+				_ => Vec::new(),
+			}
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (